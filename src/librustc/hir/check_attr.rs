@@ -11,6 +11,7 @@
 use session::Session;
 
 use syntax::ast;
+use syntax::attr;
 use syntax::visit;
 use syntax::visit::Visitor;
 
@@ -20,6 +21,8 @@ enum Target {
     Struct,
     Union,
     Enum,
+    Static,
+    Impl,
     Other,
 }
 
@@ -30,6 +33,8 @@ impl Target {
             ast::ItemKind::Struct(..) => Target::Struct,
             ast::ItemKind::Union(..) => Target::Union,
             ast::ItemKind::Enum(..) => Target::Enum,
+            ast::ItemKind::Static(..) => Target::Static,
+            ast::ItemKind::Impl(..) => Target::Impl,
             _ => Target::Other,
         }
     }
@@ -119,11 +124,35 @@ impl<'a> CheckAttrVisitor<'a> {
         }
     }
 
+    fn check_used(&self, attr: &ast::Attribute, target: Target) {
+        if target != Target::Static {
+            self.sess.span_err(attr.span, "attribute must be applied to a `static` variable");
+        }
+    }
+
+    // `recursion_limit` on a function or an impl overrides the limit of the
+    // crate for instantiating them, see `collector::recursion_limit_for`.
+    // Anywhere else it is left unused, and the lint points out that it
+    // belongs on the crate.
+    fn check_recursion_limit(&self, attr: &ast::Attribute, target: Target) {
+        if target != Target::Fn && target != Target::Impl {
+            return;
+        }
+
+        attr::mark_used(attr);
+        if attr.value_str().and_then(|s| s.as_str().parse::<usize>().ok()).is_none() {
+            span_err!(self.sess, attr.span, E0296,
+                      "malformed recursion limit attribute, expected #[recursion_limit=\"N\"]");
+        }
+    }
+
     fn check_attribute(&self, attr: &ast::Attribute, target: Target) {
         if let Some(name) = attr.name() {
             match &*name.as_str() {
                 "inline" => self.check_inline(attr, target),
                 "repr" => self.check_repr(attr, target),
+                "used" => self.check_used(attr, target),
+                "recursion_limit" => self.check_recursion_limit(attr, target),
                 _ => (),
             }
         }
@@ -138,6 +167,21 @@ impl<'a> Visitor<'a> for CheckAttrVisitor<'a> {
         }
         visit::walk_item(self, item);
     }
+
+    fn visit_impl_item(&mut self, impl_item: &'a ast::ImplItem) {
+        let target = match impl_item.node {
+            ast::ImplItemKind::Method(..) => Target::Fn,
+            _ => Target::Other,
+        };
+        for attr in &impl_item.attrs {
+            if let Some(name) = attr.name() {
+                if &*name.as_str() == "recursion_limit" {
+                    self.check_recursion_limit(attr, target);
+                }
+            }
+        }
+        visit::walk_impl_item(self, impl_item);
+    }
 }
 
 pub fn check_crate(sess: &Session, krate: &ast::Crate) {
@@ -242,6 +242,18 @@ declare_lint! {
     "detects use of deprecated items"
 }
 
+declare_lint! {
+    pub MONOMORPHIZATION_BLOAT,
+    Allow,
+    "detects generic functions that are instantiated very many times"
+}
+
+declare_lint! {
+    pub UNCALLED_VTABLE_METHODS,
+    Allow,
+    "detects trait object coercions that instantiate methods which are never called"
+}
+
 /// Does nothing as a lint pass, but registers some `Lint`s
 /// which are used by other parts of the compiler.
 #[derive(Copy, Clone)]
@@ -286,7 +298,9 @@ impl LintPass for HardwiredLints {
             LEGACY_IMPORTS,
             LEGACY_CONSTRUCTOR_VISIBILITY,
             MISSING_FRAGMENT_SPECIFIER,
-            DEPRECATED
+            DEPRECATED,
+            MONOMORPHIZATION_BLOAT,
+            UNCALLED_VTABLE_METHODS
         )
     }
 }
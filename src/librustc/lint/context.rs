@@ -46,6 +46,7 @@ use syntax::symbol::Symbol;
 use syntax_pos::{DUMMY_SP, MultiSpan, Span};
 use errors::{self, Diagnostic, DiagnosticBuilder};
 use hir;
+use hir::map as hir_map;
 use hir::def_id::LOCAL_CRATE;
 use hir::intravisit as hir_visit;
 use syntax::visit as ast_visit;
@@ -430,6 +431,94 @@ pub fn gather_attr(attr: &ast::Attribute) -> Vec<Result<(ast::Name, Level, Span)
     out
 }
 
+/// Returns the level of `lint` for the crate as a whole, as set on the command
+/// line or by the attributes of the crate root.
+///
+/// This is meant for checks in trans that run after the main lint pass is
+/// finished. Attributes on individual items are not taken into account, see
+/// `node_level_source` for that.
+pub fn crate_level_source(sess: &Session,
+                          krate: &hir::Crate,
+                          lint: &'static Lint)
+                          -> LevelSource {
+    let lints = sess.lint_store.borrow();
+    let level_source = lints.get_level_source(LintId::of(lint));
+    apply_level_attrs(&lints, &krate.attrs, lint, level_source)
+}
+
+/// Returns the level of `lint` at the node `id`, taking the attributes of the
+/// crate root and of all items enclosing the node into account, the innermost
+/// one winning.
+///
+/// Like `crate_level_source`, this is meant for checks in trans that run after
+/// the main lint pass is finished.
+pub fn node_level_source(sess: &Session,
+                         hir_map: &hir_map::Map,
+                         id: ast::NodeId,
+                         lint: &'static Lint)
+                         -> LevelSource {
+    let mut level_source = crate_level_source(sess, hir_map.krate(), lint);
+
+    let mut ids = vec![id];
+    let mut id = id;
+    loop {
+        let parent = hir_map.get_parent(id);
+        if parent == id || parent == ast::CRATE_NODE_ID {
+            break
+        }
+        ids.push(parent);
+        id = parent;
+    }
+
+    let lints = sess.lint_store.borrow();
+    for &id in ids.iter().rev() {
+        level_source = apply_level_attrs(&lints, hir_map.attrs(id), lint, level_source);
+    }
+    level_source
+}
+
+/// Returns whether `lint` is allowed everywhere in the crate, i.e. at the
+/// crate root and not raised again by the attributes of any item in it. Checks
+/// in trans can skip gathering the data for a lint that can never fire.
+pub fn allowed_in_whole_crate(sess: &Session,
+                              krate: &hir::Crate,
+                              lint: &'static Lint)
+                              -> bool {
+    let level_source = crate_level_source(sess, krate, lint);
+    if level_source.0 != Allow {
+        return false
+    }
+
+    let lints = sess.lint_store.borrow();
+    krate.items.values().map(|item| &item.attrs)
+         .chain(krate.trait_items.values().map(|item| &item.attrs))
+         .chain(krate.impl_items.values().map(|item| &item.attrs))
+         .all(|attrs| apply_level_attrs(&lints, attrs, lint, level_source).0 == Allow)
+}
+
+// Applies the lint attributes among `attrs` that set the level of `lint` to
+// `level_source`, the level of the enclosing scope.
+fn apply_level_attrs(lints: &LintStore,
+                     attrs: &[ast::Attribute],
+                     lint: &'static Lint,
+                     mut level_source: LevelSource)
+                     -> LevelSource {
+    for result in gather_attrs(attrs) {
+        if let Ok((name, level, span)) = result {
+            if level_source.0 == Forbid || name != &lint.name_lower()[..] {
+                continue
+            }
+            let level = match lints.lint_cap {
+                Some(cap) => cmp::min(level, cap),
+                None => level,
+            };
+            level_source = (level, Node(name, span));
+        }
+    }
+
+    level_source
+}
+
 /// Emit a lint as a warning or an error (or not at all)
 /// according to `level`.
 ///
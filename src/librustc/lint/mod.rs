@@ -42,7 +42,9 @@ use syntax::symbol::Symbol;
 
 pub use lint::context::{LateContext, EarlyContext, LintContext, LintStore,
                         raw_emit_lint, check_crate, check_ast_crate, gather_attrs,
-                        raw_struct_lint, FutureIncompatibleInfo, EarlyLint, IntoEarlyLint};
+                        raw_struct_lint, crate_level_source, node_level_source,
+                        allowed_in_whole_crate,
+                        FutureIncompatibleInfo, EarlyLint, IntoEarlyLint};
 
 pub use lint::table::LintTable;
 
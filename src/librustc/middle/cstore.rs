@@ -29,6 +29,7 @@ use hir::map::definitions::{Definitions, DefKey, DisambiguatedDefPathData};
 use hir::svh::Svh;
 use middle::lang_items;
 use ty::{self, TyCtxt};
+use ty::subst::Substs;
 use session::Session;
 use session::search_paths::PathKind;
 use util::nodemap::{NodeSet, DefIdMap};
@@ -221,6 +222,12 @@ pub trait CrateStore {
     fn derive_registrar_fn(&self, cnum: CrateNum) -> Option<DefId>;
     fn native_libraries(&self, cnum: CrateNum) -> Vec<NativeLibrary>;
     fn exported_symbols(&self, cnum: CrateNum) -> Vec<DefId>;
+    /// The generic instances the crate translated and exports for other
+    /// crates to link to, if it was built with `-Z share-generics`.
+    fn exported_monomorphizations<'a, 'tcx>(&self,
+                                            tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                            cnum: CrateNum)
+                                            -> Vec<(DefId, &'tcx Substs<'tcx>)>;
     fn is_no_builtins(&self, cnum: CrateNum) -> bool;
 
     // resolve
@@ -257,7 +264,9 @@ pub trait CrateStore {
     fn encode_metadata<'a, 'tcx>(&self,
                                  tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                  link_meta: &LinkMeta,
-                                 reachable: &NodeSet) -> Vec<u8>;
+                                 reachable: &NodeSet,
+                                 exported_monomorphizations: &[(DefId, &'tcx Substs<'tcx>)])
+                                 -> Vec<u8>;
     fn metadata_encoding_version(&self) -> &[u8];
 }
 
@@ -363,6 +372,12 @@ impl CrateStore for DummyCrateStore {
     fn native_libraries(&self, cnum: CrateNum) -> Vec<NativeLibrary>
         { bug!("native_libraries") }
     fn exported_symbols(&self, cnum: CrateNum) -> Vec<DefId> { bug!("exported_symbols") }
+    fn exported_monomorphizations<'a, 'tcx>(&self,
+                                            tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                            cnum: CrateNum)
+                                            -> Vec<(DefId, &'tcx Substs<'tcx>)> {
+        bug!("exported_monomorphizations")
+    }
     fn is_no_builtins(&self, cnum: CrateNum) -> bool { bug!("is_no_builtins") }
 
     // resolve
@@ -413,7 +428,9 @@ impl CrateStore for DummyCrateStore {
     fn encode_metadata<'a, 'tcx>(&self,
                                  tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                  link_meta: &LinkMeta,
-                                 reachable: &NodeSet) -> Vec<u8> { vec![] }
+                                 reachable: &NodeSet,
+                                 exported_monomorphizations: &[(DefId, &'tcx Substs<'tcx>)])
+                                 -> Vec<u8> { vec![] }
     fn metadata_encoding_version(&self) -> &[u8] { bug!("metadata_encoding_version") }
 }
 
@@ -223,17 +223,26 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
         if !self.any_library {
             // If we are building an executable, only explicitly extern
             // types need to be exported.
-            if let hir_map::NodeItem(item) = *node {
-                let reachable = if let hir::ItemFn(.., abi, _, _) = item.node {
-                    abi != Abi::Rust
-                } else {
-                    false
-                };
-                let is_extern = attr::contains_extern_indicator(&self.tcx.sess.diagnostic(),
-                                                                &item.attrs);
-                if reachable || is_extern {
-                    self.reachable_symbols.insert(search_item);
+            match *node {
+                hir_map::NodeItem(item) => {
+                    let reachable = if let hir::ItemFn(.., abi, _, _) = item.node {
+                        abi != Abi::Rust
+                    } else {
+                        false
+                    };
+                    let is_extern = attr::contains_extern_indicator(&self.tcx.sess.diagnostic(),
+                                                                    &item.attrs);
+                    if reachable || is_extern {
+                        self.reachable_symbols.insert(search_item);
+                    }
+                }
+                hir_map::NodeImplItem(impl_item) => {
+                    if attr::contains_extern_indicator(&self.tcx.sess.diagnostic(),
+                                                       &impl_item.attrs) {
+                        self.reachable_symbols.insert(search_item);
+                    }
                 }
+                _ => {}
             }
         } else {
             // If we are building a library, then reachable symbols will
@@ -530,6 +530,28 @@ impl Passes {
     }
 }
 
+/// Writes the value of an option that belongs to a group, see
+/// `DebuggingOptions::group_options()`, as it would be given on the command
+/// line, or as `(unset)`.
+pub trait OptionValue {
+    fn to_option_string(&self) -> String;
+}
+
+impl OptionValue for bool {
+    fn to_option_string(&self) -> String {
+        if *self { "yes".to_string() } else { "no".to_string() }
+    }
+}
+
+impl<T: fmt::Display> OptionValue for Option<T> {
+    fn to_option_string(&self) -> String {
+        match *self {
+            Some(ref value) => value.to_string(),
+            None => "(unset)".to_string(),
+        }
+    }
+}
+
 /// Declare a macro that will define all CodegenOptions/DebuggingOptions fields and parsers all
 /// at once. The goal of this macro is to define an interface that can be
 /// programmatically used by the option parser in order to initialize the struct
@@ -540,6 +562,10 @@ impl Passes {
 /// cgsetters module which is a bunch of generated code to parse an option into
 /// its respective field in the struct. There are a few hand-written parsers for
 /// parsing specific types of values in this module.
+///
+/// An option can be marked as belonging to a group, like `[COLLECTION]` for
+/// the translation item collection options, which lets the group be listed
+/// without naming each option again, see `group_options()`.
 macro_rules! options {
     ($struct_name:ident, $setter_name:ident, $defaultfn:ident,
      $buildfn:ident, $prefix:expr, $outputname:expr,
@@ -548,7 +574,8 @@ macro_rules! options {
         $init:expr,
         $parse:ident,
         [$dep_tracking_marker:ident $(($dep_warn_val:expr, $dep_warn_text:expr))*],
-        $desc:expr)
+        $desc:expr
+        $(, [$group:ident])*)
      ),* ,) =>
 (
     #[derive(Clone)]
@@ -617,6 +644,21 @@ macro_rules! options {
         }
     }
 
+    impl $struct_name {
+        /// Returns the name, value and description of every option marked
+        /// as belonging to `group`, in the order they are declared in.
+        #[allow(unused_mut, unused_variables)]
+        pub fn group_options(&self, group: &str) -> Vec<(&'static str, String, &'static str)> {
+            let mut options = Vec::new();
+            $($(
+                if stringify!($group) == group {
+                    options.push((stringify!($opt), self.$opt.to_option_string(), $desc));
+                }
+            )*)*
+            options
+        }
+    }
+
     pub type $setter_name = fn(&mut $struct_name, v: Option<&str>) -> bool;
     pub const $stat: &'static [(&'static str, $setter_name,
                                      Option<&'static str>, &'static str)] =
@@ -960,7 +1002,68 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
           "print layout information for each type encountered"),
     print_trans_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
-          "print the result of the translation item collection pass"),
+          "print the result of the translation item collection pass (lazy|eager), \
+           optionally only for the items matching `:pattern,...`",
+          [COLLECTION]),
+    print_mono_array_families: bool = (false, parse_bool, [UNTRACKED],
+          "print instances of the same function that only differ in array lengths",
+          [COLLECTION]),
+    monomorphization_bloat_threshold: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "the number of instances of a generic function above which the \
+           `monomorphization_bloat` lint fires (default: 100)",
+          [COLLECTION]),
+    verify_mono_determinism: bool = (false, parse_bool, [UNTRACKED],
+          "collect translation items a second time in a different order, without \
+           caching MIR walks and without deduplicating neighbours, and verify that the \
+           results are the same",
+          [COLLECTION]),
+    trans_item_limit: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+          "abort compilation if more than N translation items are collected",
+          [COLLECTION]),
+    print_address_only_items: bool = (false, parse_bool, [UNTRACKED],
+          "print the functions that are only used as function pointers or in vtables \
+           but never called directly",
+          [COLLECTION]),
+    print_cross_crate_mono_chains: bool = (false, parse_bool, [UNTRACKED],
+          "print the sequences of crates along which generic functions are instantiated",
+          [COLLECTION]),
+    print_default_impl_skips: bool = (false, parse_bool, [UNTRACKED],
+          "print why provided trait methods are not instantiated eagerly for an impl",
+          [COLLECTION]),
+    print_eager_default_impls: bool = (false, parse_bool, [UNTRACKED],
+          "print the instance each provided trait method instantiated eagerly for an \
+           impl resolved to",
+          [COLLECTION]),
+    no_mono_mir_cache: bool = (false, parse_bool, [UNTRACKED],
+          "walk the MIR of a function again for each of its instances during \
+           translation item collection (for testing)",
+          [COLLECTION]),
+    no_mono_vtable_cache: bool = (false, parse_bool, [UNTRACKED],
+          "find the vtable types and methods again for each unsizing coercion during \
+           translation item collection (for testing)",
+          [COLLECTION]),
+    trans_collection_stats: bool = (false, parse_bool, [UNTRACKED],
+          "print statistics about translation item collection, grouped by phase",
+          [COLLECTION]),
+    mono_fuel: Option<usize> = (None, parse_opt_uint, [TRACKED],
+          "only collect the first N translation items (for debugging; the output \
+           will be incomplete)",
+          [COLLECTION]),
+    export_list: Option<String> = (None, parse_opt_string, [TRACKED],
+          "check that every symbol in the given linker version script corresponds to \
+           a translated item, and translate the listed items that are not used otherwise",
+          [COLLECTION]),
+    compile_test_filter: Option<String> = (None, parse_opt_string, [TRACKED],
+          "with --test, only build the tests whose path contains the given string into \
+           the test harness, and do not translate the others",
+          [COLLECTION]),
+    share_generics: bool = (false, parse_bool, [TRACKED],
+          "link to the generic instances that upstream crates built with this option \
+           translated, instead of instantiating them again, and record the generic \
+           instances translated in this crate for downstream crates",
+          [COLLECTION]),
+    mono_help: bool = (false, parse_bool, [UNTRACKED],
+          "print the translation item collection options and their values"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
           "set the MIR optimization level (0-3, default: 1)"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
@@ -975,6 +1078,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "print some statistics about MIR"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
           "encode MIR of all functions into the crate metadata"),
+    omit_promoted_mir: bool = (false, parse_bool, [TRACKED],
+          "leave the MIR of promoted constants out of the crate metadata (for testing)",
+          [COLLECTION]),
+    omit_mir: bool = (false, parse_bool, [TRACKED],
+          "leave the MIR of all items out of the crate metadata (for testing)",
+          [COLLECTION]),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
           "pass `-install_name @rpath/...` to the macOS linker"),
     sanitizer: Option<Sanitizer> = (None, parse_sanitizer, [TRACKED],
@@ -2493,6 +2602,30 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.print_trans_items = Some(String::from("abc"));
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.print_mono_array_families = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.monomorphization_bloat_threshold = Some(10);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.verify_mono_determinism = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.trans_item_limit = Some(10);
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.print_address_only_items = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.print_cross_crate_mono_chains = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.print_default_impl_skips = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.print_eager_default_impls = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.no_mono_mir_cache = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.no_mono_vtable_cache = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.trans_collection_stats = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.mono_help = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir = Some(String::from("abc"));
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_dir = Some(String::from("abc"));
@@ -2542,5 +2675,29 @@ mod tests {
         opts = reference.clone();
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.mono_fuel = Some(10);
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.omit_promoted_mir = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.omit_mir = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.export_list = Some(String::from("exports.map"));
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.compile_test_filter = Some(String::from("parser"));
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+        opts = reference.clone();
+        opts.debugging_opts.share_generics = true;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
     }
 }
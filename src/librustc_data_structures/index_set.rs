@@ -0,0 +1,124 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use fx::{FxHashMap, FxHasher};
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+/// A set that numbers its elements in insertion order. Unlike a map from
+/// element to index next to a vector of the elements, this stores each element
+/// only once: the hash table holds the index of the last element inserted with
+/// a given hash, and `next_with_hash` chains the indices of the others.
+pub struct IndexSet<T> {
+    elements: Vec<T>,
+    last_with_hash: FxHashMap<u64, u32>,
+    next_with_hash: Vec<u32>,
+}
+
+// Ends a chain in `IndexSet::next_with_hash`.
+const NO_INDEX: u32 = !0;
+
+impl<T: Hash + Eq> IndexSet<T> {
+    pub fn new() -> IndexSet<T> {
+        IndexSet {
+            elements: Vec::new(),
+            last_with_hash: FxHashMap(),
+            next_with_hash: Vec::new(),
+        }
+    }
+
+    fn hash_of(element: &T) -> u64 {
+        let mut hasher = FxHasher::default();
+        element.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn index_of(&self, element: &T) -> Option<u32> {
+        let mut index = match self.last_with_hash.get(&IndexSet::hash_of(element)) {
+            Some(&index) => index,
+            None => return None,
+        };
+        while index != NO_INDEX {
+            if self.elements[index as usize] == *element {
+                return Some(index);
+            }
+            index = self.next_with_hash[index as usize];
+        }
+        None
+    }
+
+    /// Returns the index of `element`, adding it to the set if needed.
+    pub fn insert(&mut self, element: T) -> u32 {
+        if let Some(index) = self.index_of(&element) {
+            return index;
+        }
+        let index = self.elements.len() as u32;
+        assert!(index != NO_INDEX);
+        let next = self.last_with_hash.insert(IndexSet::hash_of(&element), index);
+        self.elements.push(element);
+        self.next_with_hash.push(next.unwrap_or(NO_INDEX));
+        index
+    }
+
+    pub fn get(&self, index: u32) -> &T {
+        &self.elements[index as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Estimates the number of bytes taken up by the set. The hash table is
+    /// counted by its number of entries.
+    pub fn memory_usage_estimate(&self) -> usize {
+        self.elements.len() * mem::size_of::<T>() +
+        self.last_with_hash.len() * (mem::size_of::<u64>() + mem::size_of::<u32>()) +
+        self.next_with_hash.len() * mem::size_of::<u32>()
+    }
+}
+
+#[test]
+fn test_insert() {
+    let mut set = IndexSet::new();
+    for i in 0..1000 {
+        assert_eq!(set.insert(i), i);
+    }
+    for i in (0..1000).rev() {
+        assert_eq!(set.insert(i), i);
+        assert_eq!(*set.get(i), i);
+    }
+    assert_eq!(set.len(), 1000);
+    assert_eq!(set.index_of(&1000), None);
+}
+
+#[test]
+fn test_hash_collisions() {
+    // Hashes only the parity, so that elements share chains.
+    #[derive(PartialEq, Eq, Debug)]
+    struct Parity(u32);
+
+    impl Hash for Parity {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            (self.0 % 2).hash(state);
+        }
+    }
+
+    let mut set = IndexSet::new();
+    for i in 0..10 {
+        assert_eq!(set.insert(Parity(i)), i);
+    }
+    for i in 0..10 {
+        assert_eq!(set.index_of(&Parity(i)), Some(i));
+        assert_eq!(set.insert(Parity(i)), i);
+    }
+    assert_eq!(set.len(), 10);
+    assert_eq!(set.index_of(&Parity(10)), None);
+    assert_eq!(set.index_of(&Parity(11)), None);
+}
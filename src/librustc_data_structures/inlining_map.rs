@@ -0,0 +1,181 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use fx::FxHashSet;
+use index_set::IndexSet;
+use std::hash::Hash;
+use std::mem;
+
+/// Maps every translation item to all translation items it references in its
+/// body, the candidates for being inlined into it. Used by the translation
+/// item collector, and generic over the items so that it can be tested here.
+pub struct InliningMap<T> {
+    // Every source and target item. Everything else refers to items by their
+    // index in here, so each is only stored once.
+    items: IndexSet<T>,
+    // Maps an item index to the range of target items that are potentially
+    // inlined by LLVM into the item, if it has been recorded as a source. The
+    // two numbers in the tuple are the start (inclusive) and end index
+    // (exclusive) within the `targets` vec.
+    ranges: Vec<Option<(u32, u32)>>,
+    // Item indices. A target is candidate for many sources, so this does not
+    // repeat the items themselves.
+    targets: Vec<u32>,
+}
+
+impl<T: Hash + Eq + Copy> InliningMap<T> {
+
+    pub fn new() -> InliningMap<T> {
+        InliningMap {
+            items: IndexSet::new(),
+            ranges: Vec::new(),
+            targets: Vec::new(),
+        }
+    }
+
+    fn intern(&mut self, item: T) -> u32 {
+        let index = self.items.insert(item);
+        if index as usize == self.ranges.len() {
+            self.ranges.push(None);
+        }
+        index
+    }
+
+    /// Records the targets as given, duplicates included. Each source can
+    /// only be recorded once.
+    pub fn record_inlining_canditates<I>(&mut self, source: T, targets: I)
+        where I: Iterator<Item=T>
+    {
+        let source = self.intern(source);
+        assert!(self.ranges[source as usize].is_none());
+
+        let start_index = self.targets.len();
+        for target in targets {
+            let target = self.intern(target);
+            self.targets.push(target);
+        }
+        let end_index = self.targets.len();
+        assert!(end_index <= u32::max_value() as usize);
+        self.ranges[source as usize] = Some((start_index as u32, end_index as u32));
+    }
+
+    fn candidates(&self, source: u32) -> Option<&[u32]> {
+        self.ranges[source as usize].map(|(start_index, end_index)| {
+            &self.targets[start_index as usize .. end_index as usize]
+        })
+    }
+
+    /// Internally iterate over all items referenced by `source` which will be
+    /// made available for inlining.
+    pub fn with_inlining_candidates<F>(&self, source: T, mut f: F)
+        where F: FnMut(T) {
+        let candidates = self.items.index_of(&source).and_then(|source| {
+            self.candidates(source)
+        });
+        if let Some(candidates) = candidates {
+            for &candidate in candidates {
+                f(*self.items.get(candidate))
+            }
+        }
+    }
+
+    /// The items that have had their inlining candidates recorded.
+    pub fn sources<'a>(&'a self) -> Box<Iterator<Item=T> + 'a> {
+        Box::new((0..self.items.len() as u32)
+            .filter(move |&index| self.ranges[index as usize].is_some())
+            .map(move |index| *self.items.get(index)))
+    }
+
+    /// Estimates the number of bytes taken up by the map. Hash tables are
+    /// counted by their number of entries.
+    pub fn memory_usage_estimate(&self) -> usize {
+        self.items.memory_usage_estimate() +
+        self.ranges.len() * mem::size_of::<Option<(u32, u32)>>() +
+        self.targets.len() * mem::size_of::<u32>()
+    }
+
+    /// Checks that every inlining candidate has had its own candidates
+    /// recorded. Returns the first source and candidate found to violate this.
+    pub fn check_closed(&self) -> Result<(), (T, T)> {
+        for source in 0..self.items.len() as u32 {
+            if let Some(candidates) = self.candidates(source) {
+                for &candidate in candidates {
+                    if self.ranges[candidate as usize].is_none() {
+                        return Err((*self.items.get(source), *self.items.get(candidate)));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the given items from the inlining candidates of every source.
+    pub fn remove_candidates(&mut self, removed: &FxHashSet<T>) {
+        let mut targets = Vec::with_capacity(self.targets.len());
+        for range in &mut self.ranges {
+            if let Some((start_index, end_index)) = *range {
+                let new_start_index = targets.len();
+                for &target in &self.targets[start_index as usize .. end_index as usize] {
+                    if !removed.contains(self.items.get(target)) {
+                        targets.push(target);
+                    }
+                }
+                *range = Some((new_start_index as u32, targets.len() as u32));
+            }
+        }
+        self.targets = targets;
+    }
+}
+
+#[cfg(test)]
+fn candidates_of(map: &InliningMap<u32>, source: u32) -> Vec<u32> {
+    let mut candidates = vec![];
+    map.with_inlining_candidates(source, |candidate| candidates.push(candidate));
+    candidates
+}
+
+#[test]
+fn test_candidates() {
+    let mut map = InliningMap::new();
+    map.record_inlining_canditates(0, vec![1, 2, 1].into_iter());
+    map.record_inlining_canditates(2, vec![0].into_iter());
+    map.record_inlining_canditates(3, vec![].into_iter());
+    assert_eq!(candidates_of(&map, 0), vec![1, 2, 1]);
+    assert_eq!(candidates_of(&map, 1), vec![]);
+    assert_eq!(candidates_of(&map, 2), vec![0]);
+    assert_eq!(candidates_of(&map, 4), vec![]);
+    assert_eq!(map.sources().collect::<Vec<_>>(), vec![0, 2, 3]);
+}
+
+#[test]
+fn test_closed() {
+    let mut map = InliningMap::new();
+    map.record_inlining_canditates(0, vec![1, 2].into_iter());
+    map.record_inlining_canditates(1, vec![0].into_iter());
+    assert_eq!(map.check_closed(), Err((0, 2)));
+
+    map.record_inlining_canditates(2, vec![].into_iter());
+    assert_eq!(map.check_closed(), Ok(()));
+}
+
+#[test]
+fn test_remove_candidates() {
+    let mut map = InliningMap::new();
+    map.record_inlining_canditates(0, vec![1, 2, 3].into_iter());
+    map.record_inlining_canditates(1, vec![2, 0].into_iter());
+    map.record_inlining_canditates(3, vec![].into_iter());
+    assert_eq!(map.check_closed(), Err((0, 2)));
+
+    let removed = vec![2].into_iter().collect();
+    map.remove_candidates(&removed);
+    assert_eq!(candidates_of(&map, 0), vec![1, 3]);
+    assert_eq!(candidates_of(&map, 1), vec![0]);
+    assert_eq!(map.check_closed(), Ok(()));
+}
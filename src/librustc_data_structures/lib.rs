@@ -61,6 +61,8 @@ pub mod fmt_wrap;
 pub mod graph;
 pub mod ivar;
 pub mod indexed_set;
+pub mod index_set;
+pub mod inlining_map;
 pub mod indexed_vec;
 pub mod obligation_forest;
 pub mod snapshot_map;
@@ -716,6 +716,7 @@ pub fn phase_2_configure_and_expand<F>(sess: &Session,
         syntax::test::modify_for_testing(&sess.parse_sess,
                                          &mut resolver,
                                          sess.opts.test,
+                                         sess.opts.debugging_opts.compile_test_filter.clone(),
                                          krate,
                                          sess.diagnostic())
     });
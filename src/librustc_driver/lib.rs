@@ -378,7 +378,7 @@ fn handle_explain(code: &str,
 impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
     fn early_callback(&mut self,
                       matches: &getopts::Matches,
-                      _: &config::Options,
+                      sopts: &config::Options,
                       _: &ast::CrateConfig,
                       descriptions: &errors::registry::Registry,
                       output: ErrorOutputType)
@@ -388,6 +388,11 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             return Compilation::Stop;
         }
 
+        if sopts.debugging_opts.mono_help {
+            rustc_trans::print_collection_flags(&sopts.debugging_opts);
+            return Compilation::Stop;
+        }
+
         Compilation::Continue
     }
 
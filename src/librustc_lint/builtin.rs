@@ -35,7 +35,7 @@ use rustc::ty::subst::Substs;
 use rustc::ty::{self, Ty, TyCtxt};
 use rustc::traits::{self, Reveal};
 use rustc::hir::map as hir_map;
-use util::nodemap::NodeSet;
+use util::nodemap::{FxHashSet, NodeSet};
 use lint::{Level, LateContext, LintContext, LintArray};
 use lint::{LintPass, LateLintPass, EarlyLintPass, EarlyContext};
 
@@ -1163,3 +1163,127 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for UnionsWithDropFields {
         }
     }
 }
+
+/// Lint for statics whose type has a destructor, which never runs.
+pub struct StaticDestructors;
+
+declare_lint! {
+    pub STATIC_DESTRUCTORS,
+    Allow,
+    "detects statics whose type has a destructor, which never runs"
+}
+
+impl LintPass for StaticDestructors {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(STATIC_DESTRUCTORS)
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for StaticDestructors {
+    fn check_item(&mut self, cx: &LateContext<'a, 'tcx>, item: &hir::Item) {
+        if let hir::ItemStatic(..) = item.node {
+            let param_env = cx.tcx.empty_parameter_environment();
+            let ty = cx.tcx.item_type(cx.tcx.hir.local_def_id(item.id));
+            if !cx.tcx.type_needs_drop_given_env(ty, &param_env) {
+                return;
+            }
+
+            let msg = format!("the destructor of static `{}` of type `{}` will never run",
+                              item.name,
+                              ty);
+            let mut diag = cx.struct_span_lint(STATIC_DESTRUCTORS, item.span, &msg);
+            for note in destructor_origin(cx.tcx, &param_env, ty) {
+                diag.note(&note);
+            }
+            diag.note("statics are never dropped");
+            diag.emit();
+        }
+    }
+}
+
+// Describes the path from `ty` to the component of it that makes it need to
+// be dropped, outermost first. The path ends at the first type it has already
+// passed through, a recursive type leads back to itself through a `Box`.
+fn destructor_origin<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               param_env: &ty::ParameterEnvironment<'tcx>,
+                               ty: Ty<'tcx>)
+                               -> Vec<String> {
+    let needs_drop = |ty: Ty<'tcx>| tcx.type_needs_drop_given_env(ty, param_env);
+    let mut notes = vec![];
+    let mut visited = FxHashSet();
+    let mut ty = ty;
+    visited.insert(ty);
+    loop {
+        let component = match ty.sty {
+            ty::TyAdt(def, _) if def.is_box() => {
+                let boxed_ty = ty.boxed_ty();
+                Some((format!("`{}` owns a heap allocation of type `{}`", ty, boxed_ty),
+                      boxed_ty))
+            }
+            ty::TyAdt(def, _) if def.has_dtor(tcx) => {
+                notes.push(format!("`{}` implements `Drop`", ty));
+                None
+            }
+            ty::TyAdt(def, substs) => {
+                def.variants.iter().flat_map(|variant| {
+                    variant.fields.iter().map(move |field| (variant, field))
+                }).map(|(variant, field)| {
+                    (variant, field, tcx.normalize_associated_type(&field.ty(tcx, substs)))
+                }).find(|&(_, _, field_ty)| {
+                    needs_drop(field_ty)
+                }).map(|(variant, field, field_ty)| {
+                    let note = if def.is_enum() {
+                        format!("field `{}` of variant `{}` of `{}` has type `{}`",
+                                field.name,
+                                variant.name,
+                                ty,
+                                field_ty)
+                    } else {
+                        format!("field `{}` of `{}` has type `{}`", field.name, ty, field_ty)
+                    };
+                    (note, field_ty)
+                })
+            }
+            ty::TyTuple(tys, _) => {
+                tys.iter()
+                   .enumerate()
+                   .find(|&(_, &field_ty)| needs_drop(field_ty))
+                   .map(|(i, &field_ty)| {
+                       (format!("field `{}` of `{}` has type `{}`", i, ty, field_ty), field_ty)
+                   })
+            }
+            ty::TyArray(ety, _) => {
+                Some((format!("the elements of `{}` have type `{}`", ty, ety), ety))
+            }
+            ty::TyClosure(def_id, substs) => {
+                substs.upvar_tys(def_id, tcx)
+                      .find(|&upvar_ty| needs_drop(upvar_ty))
+                      .map(|upvar_ty| {
+                          (format!("the closure `{}` captures a value of type `{}`",
+                                   ty,
+                                   upvar_ty),
+                           upvar_ty)
+                      })
+            }
+            ty::TyDynamic(..) => {
+                notes.push(format!("the trait object `{}` may have a destructor", ty));
+                None
+            }
+            _ => {
+                notes.push(format!("`{}` needs to be dropped", ty));
+                None
+            }
+        };
+
+        match component {
+            Some((note, component_ty)) => {
+                notes.push(note);
+                if !visited.insert(component_ty) {
+                    return notes;
+                }
+                ty = component_ty;
+            }
+            None => return notes,
+        }
+    }
+}
@@ -21,6 +21,7 @@ use rustc::middle::lang_items;
 use rustc::session::Session;
 use rustc::ty::{self, TyCtxt};
 use rustc::ty::maps::Providers;
+use rustc::ty::subst::Substs;
 use rustc::hir::def_id::{CrateNum, DefId, DefIndex, CRATE_DEF_INDEX, LOCAL_CRATE};
 
 use rustc::dep_graph::DepNode;
@@ -335,6 +336,14 @@ impl CrateStore for cstore::CStore {
         self.get_crate_data(cnum).get_exported_symbols()
     }
 
+    fn exported_monomorphizations<'a, 'tcx>(&self,
+                                            tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                            cnum: CrateNum)
+                                            -> Vec<(DefId, &'tcx Substs<'tcx>)>
+    {
+        self.get_crate_data(cnum).get_exported_monomorphizations(tcx)
+    }
+
     fn is_no_builtins(&self, cnum: CrateNum) -> bool {
         self.get_crate_data(cnum).is_no_builtins()
     }
@@ -494,9 +503,11 @@ impl CrateStore for cstore::CStore {
     fn encode_metadata<'a, 'tcx>(&self,
                                  tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                  link_meta: &LinkMeta,
-                                 reachable: &NodeSet) -> Vec<u8>
+                                 reachable: &NodeSet,
+                                 exported_monomorphizations: &[(DefId, &'tcx Substs<'tcx>)])
+                                 -> Vec<u8>
     {
-        encoder::encode_metadata(tcx, self, link_meta, reachable)
+        encoder::encode_metadata(tcx, self, link_meta, reachable, exported_monomorphizations)
     }
 
     fn metadata_encoding_version(&self) -> &[u8]
@@ -999,6 +999,15 @@ impl<'a, 'tcx> CrateMetadata {
         self.exported_symbols.iter().map(|&index| self.local_def_id(index)).collect()
     }
 
+    pub fn get_exported_monomorphizations(&self,
+                                          tcx: TyCtxt<'a, 'tcx, 'tcx>)
+                                          -> Vec<(DefId, &'tcx Substs<'tcx>)> {
+        let EncodedMonomorphizations { position, len } = self.root.exported_monomorphizations;
+        LazySeq::<(DefId, &'tcx Substs<'tcx>)>::with_position_and_length(position, len)
+            .decode((self, tcx))
+            .collect()
+    }
+
     pub fn get_macro(&self, id: DefIndex) -> (ast::Name, MacroDef) {
         let entry = self.entry(id);
         match entry.kind {
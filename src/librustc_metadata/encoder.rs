@@ -20,9 +20,11 @@ use rustc::middle::lang_items;
 use rustc::mir;
 use rustc::traits::specialization_graph;
 use rustc::ty::{self, Ty, TyCtxt, ReprOptions};
+use rustc::ty::subst::Substs;
 
 use rustc::session::config::{self, CrateTypeProcMacro};
 use rustc::util::nodemap::{FxHashMap, NodeSet};
+use rustc_data_structures::indexed_vec::IndexVec;
 
 use rustc_serialize::{Encodable, Encoder, SpecializedEncoder, opaque};
 use std::hash::Hash;
@@ -50,6 +52,7 @@ pub struct EncodeContext<'a, 'tcx: 'a> {
     link_meta: &'a LinkMeta,
     cstore: &'a cstore::CStore,
     exported_symbols: &'a NodeSet,
+    exported_monomorphizations: &'a [(DefId, &'tcx Substs<'tcx>)],
 
     lazy_state: LazyState,
     type_shorthands: FxHashMap<Ty<'tcx>, usize>,
@@ -607,7 +610,18 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
     }
 
     fn encode_mir(&mut self, def_id: DefId) -> Option<Lazy<mir::Mir<'tcx>>> {
-        self.tcx.maps.mir.borrow().get(&def_id).map(|mir| self.lazy(&*mir.borrow()))
+        if self.tcx.sess.opts.debugging_opts.omit_mir {
+            return None;
+        }
+        self.tcx.maps.mir.borrow().get(&def_id).map(|mir| {
+            if self.tcx.sess.opts.debugging_opts.omit_promoted_mir {
+                let mut mir = mir.borrow().clone();
+                mir.promoted = IndexVec::new();
+                self.lazy(&mir)
+            } else {
+                self.lazy(&*mir.borrow())
+            }
+        })
     }
 
     // Encodes the inherent implementations of a structure, enumeration, or trait.
@@ -1282,6 +1296,17 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
         self.lazy_seq(exported_symbols.iter().map(|&id| tcx.hir.local_def_id(id).index))
     }
 
+    // Encodes the generic instances this crate translated and lets other
+    // crates link to, with `-Z share-generics`.
+    fn encode_exported_monomorphizations(&mut self) -> EncodedMonomorphizations {
+        let exported_monomorphizations = self.exported_monomorphizations;
+        let seq = self.lazy_seq_ref(exported_monomorphizations);
+        EncodedMonomorphizations {
+            position: seq.position,
+            len: seq.len,
+        }
+    }
+
     fn encode_dylib_dependency_formats(&mut self) -> LazySeq<Option<LinkagePreference>> {
         match self.tcx.sess.dependency_formats.borrow().get(&config::CrateTypeDylib) {
             Some(arr) => {
@@ -1335,6 +1360,11 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
         let exported_symbols = self.encode_exported_symbols();
         let exported_symbols_bytes = self.position() - i;
 
+        // Encode the generic instances other crates can link to.
+        i = self.position();
+        let exported_monomorphizations = self.encode_exported_monomorphizations();
+        let exported_monomorphizations_bytes = self.position() - i;
+
         // Encode and index the items.
         i = self.position();
         let items = self.encode_info_for_items();
@@ -1373,6 +1403,7 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             def_path_table: def_path_table,
             impls: impls,
             exported_symbols: exported_symbols,
+            exported_monomorphizations: exported_monomorphizations,
             index: index,
         });
 
@@ -1393,6 +1424,7 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
             println!("         codemap bytes: {}", codemap_bytes);
             println!("            impl bytes: {}", impl_bytes);
             println!("    exp. symbols bytes: {}", exported_symbols_bytes);
+            println!("   exp. generics bytes: {}", exported_monomorphizations_bytes);
             println!("  def-path table bytes: {}", def_path_table_bytes);
             println!("            item bytes: {}", item_bytes);
             println!("           index bytes: {}", index_bytes);
@@ -1430,7 +1462,8 @@ impl<'a, 'tcx> EncodeContext<'a, 'tcx> {
 pub fn encode_metadata<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                  cstore: &cstore::CStore,
                                  link_meta: &LinkMeta,
-                                 exported_symbols: &NodeSet)
+                                 exported_symbols: &NodeSet,
+                                 exported_monomorphizations: &[(DefId, &'tcx Substs<'tcx>)])
                                  -> Vec<u8> {
     let mut cursor = Cursor::new(vec![]);
     cursor.write_all(METADATA_HEADER).unwrap();
@@ -1445,6 +1478,7 @@ pub fn encode_metadata<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             link_meta: link_meta,
             cstore: cstore,
             exported_symbols: exported_symbols,
+            exported_monomorphizations: exported_monomorphizations,
             lazy_state: LazyState::NoNode,
             type_shorthands: Default::default(),
             predicate_shorthands: Default::default(),
@@ -183,9 +183,18 @@ pub struct CrateRoot {
     pub def_path_table: Lazy<hir::map::definitions::DefPathTable>,
     pub impls: LazySeq<TraitImpls>,
     pub exported_symbols: LazySeq<DefIndex>,
+    pub exported_monomorphizations: EncodedMonomorphizations,
     pub index: LazySeq<index::Index>,
 }
 
+/// The position and length of a `LazySeq<(DefId, &'tcx Substs<'tcx>)>`,
+/// which can't be named in `CrateRoot` as it has no `'tcx` lifetime.
+#[derive(RustcEncodable, RustcDecodable)]
+pub struct EncodedMonomorphizations {
+    pub position: usize,
+    pub len: usize,
+}
+
 #[derive(RustcEncodable, RustcDecodable)]
 pub struct CrateDep {
     pub name: ast::Name,
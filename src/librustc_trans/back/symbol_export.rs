@@ -11,9 +11,10 @@
 use context::SharedCrateContext;
 use monomorphize::Instance;
 use symbol_map::SymbolMap;
-use back::symbol_names::symbol_name;
+use back::symbol_names::{instantiated_symbol_name, symbol_name};
 use util::nodemap::FxHashMap;
 use rustc::hir::def_id::{DefId, CrateNum, LOCAL_CRATE};
+use rustc::middle::weak_lang_items;
 use rustc::session::config;
 use syntax::attr;
 use trans_item::TransItem;
@@ -59,6 +60,15 @@ impl ExportedSymbols {
             })
             .collect();
 
+        // The generic instances exported with `-Z share-generics` have to
+        // stay visible to the downstream crates linking to them.
+        for &(def_id, substs) in scx.exported_generics().borrow().iter() {
+            let trans_item = TransItem::Fn(Instance::new(def_id, substs));
+            let name = symbol_map.get_or_compute(scx, trans_item).into_owned();
+            debug!("EXPORTED SYMBOL (generic): {}", name);
+            local_crate.push((name, SymbolExportLevel::Rust));
+        }
+
         if scx.sess().entry_fn.borrow().is_some() {
             local_crate.push(("main".to_string(), SymbolExportLevel::C));
         }
@@ -105,8 +115,8 @@ impl ExportedSymbols {
                 .sess()
                 .cstore
                 .exported_symbols(cnum)
-                .iter()
-                .map(|&def_id| {
+                .into_iter()
+                .map(|def_id| {
                     let name = symbol_name(Instance::mono(scx.tcx(), def_id), scx);
                     let export_level = if special_runtime_crate {
                         // We can probably do better here by just ensuring that
@@ -128,10 +138,24 @@ impl ExportedSymbols {
                     };
                     debug!("EXPORTED SYMBOL (re-export): {} ({:?})", name, export_level);
                     (name, export_level)
-                })
-                .collect();
+                });
 
-            exports.insert(cnum, crate_exports);
+            // The generic instances the crate exports with `-Z share-generics`
+            // are linked to by their symbols as well. A dylib the crate is
+            // linked into statically has to export them in its place.
+            let generic_exports = scx
+                .sess()
+                .cstore
+                .exported_monomorphizations(scx.tcx(), cnum)
+                .into_iter()
+                .map(|(def_id, substs)| {
+                    let instance = Instance::new(def_id, substs);
+                    let name = instantiated_symbol_name(instance, cnum, scx);
+                    debug!("EXPORTED SYMBOL (re-export, generic): {}", name);
+                    (name, SymbolExportLevel::Rust)
+                });
+
+            exports.insert(cnum, crate_exports.chain(generic_exports).collect());
         }
 
         return ExportedSymbols {
@@ -142,7 +166,10 @@ impl ExportedSymbols {
                         sym_def_id: DefId)
                         -> SymbolExportLevel {
             let attrs = scx.tcx().get_attrs(sym_def_id);
-            if attr::contains_extern_indicator(scx.sess().diagnostic(), &attrs) {
+            // Weak lang items are defined in one crate and referred to by
+            // name from the code of all the others, like `extern` functions.
+            if attr::contains_extern_indicator(scx.sess().diagnostic(), &attrs) ||
+               weak_lang_items::link_name(&attrs).is_some() {
                 SymbolExportLevel::C
             } else {
                 SymbolExportLevel::Rust
@@ -101,7 +101,7 @@ use common::SharedCrateContext;
 use monomorphize::Instance;
 
 use rustc::middle::weak_lang_items;
-use rustc::hir::def_id::LOCAL_CRATE;
+use rustc::hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc::hir::map as hir_map;
 use rustc::ty::{self, Ty, TypeFoldable};
 use rustc::ty::fold::TypeVisitor;
@@ -126,7 +126,12 @@ fn get_symbol_hash<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
 
                              // values for generic type parameters,
                              // if any.
-                             substs: Option<&'tcx Substs<'tcx>>)
+                             substs: Option<&'tcx Substs<'tcx>>,
+
+                             // the crate translating the instance, which
+                             // is an upstream crate if this crate links to
+                             // its instance with `-Z share-generics`.
+                             instantiating_crate: CrateNum)
                              -> String {
     debug!("get_symbol_hash(def_path={:?}, parameters={:?})",
            def_path, substs);
@@ -158,8 +163,14 @@ fn get_symbol_hash<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
             // in case the same instances is emitted in two crates of the same
             // project.
             if substs.types().next().is_some() {
-                hasher.hash(scx.tcx().crate_name.as_str());
-                hasher.hash(scx.sess().local_crate_disambiguator().as_str());
+                if instantiating_crate == LOCAL_CRATE {
+                    hasher.hash(scx.tcx().crate_name.as_str());
+                    hasher.hash(scx.sess().local_crate_disambiguator().as_str());
+                } else {
+                    let cstore = &scx.sess().cstore;
+                    hasher.hash(cstore.original_crate_name(instantiating_crate).as_str());
+                    hasher.hash(cstore.crate_disambiguator(instantiating_crate).as_str());
+                }
             }
         }
     });
@@ -170,6 +181,17 @@ fn get_symbol_hash<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
 
 pub fn symbol_name<'a, 'tcx>(instance: Instance<'tcx>,
                              scx: &SharedCrateContext<'a, 'tcx>) -> String {
+    let instantiating_crate = scx.upstream_monomorphization(&instance).unwrap_or(LOCAL_CRATE);
+    instantiated_symbol_name(instance, instantiating_crate, scx)
+}
+
+/// The symbol name of `instance` as translated by `instantiating_crate`. Only
+/// differs from `symbol_name()` for generic instances that more than one crate
+/// may translate, see `get_symbol_hash()`.
+pub fn instantiated_symbol_name<'a, 'tcx>(instance: Instance<'tcx>,
+                                          instantiating_crate: CrateNum,
+                                          scx: &SharedCrateContext<'a, 'tcx>)
+                                          -> String {
     let def_id = instance.def_id();
     let substs = instance.substs;
 
@@ -256,7 +278,7 @@ pub fn symbol_name<'a, 'tcx>(instance: Instance<'tcx>,
     // and should not matter anyhow.
     let instance_ty = scx.tcx().erase_regions(&instance_ty);
 
-    let hash = get_symbol_hash(scx, &def_path, instance_ty, Some(substs));
+    let hash = get_symbol_hash(scx, &def_path, instance_ty, Some(substs), instantiating_crate);
 
     let mut buffer = SymbolPathBuffer {
         names: Vec::with_capacity(def_path.data.len())
@@ -292,7 +314,7 @@ pub fn exported_name_from_type_and_prefix<'a, 'tcx>(scx: &SharedCrateContext<'a,
         data: vec![],
         krate: LOCAL_CRATE,
     };
-    let hash = get_symbol_hash(scx, &empty_def_path, t, None);
+    let hash = get_symbol_hash(scx, &empty_def_path, t, None, LOCAL_CRATE);
     let path = [Symbol::intern(prefix).as_str()];
     mangle(path.iter().cloned(), &hash)
 }
@@ -34,9 +34,10 @@ use back::linker::LinkerInfo;
 use back::symbol_export::{self, ExportedSymbols};
 use llvm::{Linkage, ValueRef, Vector, get_param};
 use llvm;
-use rustc::hir::def_id::LOCAL_CRATE;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use middle::lang_items::StartFnLangItem;
 use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::subst::Substs;
 use rustc::dep_graph::{AssertDepGraphSafe, DepNode, WorkProduct};
 use rustc::hir::map as hir_map;
 use rustc::util::common::time;
@@ -48,8 +49,8 @@ use mir::lvalue::LvalueRef;
 use attributes;
 use builder::Builder;
 use callee;
-use common::{C_bool, C_bytes_in_context, C_i32, C_uint};
-use collector::{self, TransItemCollectionMode};
+use common::{C_array, C_bool, C_bytes_in_context, C_i32, C_uint};
+use collector::{self, TransItemCollectionMode, TransItemFilter};
 use common::{C_struct_in_context, C_u64, C_undef};
 use common::CrateContext;
 use common::{type_is_zero_size, val_ty};
@@ -725,8 +726,10 @@ fn contains_null(s: &str) -> bool {
     s.bytes().any(|b| b == 0)
 }
 
-fn write_metadata(cx: &SharedCrateContext,
-                  exported_symbols: &NodeSet) -> Vec<u8> {
+fn write_metadata<'a, 'tcx>(cx: &SharedCrateContext<'a, 'tcx>,
+                            exported_symbols: &NodeSet,
+                            exported_generics: &[(DefId, &'tcx Substs<'tcx>)])
+                            -> Vec<u8> {
     use flate;
 
     #[derive(PartialEq, Eq, PartialOrd, Ord)]
@@ -756,7 +759,8 @@ fn write_metadata(cx: &SharedCrateContext,
     let cstore = &cx.tcx().sess.cstore;
     let metadata = cstore.encode_metadata(cx.tcx(),
                                           cx.link_meta(),
-                                          exported_symbols);
+                                          exported_symbols,
+                                          exported_generics);
     if kind == MetadataKind::Uncompressed {
         return metadata;
     }
@@ -840,14 +844,16 @@ fn internalize_symbols<'a, 'tcx>(sess: &Session,
         }
 
         // Also collect all symbols for which we cannot adjust linkage, because
-        // it is fixed by some directive in the source code.
+        // it is fixed by some directive in the source code. `#[used]` statics
+        // are kept visible too, as something outside of Rust looks for them.
         let (locally_defined_symbols, linkage_fixed_explicitly) = {
             let mut locally_defined_symbols = FxHashSet();
             let mut linkage_fixed_explicitly = FxHashSet();
 
             for trans_item in scx.translation_items().borrow().iter() {
                 let symbol_name = symbol_map.get_or_compute(scx, *trans_item);
-                if trans_item.explicit_linkage(tcx).is_some() {
+                if trans_item.explicit_linkage(tcx).is_some() ||
+                   trans_item.is_used_static(tcx) {
                     linkage_fixed_explicitly.insert(symbol_name.clone());
                 }
                 locally_defined_symbols.insert(symbol_name);
@@ -914,6 +920,26 @@ fn internalize_symbols<'a, 'tcx>(sess: &Session,
     }
 }
 
+/// Creates the `llvm.used` variable listing the `#[used]` statics of the
+/// module, so that LLVM neither removes them nor the items they refer to.
+fn create_used_variable(ccx: &CrateContext) {
+    let used = ccx.used_statics().borrow();
+    if used.is_empty() {
+        return;
+    }
+
+    let name = "llvm.used\0".as_ptr() as *const _;
+    let section = "llvm.metadata\0".as_ptr() as *const _;
+    let array = C_array(Type::i8p(ccx), &used);
+
+    unsafe {
+        let g = llvm::LLVMAddGlobal(ccx.llmod(), val_ty(array).to_ref(), name);
+        llvm::LLVMSetInitializer(g, array);
+        llvm::LLVMRustSetLinkage(g, llvm::Linkage::AppendingLinkage);
+        llvm::LLVMSetSection(g, section);
+    }
+}
+
 // Create a `__imp_<symbol> = &symbol` global for every public static `symbol`.
 // This is required to satisfy `dllimport` references to static data in .rlibs
 // when using MSVC linker.  We do this only for data, as linker can fix up
@@ -1066,9 +1092,27 @@ pub fn trans_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                                              link_meta.clone(),
                                              exported_symbols,
                                              check_overflow);
+
+    let should_trans = !tcx.sess.opts.debugging_opts.no_trans &&
+                       tcx.sess.opts.output_types.should_trans();
+
+    // With `-Z share-generics` the metadata lists the generic instances
+    // this crate exports, so the translation items have to be collected
+    // before it is written.
+    let early_partitioning = if should_trans && shared_ccx.collection_flags().share_generics {
+        let (codegen_units, symbol_map) = collect_and_partition_translation_items(&shared_ccx);
+        *shared_ccx.exported_generics().borrow_mut() =
+            exported_generics(&codegen_units, &symbol_map);
+        Some((codegen_units, symbol_map))
+    } else {
+        None
+    };
+
     // Translate the metadata.
     let metadata = time(tcx.sess.time_passes(), "write metadata", || {
-        write_metadata(&shared_ccx, shared_ccx.exported_symbols())
+        write_metadata(&shared_ccx,
+                       shared_ccx.exported_symbols(),
+                       &shared_ccx.exported_generics().borrow())
     });
 
     let metadata_module = ModuleTranslation {
@@ -1082,8 +1126,7 @@ pub fn trans_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
     let no_builtins = attr::contains_name(&krate.attrs, "no_builtins");
 
     // Skip crate items and just output metadata in -Z no-trans mode.
-    if tcx.sess.opts.debugging_opts.no_trans ||
-       !tcx.sess.opts.output_types.should_trans() {
+    if !should_trans {
         let empty_exported_symbols = ExportedSymbols::empty();
         let linker_info = LinkerInfo::new(&shared_ccx, &empty_exported_symbols);
         return CrateTranslation {
@@ -1100,7 +1143,10 @@ pub fn trans_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
     // Run the translation item collector and partition the collected items into
     // codegen units.
-    let (codegen_units, symbol_map) = collect_and_partition_translation_items(&shared_ccx);
+    let (codegen_units, symbol_map) = match early_partitioning {
+        Some(partitioning) => partitioning,
+        None => collect_and_partition_translation_items(&shared_ccx),
+    };
 
     let symbol_map = Rc::new(symbol_map);
 
@@ -1187,6 +1233,9 @@ pub fn trans_crate<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                 }
             }
 
+            // Keep `#[used]` statics alive through LLVM's optimizations
+            create_used_variable(&ccx);
+
             // Finalize debuginfo
             if ccx.sess().opts.debuginfo != NoDebugInfo {
                 debuginfo::finalize(&ccx);
@@ -1497,15 +1546,43 @@ fn trans_reuse_previous_work_products(scx: &SharedCrateContext,
         .collect()
 }
 
+/// The generic instances this crate exports for downstream crates built
+/// with `-Z share-generics` to link to, ordered by symbol name.
+fn exported_generics<'tcx>(codegen_units: &[CodegenUnit<'tcx>],
+                           symbol_map: &SymbolMap<'tcx>)
+                           -> Vec<(DefId, &'tcx Substs<'tcx>)> {
+    let mut generics: Vec<_> = codegen_units.iter().flat_map(|cgu| {
+        cgu.items().iter().filter_map(move |(&trans_item, &linkage)| {
+            match trans_item {
+                TransItem::Fn(Instance { def: ty::InstanceDef::Item(def_id), substs })
+                    if linkage == llvm::ExternalLinkage &&
+                       substs.types().next().is_some() => {
+                    Some((symbol_map.get(trans_item).unwrap(), (def_id, substs)))
+                }
+                _ => None,
+            }
+        })
+    }).collect();
+
+    generics.sort_by_key(|&(symbol_name, _)| symbol_name);
+    generics.into_iter().map(|(_, generic)| generic).collect()
+}
+
 fn collect_and_partition_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>)
                                                      -> (Vec<CodegenUnit<'tcx>>, SymbolMap<'tcx>) {
     let time_passes = scx.sess().time_passes();
 
-    let collection_mode = match scx.sess().opts.debugging_opts.print_trans_items {
+    let (collection_mode, print_filter) = match scx.collection_flags().print_trans_items {
         Some(ref s) => {
-            let mode_string = s.to_lowercase();
+            // The mode can be followed by patterns selecting the printed items,
+            // as in `lazy:my_mod::*`.
+            let (mode_string, print_filter) = match s.find(':') {
+                Some(index) => (&s[..index], Some(TransItemFilter::new(&s[index + 1..]))),
+                None => (&s[..], None),
+            };
+            let mode_string = mode_string.to_lowercase();
             let mode_string = mode_string.trim();
-            if mode_string == "eager" {
+            let collection_mode = if mode_string == "eager" {
                 TransItemCollectionMode::Eager
             } else {
                 if mode_string != "lazy" {
@@ -1516,12 +1593,13 @@ fn collect_and_partition_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a
                 }
 
                 TransItemCollectionMode::Lazy
-            }
+            };
+            (collection_mode, print_filter)
         }
-        None => TransItemCollectionMode::Lazy
+        None => (TransItemCollectionMode::Lazy, None)
     };
 
-    let (items, inlining_map) =
+    let (items, inlining_map, available_upstream) =
         time(time_passes, "translation item collection", || {
             collector::collect_crate_translation_items(&scx, collection_mode)
     });
@@ -1552,7 +1630,7 @@ fn collect_and_partition_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a
         }
     }
 
-    if scx.sess().opts.debugging_opts.print_trans_items.is_some() {
+    if scx.collection_flags().print_trans_items.is_some() {
         let mut item_to_cgus = FxHashMap();
 
         for cgu in &codegen_units {
@@ -1565,6 +1643,9 @@ fn collect_and_partition_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a
 
         let mut item_keys: Vec<_> = items
             .iter()
+            .filter(|&&i| {
+                print_filter.as_ref().map_or(true, |filter| filter.matches(scx.tcx(), i))
+            })
             .map(|i| {
                 let mut output = i.to_string(scx.tcx());
                 output.push_str(" @@");
@@ -1594,6 +1675,10 @@ fn collect_and_partition_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a
                     output.push_str(linkage_abbrev);
                     output.push_str("]");
                 }
+                if let Some(reason) = collector::trans_item_reason(scx, *i, &available_upstream) {
+                    output.push_str(" ## ");
+                    output.push_str(&reason);
+                }
                 output
             })
             .collect();
@@ -1605,5 +1690,9 @@ fn collect_and_partition_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a
         }
     }
 
+    if scx.collection_flags().print_mono_array_families {
+        collector::print_array_length_families(scx, &items);
+    }
+
     (codegen_units, symbol_map)
 }
@@ -75,14 +75,29 @@
 //! create a translation item consisting of the items DefId and, since we only
 //! consider non-generic items, an empty type-substitution set.
 //!
+//! The entry function of an executable is always a root. The `main` shim
+//! created along with it calls the start lang item, which is why the entry
+//! function has an edge to that item, and it is a root as well if it is
+//! translated in this crate.
+//!
+//! A crate built only as a `cdylib` or `staticlib` can only be used through
+//! the items that have a fixed symbol name. These, along with its lang items
+//! and `#[used]` statics, are its only roots; its other functions and statics
+//! are translated if they are reachable from these.
+//!
 //! ### Finding neighbor nodes
 //! Given a translation item node, we can discover neighbors by inspecting its
 //! MIR. We walk the MIR and any time we hit upon something that signifies a
 //! reference to another translation item, we have found a neighbor. Since the
 //! translation item we are currently at is always monomorphic, we also know the
 //! concrete type arguments of its neighbors, and so all neighbors again will be
-//! monomorphic. The specific forms a reference to a neighboring node can take
-//! in MIR are quite diverse. Here is an overview:
+//! monomorphic. Only the blocks that can be reached in the item are walked: a
+//! `match` or `if` on a constant, like an associated constant of a type
+//! parameter, only leads to the arm its value selects, and the other arms are
+//! neither walked here nor translated later.
+//!
+//! The specific forms a reference to a neighboring node can take in MIR are
+//! quite diverse. Here is an overview:
 //!
 //! #### Calling Functions/Methods
 //! The most obvious form of one translation item referencing another is a
@@ -118,17 +133,22 @@
 //! translation item. Calls are just a special case of that.
 //!
 //! #### Closures
-//! In a way, closures are a simple case. Since every closure object needs to be
-//! constructed somewhere, we can reliably discover them by observing
-//! `RValue::Aggregate` expressions with `AggregateKind::Closure`. This is also
-//! true for closures inlined from other crates.
+//! In a way, closures are a simple case. A closure body is a neighbor of the
+//! items that call it, like any other function. A closure called through a
+//! trait it does not implement directly, i.e. an `Fn` or `FnMut` closure called
+//! through `FnOnce`, or cast to a function pointer, is called by a shim
+//! instead, and the shim's MIR calls the body. This is also true for closures
+//! inlined from other crates, whose MIR comes from the crate metadata like that
+//! of the function defining them.
 //!
 //! #### Drop glue
 //! Drop glue translation items are introduced by MIR drop-statements. The
 //! generated translation item will again have drop-glue item neighbors if the
 //! type to be dropped contains nested values that also need to be dropped. It
 //! might also have a function item neighbor for the explicit `Drop::drop`
-//! implementation of its type.
+//! implementation of its type. Unions never drop their fields, so the drop
+//! glue of a union only leads to its own `Drop::drop`, if there is one, and a
+//! union without one needs no drop glue at all.
 //!
 //! #### Unsizing Casts
 //! A subtle way of introducing neighbor edges is by casting to a trait object.
@@ -141,7 +161,40 @@
 //! Since `Box` expression have special compiler support, no explicit calls to
 //! `exchange_malloc()` and `exchange_free()` may show up in MIR, even if the
 //! compiler will generate them. We have to observe `Rvalue::Box` expressions
-//! and Box-typed drop-statements for that purpose.
+//! and Box-typed drop-statements for that purpose. Boxes of zero-sized values
+//! are never allocated, so these lead to neither function.
+//!
+//! #### Landing pads
+//! The cleanup blocks of a function are translated to landing pads, which
+//! refer to the `eh_personality` lang item, and to `eh_unwind_resume` on some
+//! targets, without the MIR ever mentioning them. Functions with cleanup
+//! blocks thus have these as neighbors if they are defined in the crate.
+//! Without landing pads, i.e. with `-C panic=abort`, cleanup blocks are
+//! ignored altogether.
+//!
+//! #### Statics and Constants
+//! The initializer of a static has MIR like any function body, and is searched
+//! the same way, together with the values promoted in it. Function pointers,
+//! arrays of them and trait objects stored in a static thus lead to the items
+//! they refer to. The body of a constant is searched as if it was part of each
+//! body using the constant, as it does not get a translation item of its own.
+//! Associated constants are first resolved to the impl, or the trait, that
+//! provides their value for the substitutions of the using body.
+//! Every static declared outside of function bodies is a root, whether it is
+//! used or not, except in C-compatible libraries; `#[used]` statics are
+//! additionally kept out of internalization and put into `llvm.used`, so that
+//! neither they nor the functions in their initializers are discarded later.
+//! Statics declared inside a function body are neighbors of the functions
+//! whose MIR refers to them instead, unless they are `#[used]`, have a fixed
+//! symbol name, or can be linked to by other crates.
+//!
+//! #### Const Fns
+//! Calls of a `const fn` in the bodies of statics, constants and promoted
+//! values are evaluated at compile time, and the function needs no translation
+//! item for them; only the items its body refers to are neighbors. Calls at
+//! runtime and function pointers lead to it like to any other function. For
+//! the same reason, a non-generic `const fn` is only a root if other crates
+//! can call it.
 //!
 //!
 //! Interaction with Cross-Crate Inlining
@@ -171,469 +224,3154 @@
 //!   in the crate, even of no drop call for that type exists (yet). It will
 //!   also instantiate default implementations of trait methods, something that
 //!   otherwise is only done on demand.
-//!
-//!
-//! Open Issues
-//! -----------
-//! Some things are not yet fully implemented in the current version of this
-//! module.
-//!
-//! ### Initializers of Constants and Statics
-//! Since no MIR is constructed yet for initializer expressions of constants and
-//! statics we cannot inspect these properly.
-//!
-//! ### Const Fns
-//! Ideally, no translation item should be generated for const fns unless there
-//! is a call to them that cannot be evaluated at compile time. At the moment
-//! this is not implemented however: a translation item will be produced
-//! regardless of whether it is actually needed or not.
 
 use rustc::hir;
 use rustc::hir::itemlikevisit::ItemLikeVisitor;
 
 use rustc::hir::map as hir_map;
-use rustc::hir::def_id::DefId;
-use rustc::middle::lang_items::{ExchangeMallocFnLangItem};
+use rustc::hir::map::blocks::FnLikeNode;
+use rustc::hir::map::definitions::DefPathData;
+use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc::infer::TransNormalize;
+use rustc::lint;
+use rustc::lint::builtin::{MONOMORPHIZATION_BLOAT, UNCALLED_VTABLE_METHODS};
+use rustc::middle::lang_items::{ExchangeMallocFnLangItem, LangItem, LanguageItems};
+use rustc::middle::lang_items::{PanicBoundsCheckFnLangItem, PanicFnLangItem};
+use rustc::middle::weak_lang_items;
+use rustc::session::Session;
+use rustc::session::config;
 use rustc::traits;
 use rustc::ty::subst::{Substs, Subst};
-use rustc::ty::{self, TypeFoldable, TyCtxt};
+use rustc::ty::{self, Ty, TypeFoldable, TyCtxt};
 use rustc::ty::adjustment::CustomCoerceUnsized;
 use rustc::mir::{self, Location};
 use rustc::mir::visit::Visitor as MirVisitor;
+use errors::DiagnosticBuilder;
 
+use base;
 use context::SharedCrateContext;
 use common::{def_ty, instance_ty};
+use glue;
 use monomorphize::{self, Instance};
+use mir::{reachable_blocks, switch_constant};
 use util::nodemap::{FxHashSet, FxHashMap, DefIdMap};
 
 use trans_item::{TransItem, DefPathBasedNames, InstantiationMode};
 
+use rustc_data_structures::inlining_map;
+use rustc_data_structures::indexed_vec::Idx;
+
+use std::cell::{Cell, RefCell};
+use std::cmp;
+use std::fmt::{self, Write};
+use std::fs::File;
+use std::io::Read;
+use std::mem;
+use std::rc::Rc;
+use syntax::ast;
+use syntax::attr;
+use syntax_pos::{DUMMY_SP, MultiSpan, Span};
+
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum TransItemCollectionMode {
     Eager,
     Lazy
 }
 
-/// Maps every translation item to all translation items it references in its
-/// body.
-pub struct InliningMap<'tcx> {
-    // Maps a source translation item to a range of target translation items
-    // that are potentially inlined by LLVM into the source.
-    // The two numbers in the tuple are the start (inclusive) and
-    // end index (exclusive) within the `targets` vecs.
-    index: FxHashMap<TransItem<'tcx>, (usize, usize)>,
-    targets: Vec<TransItem<'tcx>>,
+/// The name, as given on the command line, value and description of every
+/// collection flag, see `SharedCrateContext::collection_flags()`.
+pub fn collection_flags(opts: &config::DebuggingOptions) -> Vec<(String, String, &'static str)> {
+    opts.group_options("COLLECTION")
+        .into_iter()
+        .map(|(name, value, description)| (name.replace("_", "-"), value, description))
+        .collect()
+}
+
+/// Implements `-Z mono-help`: prints every collection flag with its value.
+pub fn print_collection_flags(opts: &config::DebuggingOptions) {
+    println!("\nTranslation item collection options:\n");
+    for (name, value, description) in collection_flags(opts) {
+        println!("    -Z {} = {}\n        {}", name, value, description);
+    }
 }
 
-impl<'tcx> InliningMap<'tcx> {
+macro_rules! collection_stats {
+    ($($phase:expr => { $($name:ident,)* })*) => {
+        /// The counters printed by `-Z trans-collection-stats`, grouped by the
+        /// phase of collection they belong to. Nothing is counted unless the
+        /// flag is set.
+        pub struct CollectionStats {
+            enabled: bool,
+            $($(pub $name: Cell<usize>,)*)*
+        }
+
+        impl CollectionStats {
+            pub fn new(enabled: bool) -> CollectionStats {
+                CollectionStats {
+                    enabled: enabled,
+                    $($($name: Cell::new(0),)*)*
+                }
+            }
 
-    fn new() -> InliningMap<'tcx> {
-        InliningMap {
-            index: FxHashMap(),
-            targets: Vec::new(),
+            /// Prints the collection flags the counters were produced with,
+            /// followed by the counters in a fixed order, so that the output
+            /// of two runs can be compared line by line.
+            fn print(&self, opts: &config::DebuggingOptions) {
+                println!("--- translation item collection flags ---");
+                for (name, value, _) in collection_flags(opts) {
+                    println!("    {}: {}", name, value);
+                }
+                println!("--- translation item collection stats ---");
+                $(
+                    println!("{}:", $phase);
+                    $(println!("    {}: {}", stringify!($name), self.$name.get());)*
+                )*
+            }
         }
     }
+}
 
-    fn record_inlining_canditates<I>(&mut self,
-                                     source: TransItem<'tcx>,
-                                     targets: I)
-        where I: Iterator<Item=TransItem<'tcx>>
-    {
-        assert!(!self.index.contains_key(&source));
+collection_stats! {
+    "roots" => {
+        roots,
+        eager_default_impls,
+    }
+    "walk" => {
+        items_collected,
+        mir_bodies_visited,
+        trivial_bodies_skipped,
+        recursion_depth_bucket_hits,
+        recursion_depth_entries_searched,
+        recursion_depth_entries_recorded,
+    }
+    "allocations" => {
+        visited_capacity_reserved,
+        visited_set_growths,
+        neighbour_buffers_allocated,
+    }
+    "substitution" => {
+        param_substs_folds,
+        param_substs_folds_skipped,
+    }
+    "resolution" => {
+        upstream_trans_locally_hits,
+        upstream_trans_locally_misses,
+    }
+    "drop glue" => {
+        drop_glue_resolutions,
+        drop_in_place_cache_hits,
+        drop_in_place_cache_misses,
+        drop_in_place_max_depth,
+        drop_in_place_max_adjusted_depth,
+    }
+    "vtables" => {
+        unsizing_cache_hits,
+        unsizing_cache_misses,
+        vtable_methods_cache_hits,
+        vtable_methods_cache_misses,
+        lockstep_tails_cache_hits,
+        lockstep_tails_cache_misses,
+    }
+    "default impls" => {
+        default_impl_predicates_tested,
+        default_impl_predicates_skipped,
+    }
+    "inlining map" => {
+        inlining_map_bytes,
+    }
+}
 
-        let start_index = self.targets.len();
-        self.targets.extend(targets);
-        let end_index = self.targets.len();
-        self.index.insert(source, (start_index, end_index));
+impl CollectionStats {
+    pub fn enabled(&self) -> bool {
+        self.enabled
     }
 
-    // Internally iterate over all items referenced by `source` which will be
-    // made available for inlining.
-    pub fn with_inlining_candidates<F>(&self, source: TransItem<'tcx>, mut f: F)
-        where F: FnMut(TransItem<'tcx>) {
-        if let Some(&(start_index, end_index)) = self.index.get(&source)
-        {
-            for candidate in &self.targets[start_index .. end_index] {
-                f(*candidate)
-            }
+    pub fn bump(&self, counter: &Cell<usize>) {
+        if self.enabled {
+            counter.set(counter.get() + 1);
         }
     }
+
+    fn set(&self, counter: &Cell<usize>, value: usize) {
+        if self.enabled {
+            counter.set(value);
+        }
+    }
+}
+
+/// Maps every translation item to all translation items it references in its
+/// body.
+pub type InliningMap<'tcx> = inlining_map::InliningMap<TransItem<'tcx>>;
+
+// Checks that every inlining candidate has had its own candidates recorded,
+// which every item that collection has searched has, even if it has none. The
+// partitioner relies on this when it copies candidates into codegen units: the
+// candidates of an unrecorded item would silently be left out, and show up as
+// missing symbols only in multi-CGU builds.
+pub fn assert_inlining_map_closed<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                            inlining_map: &InliningMap<'tcx>) {
+    if let Err((source, candidate)) = inlining_map.check_closed() {
+        bug!("inlining candidate `{}` of `{}` has no inlining candidates recorded",
+             candidate.to_string(tcx),
+             source.to_string(tcx));
+    }
 }
 
 pub fn collect_crate_translation_items<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
                                                  mode: TransItemCollectionMode)
                                                  -> (FxHashSet<TransItem<'tcx>>,
-                                                     InliningMap<'tcx>) {
+                                                     InliningMap<'tcx>,
+                                                     FxHashMap<TransItem<'tcx>, CrateNum>) {
     // We are not tracking dependencies of this pass as it has to be re-executed
     // every time no matter what.
     scx.tcx().dep_graph.with_ignore(|| {
-        let roots = collect_roots(scx, mode);
-
-        debug!("Building translation item graph, beginning at roots");
-        let mut visited = FxHashSet();
-        let mut recursion_depths = DefIdMap();
-        let mut inlining_map = InliningMap::new();
+        // Root collection asks much the same questions as the walk, so it
+        // uses the memos of the walk as well.
+        let memos = CollectionMemos::new();
+        let mut eager_default_impls = Vec::new();
+        let mut roots = collect_roots(scx, &memos, mode, &mut eager_default_impls);
+        let entry_fn_instances = entry_fn_instances(scx, &memos);
+        add_entry_roots(scx, entry_fn_instances, &mut roots);
 
-        for root in roots {
-            collect_items_rec(scx,
-                              root,
-                              &mut visited,
-                              &mut recursion_depths,
-                              &mut inlining_map);
+        let export_list = scx.collection_flags()
+                             .export_list
+                             .as_ref()
+                             .map(|path| ExportList::load(scx.sess(), path));
+        if let Some(ref export_list) = export_list {
+            if mode == TransItemCollectionMode::Lazy {
+                add_export_list_roots(scx, &memos, export_list, &mut roots);
+            }
         }
 
-        (visited, inlining_map)
-    })
-}
-
-// Find all non-generic items by walking the HIR. These items serve as roots to
-// start monomorphizing from.
-fn collect_roots<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
-                           mode: TransItemCollectionMode)
-                           -> Vec<TransItem<'tcx>> {
-    debug!("Collecting roots");
-    let mut roots = Vec::new();
-
-    {
-        let mut visitor = RootCollector {
-            scx: scx,
-            mode: mode,
-            output: &mut roots,
+        debug!("Building translation item graph, beginning at roots");
+        let expected_items = expected_item_count(scx, &roots);
+        scx.collection_stats().set(&scx.collection_stats().visited_capacity_reserved,
+                                   expected_items);
+        let entry_edge = match entry_fn_instances {
+            Some((entry_fn, Some(start_fn))) => Some((entry_fn, start_fn)),
+            _ => None,
         };
+        let unwind_lang_items = unwind_lang_items(scx, &memos);
+        // How items are referenced is only needed for printing the items whose
+        // address is taken and for the `uncalled_vtable_methods` lint.
+        let check_vtables = !lint::allowed_in_whole_crate(scx.sess(),
+                                                          scx.tcx().hir.krate(),
+                                                          UNCALLED_VTABLE_METHODS);
+        let references = if scx.collection_flags().print_address_only_items || check_vtables {
+            Some(ItemReferences::new(check_vtables))
+        } else {
+            None
+        };
+        let mut state = CollectionState::new(expected_items,
+                                             scx.collection_flags().mono_fuel,
+                                             !scx.collection_flags().no_mono_mir_cache,
+                                             memos,
+                                             entry_edge,
+                                             unwind_lang_items,
+                                             references);
+        if scx.collection_flags().print_cross_crate_mono_chains {
+            state.first_users = Some(FxHashMap());
+        }
+        // Upstream functions get the level of the crate root, see
+        // `check_monomorphization_bloat()`.
+        let bloat_level = lint::crate_level_source(scx.sess(),
+                                                   scx.tcx().hir.krate(),
+                                                   MONOMORPHIZATION_BLOAT);
+        if bloat_level.0 != lint::Allow {
+            state.upstream_users = Some(DefIdMap());
+        }
+        for &root in &roots {
+            state.current_root = Some(root);
+            collect_items_rec(scx, root, &mut state);
+        }
 
-        scx.tcx().hir.krate().visit_all_item_likes(&mut visitor);
-    }
+        // Items are recorded as inlining candidates of their users before
+        // they are searched, so this is where the ones that ran out of
+        // mono-fuel show up. Partitioning would translate them as local copies
+        // after all.
+        if !state.fuel.skipped.is_empty() {
+            state.inlining_map.remove_candidates(&state.fuel.skipped);
+        }
 
-    roots
-}
+        // Printed before anything walks the graph a second time.
+        let stats = scx.collection_stats();
+        if stats.enabled() {
+            stats.set(&stats.roots, roots.len());
+            stats.set(&stats.eager_default_impls, eager_default_impls.len());
+            stats.set(&stats.items_collected, state.visited.len());
+            stats.set(&stats.recursion_depth_bucket_hits, state.recursion_depths.bucket_hits);
+            stats.set(&stats.recursion_depth_entries_searched,
+                      state.recursion_depths.entries_searched);
+            stats.set(&stats.recursion_depth_entries_recorded,
+                      state.recursion_depths.entries_recorded);
+            stats.set(&stats.drop_in_place_max_depth, state.drop_recursion_warning.max_depth);
+            stats.set(&stats.drop_in_place_max_adjusted_depth,
+                      state.drop_recursion_warning.max_adjusted_depth);
+            stats.set(&stats.inlining_map_bytes, state.inlining_map.memory_usage_estimate());
+            stats.print(scx.collection_flags());
+        }
 
-// Collect all monomorphized translation items reachable from `starting_point`
-fn collect_items_rec<'a, 'tcx: 'a>(scx: &SharedCrateContext<'a, 'tcx>,
-                                   starting_point: TransItem<'tcx>,
-                                   visited: &mut FxHashSet<TransItem<'tcx>>,
-                                   recursion_depths: &mut DefIdMap<usize>,
-                                   inlining_map: &mut InliningMap<'tcx>) {
-    if !visited.insert(starting_point.clone()) {
-        // We've been here already, no need to search again.
-        return;
-    }
-    debug!("BEGIN collect_items_rec({})", starting_point.to_string(scx.tcx()));
+        state.fuel.report(scx);
+        state.recursion_limit_errors.report(scx.tcx());
+        state.type_length_overflow.report(scx.tcx());
 
-    let mut neighbors = Vec::new();
-    let recursion_depth_reset;
+        // Errors found during collection (e.g. reified intrinsics or exceeded
+        // limits) leave nothing sensible to translate. Everything has been
+        // reported at this point, so that all of the errors can be fixed at
+        // once.
+        scx.sess().abort_if_errors();
 
-    match starting_point {
-        TransItem::Static(node_id) => {
-            let def_id = scx.tcx().hir.local_def_id(node_id);
-            let instance = Instance::mono(scx.tcx(), def_id);
+        if let Some(ref export_list) = export_list {
+            check_export_list(scx, export_list, &state.visited);
+        }
 
-            // Sanity check whether this ended up being collected accidentally
-            debug_assert!(should_trans_locally(scx.tcx(), &instance));
+        check_monomorphization_bloat(scx, &state);
 
-            let ty = instance_ty(scx, &instance);
-            visit_drop_use(scx, ty, true, &mut neighbors);
+        if let Some(ref references) = state.references {
+            check_uncalled_vtable_methods(scx, references);
 
-            recursion_depth_reset = None;
+            if scx.collection_flags().print_address_only_items {
+                print_address_only_items(scx, references);
+            }
+        }
 
-            collect_neighbours(scx, instance, &mut neighbors);
+        if scx.collection_flags().print_cross_crate_mono_chains {
+            print_cross_crate_mono_chains(scx, &state);
         }
-        TransItem::Fn(instance) => {
-            // Sanity check whether this ended up being collected accidentally
-            debug_assert!(should_trans_locally(scx.tcx(), &instance));
 
-            // Keep track of the monomorphization recursion depth
-            recursion_depth_reset = Some(check_recursion_limit(scx.tcx(),
-                                                               instance,
-                                                               recursion_depths));
-            check_type_length_limit(scx.tcx(), instance);
+        if scx.collection_flags().print_eager_default_impls {
+            print_eager_default_impls(scx, &eager_default_impls);
+        }
 
-            collect_neighbours(scx, instance, &mut neighbors);
+        if scx.collection_flags().verify_mono_determinism {
+            verify_determinism(scx, &roots, &state);
         }
-    }
 
-    record_inlining_canditates(scx.tcx(), starting_point, &neighbors[..], inlining_map);
+        if cfg!(debug_assertions) {
+            assert_inlining_map_closed(scx.tcx(), &state.inlining_map);
+        }
+
+        // The items an upstream crate also exports a copy of go with the
+        // result. They are only reported by `-Z print-trans-items` for now,
+        // partitioning translates them like any other item.
+        (state.visited, state.inlining_map, state.available_upstream)
+    })
+}
+
+// Implements `-Z verify-mono-determinism`: walks the translation item graph a
+// second time, starting from the roots in reverse order, and reports an error
+// if the result differs from the first walk in any way. The outcome of
+// collection must not depend on the order in which items are discovered.
+fn verify_determinism<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                roots: &[TransItem<'tcx>],
+                                state: &CollectionState<'tcx>) {
+    let tcx = scx.tcx();
 
-    for neighbour in neighbors {
-        collect_items_rec(scx, neighbour, visited, recursion_depths, inlining_map);
+    if scx.collection_flags().mono_fuel.is_some() {
+        // With limited fuel, the collected set depends on the order of the
+        // walk by design.
+        scx.sess().warn("-Z verify-mono-determinism has no effect with -Z mono-fuel");
+        return
     }
 
-    if let Some((def_id, depth)) = recursion_depth_reset {
-        recursion_depths.insert(def_id, depth);
+    // The second walk neither uses the MIR use cache nor deduplicates
+    // neighbours, to check these as well. It starts out with memos of its
+    // own, so that it does not merely repeat the answers of the first walk.
+    let mut second_state = CollectionState::new(state.visited.len(),
+                                                None,
+                                                false,
+                                                CollectionMemos::new(),
+                                                state.entry_edge,
+                                                state.unwind_lang_items.clone(),
+                                                None);
+    second_state.dedup_neighbours = false;
+    // Warnings have already been emitted by the first walk.
+    second_state.drop_recursion_warning.emitted = true;
+    for &root in roots.iter().rev() {
+        second_state.current_root = Some(root);
+        collect_items_rec(scx, root, &mut second_state);
     }
 
-    debug!("END collect_items_rec({})", starting_point.to_string(scx.tcx()));
-}
+    let canonical_items = |state: &CollectionState<'tcx>| {
+        let mut items: Vec<_> = state.visited.iter().map(|i| i.to_string(tcx)).collect();
+        items.sort();
+        items
+    };
 
-fn record_inlining_canditates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                                        caller: TransItem<'tcx>,
-                                        callees: &[TransItem<'tcx>],
-                                        inlining_map: &mut InliningMap<'tcx>) {
-    let is_inlining_candidate = |trans_item: &TransItem<'tcx>| {
-        trans_item.instantiation_mode(tcx) == InstantiationMode::LocalCopy
+    let canonical_inlining_map = |state: &CollectionState<'tcx>| {
+        let mut entries: Vec<_> = state.inlining_map
+                                       .sources()
+                                       .map(|source| {
+            let mut targets = Vec::new();
+            state.inlining_map.with_inlining_candidates(source, |t| {
+                targets.push(t.to_string(tcx));
+            });
+            targets.sort();
+            targets.dedup();
+            (source.to_string(tcx), targets)
+        }).collect();
+        entries.sort();
+        entries
     };
 
-    let inlining_candidates = callees.into_iter()
-                                     .map(|x| *x)
-                                     .filter(is_inlining_candidate);
+    let first_items = canonical_items(state);
+    let second_items = canonical_items(&second_state);
+    if let Some(divergence) = first_divergence(&first_items, &second_items) {
+        scx.sess().err(&format!("translation item collection is not deterministic: {}",
+                                divergence));
+        return
+    }
 
-    inlining_map.record_inlining_canditates(caller, inlining_candidates);
-}
+    // The first walk drops duplicate neighbours before recording them as
+    // inlining candidates, so there must not be any in what it recorded. The
+    // second walk keeps them, they are only dropped to compare the walks.
+    for source in state.inlining_map.sources() {
+        let mut seen = FxHashSet();
+        let mut duplicate = None;
+        state.inlining_map.with_inlining_candidates(source, |target| {
+            if !seen.insert(target) && duplicate.is_none() {
+                duplicate = Some(target);
+            }
+        });
+        if let Some(duplicate) = duplicate {
+            scx.sess().err(&format!("inlining candidates of `{}` contain `{}` more than once",
+                                    source.to_string(tcx),
+                                    duplicate.to_string(tcx)));
+            return
+        }
+    }
 
-fn check_recursion_limit<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                                   instance: Instance<'tcx>,
-                                   recursion_depths: &mut DefIdMap<usize>)
-                                   -> (DefId, usize) {
-    let def_id = instance.def_id();
-    let recursion_depth = recursion_depths.get(&def_id).cloned().unwrap_or(0);
-    debug!(" => recursion depth={}", recursion_depth);
+    let first_inlining_map = canonical_inlining_map(state);
+    let second_inlining_map = canonical_inlining_map(&second_state);
 
-    let recursion_depth = if Some(def_id) == tcx.lang_items.drop_in_place_fn() {
-        // HACK: drop_in_place creates tight monomorphization loops. Give
-        // it more margin.
-        recursion_depth / 4
-    } else {
-        recursion_depth
-    };
+    for (first, second) in first_inlining_map.iter().zip(second_inlining_map.iter()) {
+        if first != second {
+            let divergence = if first.0 != second.0 {
+                format!("`{}` vs `{}`", first.0, second.0)
+            } else {
+                first_divergence(&first.1, &second.1).unwrap()
+            };
+            scx.sess().err(&format!("inlining candidates of `{}` are not deterministic: \
+                                     {}",
+                                    first.0,
+                                    divergence));
+            return
+        }
+    }
 
-    // Code that needs to instantiate the same function recursively
-    // more than the recursion limit is assumed to be causing an
-    // infinite expansion.
-    if recursion_depth > tcx.sess.recursion_limit.get() {
-        let error = format!("reached the recursion limit while instantiating `{}`",
-                            instance);
-        if let Some(node_id) = tcx.hir.as_local_node_id(def_id) {
-            tcx.sess.span_fatal(tcx.hir.span(node_id), &error);
-        } else {
-            tcx.sess.fatal(&error);
+    // Both walks found the same items, so the inlining maps can only differ
+    // in the sources recorded for them.
+    if first_inlining_map.len() != second_inlining_map.len() {
+        scx.sess().err("the inlining map is not deterministic: the walks recorded \
+                        inlining candidates for a different number of items");
+    }
+}
+
+// Describes the first difference between two sorted lists of item names.
+fn first_divergence(first: &[String], second: &[String]) -> Option<String> {
+    for (a, b) in first.iter().zip(second.iter()) {
+        if a != b {
+            return Some(format!("first walk found `{}` where second walk found `{}`", a, b));
         }
     }
 
-    recursion_depths.insert(def_id, recursion_depth + 1);
+    if first.len() > second.len() {
+        Some(format!("only the first walk found `{}`", first[second.len()]))
+    } else if second.len() > first.len() {
+        Some(format!("only the second walk found `{}`", second[first.len()]))
+    } else {
+        None
+    }
+}
 
-    (def_id, recursion_depth)
+// The mutable state of the walk over the translation item graph.
+struct CollectionState<'tcx> {
+    visited: FxHashSet<TransItem<'tcx>>,
+    recursion_depths: RecursionDepths,
+    recursion_limit_errors: RecursionLimitErrors<'tcx>,
+    // The function instances currently being searched, innermost last.
+    instance_stack: Vec<Instance<'tcx>>,
+    inlining_map: InliningMap<'tcx>,
+    fuel: CollectionFuel<'tcx>,
+    type_length_overflow: TypeLengthOverflow<'tcx>,
+    // Maps upstream functions to the local items that reference them, so
+    // that diagnostics about the former can point at the latter. Only
+    // recorded if the `monomorphization_bloat` lint can fire for them.
+    upstream_users: Option<DefIdMap<FxHashSet<DefId>>>,
+    drop_recursion_warning: DropRecursionWarning<'tcx>,
+    // Caches the result of `recursion_limit_for()`.
+    recursion_limits: DefIdMap<usize>,
+    // Only recorded if anything is going to look at it.
+    references: Option<ItemReferences<'tcx>>,
+    // The root that the current walk of `collect_items_rec()` started from.
+    current_root: Option<TransItem<'tcx>>,
+    // Maps every collected item that is not a root to the item it has first
+    // been discovered from. Only recorded for
+    // `-Z print-cross-crate-mono-chains`.
+    first_users: Option<FxHashMap<TransItem<'tcx>, TransItem<'tcx>>>,
+    // Caches the result of `promoted_mir_complete()`.
+    promoted_mir_complete: DefIdMap<bool>,
+    mir_uses: MirUseCache<'tcx>,
+    memos: CollectionMemos<'tcx>,
+    // Whether to drop the neighbours an item references more than once
+    // before searching them. Always the case, except to check that it makes
+    // no difference.
+    dedup_neighbours: bool,
+    // The neighbour lists of the items that are not being searched at the
+    // moment, cleared, so that their memory is reused for the next ones.
+    // There are never more of them than the walk is deep.
+    neighbour_buffers: Vec<Vec<TransItem<'tcx>>>,
+    // Used to deduplicate the neighbours of each item in turn.
+    seen_neighbours: FxHashSet<TransItem<'tcx>>,
+    // The entry function of an executable and the start lang item that the
+    // `main` shim translated along with it calls. The shim is translated in
+    // the codegen unit of the entry function, so an inlinable start function
+    // has to end up there as well.
+    entry_edge: Option<(Instance<'tcx>, Instance<'tcx>)>,
+    // The lang items called by landing pads, see `unwind_lang_items()`.
+    unwind_lang_items: Vec<Instance<'tcx>>,
+    // Maps the collected items that an upstream crate also exports a
+    // translated copy of to that crate, see `exported_upstream_copy()`.
+    available_upstream: FxHashMap<TransItem<'tcx>, CrateNum>,
 }
 
-fn check_type_length_limit<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
-                                     instance: Instance<'tcx>)
-{
-    let type_length = instance.substs.types().flat_map(|ty| ty.walk()).count();
-    debug!(" => type length={}", type_length);
+impl<'tcx> CollectionState<'tcx> {
+    fn new(expected_items: usize,
+           mono_fuel: Option<usize>,
+           use_mir_cache: bool,
+           memos: CollectionMemos<'tcx>,
+           entry_edge: Option<(Instance<'tcx>, Instance<'tcx>)>,
+           unwind_lang_items: Vec<Instance<'tcx>>,
+           references: Option<ItemReferences<'tcx>>)
+           -> CollectionState<'tcx> {
+        CollectionState {
+            visited: FxHashSet::with_capacity_and_hasher(expected_items, Default::default()),
+            recursion_depths: RecursionDepths::new(),
+            recursion_limit_errors: RecursionLimitErrors::new(),
+            instance_stack: Vec::new(),
+            inlining_map: InliningMap::new(),
+            fuel: CollectionFuel::new(mono_fuel),
+            type_length_overflow: TypeLengthOverflow::new(),
+            upstream_users: None,
+            drop_recursion_warning: DropRecursionWarning::new(),
+            recursion_limits: DefIdMap(),
+            references: references,
+            current_root: None,
+            first_users: None,
+            promoted_mir_complete: DefIdMap(),
+            mir_uses: MirUseCache::new(use_mir_cache),
+            memos: memos,
+            dedup_neighbours: true,
+            neighbour_buffers: Vec::new(),
+            seen_neighbours: FxHashSet(),
+            entry_edge: entry_edge,
+            unwind_lang_items: unwind_lang_items,
+            available_upstream: FxHashMap(),
+        }
+    }
+}
 
-    // Rust code can easily create exponentially-long types using only a
-    // polynomial recursion depth. Even with the default recursion
-    // depth, you can easily get cases that take >2^60 steps to run,
-    // which means that rustc basically hangs.
-    //
-    // Bail out in these cases to avoid that bad user experience.
-    let type_length_limit = tcx.sess.type_length_limit.get();
-    if type_length > type_length_limit {
-        // The instance name is already known to be too long for rustc. Use
-        // `{:.64}` to avoid blasting the user's terminal with thousands of
-        // lines of type-name.
-        let instance_name = instance.to_string();
-        let msg = format!("reached the type-length limit while instantiating `{:.64}...`",
-                          instance_name);
-        let mut diag = if let Some(node_id) = tcx.hir.as_local_node_id(instance.def_id()) {
-            tcx.sess.struct_span_fatal(tcx.hir.span(node_id), &msg)
-        } else {
-            tcx.sess.struct_fatal(&msg)
-        };
+// Memoizes the answers to the questions that collection asks over and over
+// about the same types and items. The answers never change during a walk, and
+// each walk has memos of its own, see `verify_determinism()`.
+struct CollectionMemos<'tcx> {
+    // See `resolve_drop_in_place()`.
+    drop_in_place_instances: RefCell<FxHashMap<Ty<'tcx>, Instance<'tcx>>>,
+    vtables: RefCell<VtableCache<'tcx>>,
+    // Whether each upstream item needs to be translated locally, see
+    // `should_trans_locally()`.
+    upstream_trans_locally: RefCell<DefIdMap<bool>>,
+}
 
-        diag.note(&format!(
-            "consider adding a `#![type_length_limit=\"{}\"]` attribute to your crate",
-            type_length_limit*2));
-        diag.emit();
-        tcx.sess.abort_if_errors();
+impl<'tcx> CollectionMemos<'tcx> {
+    fn new() -> CollectionMemos<'tcx> {
+        CollectionMemos {
+            drop_in_place_instances: RefCell::new(FxHashMap()),
+            vtables: RefCell::new(VtableCache::new()),
+            upstream_trans_locally: RefCell::new(DefIdMap()),
+        }
     }
 }
 
-struct MirNeighborCollector<'a, 'tcx: 'a> {
-    scx: &'a SharedCrateContext<'a, 'tcx>,
-    mir: &'a mir::Mir<'tcx>,
-    output: &'a mut Vec<TransItem<'tcx>>,
-    param_substs: &'tcx Substs<'tcx>
+// The number of items collected per item in the HIR of the crate, roughly.
+// Generic items are usually instantiated a few times, while modules and the
+// like are not collected at all.
+const EXPECTED_ITEMS_PER_HIR_ITEM: usize = 2;
+
+// Guesses how many translation items will be collected, so that the set of
+// visited items does not have to grow (and rehash everything) over and over
+// while the graph is walked.
+fn expected_item_count<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                 roots: &[TransItem<'tcx>])
+                                 -> usize {
+    let krate = scx.tcx().hir.krate();
+    let hir_items = krate.items.len() + krate.trait_items.len() + krate.impl_items.len();
+    cmp::max(hir_items * EXPECTED_ITEMS_PER_HIR_ITEM, roots.len())
 }
 
-impl<'a, 'tcx> MirVisitor<'tcx> for MirNeighborCollector<'a, 'tcx> {
+// The ways in which one translation item can refer to another.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ReferenceKind {
+    // The target is called directly.
+    Call,
+    // The target is reified to a function pointer.
+    Reify,
+    // The target ends up in a vtable.
+    Vtable,
+}
 
-    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
-        debug!("visiting rvalue {:?}", *rvalue);
+// Records how the collected items are referenced across the whole item graph.
+struct ItemReferences<'tcx> {
+    called: FxHashSet<TransItem<'tcx>>,
+    // The first place where the address of an item has been taken.
+    address_taken: FxHashMap<TransItem<'tcx>, (ReferenceKind, Span)>,
+    // The trait methods that are called through a trait object somewhere.
+    virtually_called: FxHashSet<DefId>,
+    // The methods put into the vtable built by a trait object coercion, along
+    // with the trait methods they implement, and the function containing the
+    // coercion. Keyed by the coercion and its concrete source and trait object
+    // target types.
+    // The method lists are shared with the vtable cache.
+    vtable_casts: FxHashMap<(Span, Ty<'tcx>, Ty<'tcx>),
+                            (DefId, Rc<Vec<(DefId, TransItem<'tcx>)>>)>,
+    // Whether to record virtual calls and vtable casts at all, which only the
+    // `uncalled_vtable_methods` lint looks at.
+    check_vtables: bool,
+    // Whether the lint is allowed in each function containing a coercion.
+    vtable_lint_allowed: DefIdMap<bool>,
+}
 
-        match *rvalue {
-            // When doing an cast from a regular pointer to a fat pointer, we
-            // have to instantiate all methods of the trait being cast to, so we
-            // can build the appropriate vtable.
-            mir::Rvalue::Cast(mir::CastKind::Unsize, ref operand, target_ty) => {
-                let target_ty = monomorphize::apply_param_substs(self.scx,
-                                                                 self.param_substs,
-                                                                 &target_ty);
-                let source_ty = operand.ty(self.mir, self.scx.tcx());
-                let source_ty = monomorphize::apply_param_substs(self.scx,
-                                                                 self.param_substs,
-                                                                 &source_ty);
-                let (source_ty, target_ty) = find_vtable_types_for_unsizing(self.scx,
-                                                                            source_ty,
-                                                                            target_ty);
-                // This could also be a different Unsize instruction, like
-                // from a fixed sized array to a slice. But we are only
-                // interested in things that produce a vtable.
-                if target_ty.is_trait() && !source_ty.is_trait() {
-                    create_trans_items_for_vtable_methods(self.scx,
-                                                          target_ty,
-                                                          source_ty,
-                                                          self.output);
-                }
-            }
-            mir::Rvalue::Cast(mir::CastKind::ReifyFnPointer, ref operand, _) => {
-                let fn_ty = operand.ty(self.mir, self.scx.tcx());
-                let fn_ty = monomorphize::apply_param_substs(
-                    self.scx,
-                    self.param_substs,
-                    &fn_ty);
-                visit_fn_use(self.scx, fn_ty, false, &mut self.output);
-            }
-            mir::Rvalue::Cast(mir::CastKind::ClosureFnPointer, ref operand, _) => {
-                let source_ty = operand.ty(self.mir, self.scx.tcx());
-                match source_ty.sty {
-                    ty::TyClosure(def_id, substs) => {
-                        let instance = monomorphize::resolve_closure(
-                            self.scx, def_id, substs, ty::ClosureKind::FnOnce);
-                        self.output.push(create_fn_trans_item(instance));
-                    }
-                    _ => bug!(),
-                }
-            }
-            mir::Rvalue::Box(..) => {
-                let tcx = self.scx.tcx();
-                let exchange_malloc_fn_def_id = tcx
-                    .lang_items
-                    .require(ExchangeMallocFnLangItem)
-                    .unwrap_or_else(|e| self.scx.sess().fatal(&e));
-                let instance = Instance::mono(tcx, exchange_malloc_fn_def_id);
-                if should_trans_locally(tcx, &instance) {
-                    self.output.push(create_fn_trans_item(instance));
-                }
-            }
-            _ => { /* not interesting */ }
+impl<'tcx> ItemReferences<'tcx> {
+    fn new(check_vtables: bool) -> ItemReferences<'tcx> {
+        ItemReferences {
+            called: FxHashSet(),
+            address_taken: FxHashMap(),
+            virtually_called: FxHashSet(),
+            vtable_casts: FxHashMap(),
+            check_vtables: check_vtables,
+            vtable_lint_allowed: DefIdMap(),
         }
+    }
 
-        self.super_rvalue(rvalue, location);
+    fn record(&mut self, targets: &[TransItem<'tcx>], kind: ReferenceKind, span: Span) {
+        for &target in targets {
+            if kind == ReferenceKind::Call {
+                self.called.insert(target);
+            } else {
+                self.address_taken.entry(target).or_insert((kind, span));
+            }
+        }
     }
 
-    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: Location) {
-        debug!("visiting constant {:?} @ {:?}", *constant, location);
+    fn record_virtual_call(&mut self, trait_method: DefId) {
+        if self.check_vtables {
+            self.virtually_called.insert(trait_method);
+        }
+    }
 
-        if let ty::TyFnDef(..) = constant.ty.sty {
-            // function definitions are zero-sized, and only generate
-            // IR when they are called/reified.
-            self.super_constant(constant, location);
+    // Coercions in functions that allow the lint are left out. The level is
+    // looked up once per function.
+    fn record_vtable_cast<'a>(&mut self,
+                              tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                              span: Span,
+                              source_ty: Ty<'tcx>,
+                              target_ty: Ty<'tcx>,
+                              user: DefId,
+                              methods: Rc<Vec<(DefId, TransItem<'tcx>)>>) {
+        if !self.check_vtables {
             return
         }
 
-        if let mir::Literal::Item { def_id, substs } = constant.literal {
-            let substs = monomorphize::apply_param_substs(self.scx,
-                                                          self.param_substs,
-                                                          &substs);
-            let instance = monomorphize::resolve(self.scx, def_id, substs);
-            collect_neighbours(self.scx, instance, self.output);
+        let allowed = *self.vtable_lint_allowed.entry(user).or_insert_with(|| {
+            item_level_source(tcx, user, UNCALLED_VTABLE_METHODS).0 == lint::Allow
+        });
+        if !allowed {
+            self.vtable_casts.entry((span, source_ty, target_ty)).or_insert((user, methods));
         }
+    }
+}
 
-        self.super_constant(constant, location);
+/// Implements `-Z mono-fuel=N`, a debugging aid for bisecting which
+/// translation item triggers a bug further down the pipeline: only the first
+/// `N` items discovered are added to the translation item graph, everything
+/// found afterwards is neither added nor searched for neighbors, it is only
+/// counted in the warning reported at the end of collection.
+struct CollectionFuel<'tcx> {
+    limit: Option<usize>,
+    consumed: usize,
+    last_item: Option<TransItem<'tcx>>,
+    skipped: FxHashSet<TransItem<'tcx>>,
+}
+
+impl<'tcx> CollectionFuel<'tcx> {
+    fn new(limit: Option<usize>) -> CollectionFuel<'tcx> {
+        CollectionFuel {
+            limit: limit,
+            consumed: 0,
+            last_item: None,
+            skipped: FxHashSet(),
+        }
     }
 
-    fn visit_terminator_kind(&mut self,
-                             block: mir::BasicBlock,
-                             kind: &mir::TerminatorKind<'tcx>,
-                             location: Location) {
-        let tcx = self.scx.tcx();
-        match *kind {
-            mir::TerminatorKind::Call { ref func, .. } => {
-                let callee_ty = func.ty(self.mir, tcx);
-                let callee_ty = monomorphize::apply_param_substs(
-                    self.scx, self.param_substs, &callee_ty);
-                visit_fn_use(self.scx, callee_ty, true, &mut self.output);
-            }
-            mir::TerminatorKind::Drop { ref location, .. } |
-            mir::TerminatorKind::DropAndReplace { ref location, .. } => {
-                let ty = location.ty(self.mir, self.scx.tcx())
-                    .to_ty(self.scx.tcx());
-                let ty = monomorphize::apply_param_substs(self.scx,
-                                                          self.param_substs,
-                                                          &ty);
-                visit_drop_use(self.scx, ty, true, self.output);
+    // Returns false if `item` must not be collected because the fuel has run
+    // out.
+    fn consume(&mut self, item: TransItem<'tcx>) -> bool {
+        match self.limit {
+            Some(limit) if self.consumed >= limit => {
+                self.skipped.insert(item);
+                false
+            }
+            _ => {
+                self.consumed += 1;
+                self.last_item = Some(item);
+                true
             }
-            mir::TerminatorKind::Goto { .. } |
-            mir::TerminatorKind::SwitchInt { .. } |
-            mir::TerminatorKind::Resume |
-            mir::TerminatorKind::Return |
-            mir::TerminatorKind::Unreachable |
-            mir::TerminatorKind::Assert { .. } => {}
         }
-
-        self.super_terminator_kind(block, kind, location);
     }
-}
 
-fn visit_drop_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
-                            ty: ty::Ty<'tcx>,
-                            is_direct_call: bool,
-                            output: &mut Vec<TransItem<'tcx>>)
-{
-    let instance = monomorphize::resolve_drop_in_place(scx, ty);
-    visit_instance_use(scx, instance, is_direct_call, output);
-}
+    fn report<'a>(&self, scx: &SharedCrateContext<'a, 'tcx>) {
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => return,
+        };
 
-fn visit_fn_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
-                          ty: ty::Ty<'tcx>,
-                          is_direct_call: bool,
-                          output: &mut Vec<TransItem<'tcx>>)
-{
-    if let ty::TyFnDef(def_id, substs, _) = ty.sty {
-        let instance = monomorphize::resolve(scx, def_id, substs);
-        visit_instance_use(scx, instance, is_direct_call, output);
+        if let Some(last_item) = self.last_item {
+            scx.sess().note_without_error(&format!("mono-fuel: translation item #{} is `{}`",
+                                                   self.consumed,
+                                                   last_item.to_string(scx.tcx())));
+        }
+
+        if self.skipped.is_empty() {
+            scx.sess().note_without_error(&format!("mono-fuel: all {} translation items were \
+                                                    collected within the limit of {}",
+                                                   self.consumed,
+                                                   limit));
+        } else {
+            scx.sess().warn(&format!("-Z mono-fuel={} stopped translation item collection, \
+                                      {} further items were not collected. The result of this \
+                                      compilation is intentionally incomplete and will likely \
+                                      fail to link.",
+                                     limit,
+                                     self.skipped.len()));
+        }
     }
 }
 
-fn visit_instance_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
-                                instance: ty::Instance<'tcx>,
-                                is_direct_call: bool,
-                                output: &mut Vec<TransItem<'tcx>>)
-{
+// Find all non-generic items by walking the HIR. These items serve as roots to
+// start monomorphizing from.
+// Also records the provided trait methods instantiated eagerly for impls in
+// `default_impls`, see `EagerDefaultImpl`.
+fn collect_roots<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                           memos: &CollectionMemos<'tcx>,
+                           mode: TransItemCollectionMode,
+                           default_impls: &mut Vec<EagerDefaultImpl<'tcx>>)
+                           -> Vec<TransItem<'tcx>> {
+    debug!("Collecting roots");
+    let mut roots = Vec::new();
+
+    {
+        let mut visitor = RootCollector {
+            scx: scx,
+            memos: memos,
+            mode: mode,
+            output: &mut roots,
+            default_impls: default_impls,
+            exports_only: roots_limited_to_exports(scx.sess()),
+        };
+
+        scx.tcx().hir.krate().visit_all_item_likes(&mut visitor);
+    }
+
+    roots
+}
+
+// Makes the entry function and the start lang item roots. The entry function
+// usually is one already, but not if it is a `const fn`, see
+// `is_compile_time_only()`. The start lang item only needs to be translated
+// here if it can be inlined.
+fn add_entry_roots<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                             entry_fn_instances: Option<(Instance<'tcx>, Option<Instance<'tcx>>)>,
+                             roots: &mut Vec<TransItem<'tcx>>) {
+    if let Some((entry_fn, start_fn)) = entry_fn_instances {
+        let entry_items = Some(entry_fn).into_iter().chain(start_fn);
+        for item in entry_items.map(create_fn_trans_item) {
+            if !roots.contains(&item) {
+                debug!("add_entry_roots: adding {}", item.to_string(scx.tcx()));
+                roots.push(item);
+            }
+        }
+    }
+}
+
+// The user's entry function of an executable, along with the instance of the
+// start lang item that the `main` shim calls with it, if that is translated
+// locally. `#[start]` functions are called by the shim directly. Libraries and
+// `#![no_main]` crates have no entry function.
+fn entry_fn_instances<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                memos: &CollectionMemos<'tcx>)
+                                -> Option<(Instance<'tcx>, Option<Instance<'tcx>>)> {
+    let tcx = scx.tcx();
+    if !scx.sess().crate_types.borrow().contains(&config::CrateTypeExecutable) {
+        return None;
+    }
+    let entry_fn = match *scx.sess().entry_fn.borrow() {
+        Some((node_id, _)) => Instance::mono(tcx, tcx.hir.local_def_id(node_id)),
+        None => return None,
+    };
+
+    let start_fn = match (scx.sess().entry_type.get(), tcx.lang_items.start_fn()) {
+        (Some(config::EntryMain), Some(start_def_id)) => {
+            let start_fn = Instance::mono(tcx, start_def_id);
+            if should_trans_locally(scx, memos, &start_fn) {
+                Some(start_fn)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+
+    Some((entry_fn, start_fn))
+}
+
+// The lang items that landing pads refer to without any edge in the MIR: the
+// personality function and, on targets with a custom one, the function
+// resuming unwinding. They become neighbours of the functions with cleanup
+// blocks. Only local definitions are collected, and nothing without landing
+// pads.
+fn unwind_lang_items<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                               memos: &CollectionMemos<'tcx>)
+                               -> Vec<Instance<'tcx>> {
+    let tcx = scx.tcx();
+    let sess = scx.sess();
+    if sess.no_landing_pads() {
+        return Vec::new();
+    }
+
+    let mut def_ids = Vec::new();
+    // MSVC targets use the personality function of the CRT instead, see
+    // `CrateContext::eh_personality()`.
+    if !base::wants_msvc_seh(sess) {
+        def_ids.extend(tcx.lang_items.eh_personality());
+    }
+    if sess.target.target.options.custom_unwind_resume {
+        def_ids.extend(tcx.lang_items.eh_unwind_resume());
+    }
+    def_ids.into_iter()
+           .map(|def_id| Instance::mono(tcx, def_id))
+           .filter(|instance| should_trans_locally(scx, memos, instance))
+           .collect()
+}
+
+/// The symbols listed in the file given to `-Z export-list`. The file is
+/// expected to be a linker version script like
+///
+/// ```text
+/// VERS_1.0 {
+///     global:
+///         foo;
+///         bar;
+///     local: *;
+/// };
+/// ```
+///
+/// with any number of version nodes, named or not, but a plain list of symbol
+/// names, one per line, works as well. Symbols in `local:` sections and
+/// wildcard patterns are ignored. `extern "C++"` blocks and the like are not
+/// supported, their patterns are not symbol names.
+struct ExportList {
+    path: String,
+    symbols: Vec<String>,
+}
+
+impl ExportList {
+    fn load(sess: &Session, path: &str) -> ExportList {
+        let mut contents = String::new();
+        if let Err(e) = File::open(path).and_then(|mut file| file.read_to_string(&mut contents)) {
+            sess.fatal(&format!("couldn't read export list `{}`: {}", path, e));
+        }
+
+        let symbols = ExportList::parse(&contents).unwrap_or_else(|msg| {
+            sess.fatal(&format!("couldn't parse export list `{}`: {}", path, msg))
+        });
+
+        ExportList {
+            path: path.to_string(),
+            symbols: symbols,
+        }
+    }
+
+    // Splits a version script into names, quoted strings and the punctuation
+    // `{`, `}`, `;` and `:`, leaving out comments.
+    fn tokenize(contents: &str) -> Result<Vec<&str>, String> {
+        let mut tokens = Vec::new();
+        let mut rest = contents;
+
+        loop {
+            rest = rest.trim_left();
+            let next = match rest.chars().next() {
+                Some(c) => c,
+                None => return Ok(tokens),
+            };
+
+            let len = if next == '#' {
+                rest.find('\n').unwrap_or(rest.len())
+            } else if rest.starts_with("/*") {
+                match rest.find("*/") {
+                    Some(end) => end + 2,
+                    None => return Err("unterminated comment".to_string()),
+                }
+            } else if next == '"' {
+                match rest[1..].find('"') {
+                    Some(end) => {
+                        tokens.push(&rest[..end + 2]);
+                        end + 2
+                    }
+                    None => return Err("unterminated string".to_string()),
+                }
+            } else if "{};:".contains(next) {
+                tokens.push(&rest[..1]);
+                1
+            } else {
+                let end = rest.find(|c: char| c.is_whitespace() || "{};:\"#".contains(c))
+                              .unwrap_or(rest.len());
+                tokens.push(&rest[..end]);
+                end
+            };
+
+            rest = &rest[len..];
+        }
+    }
+
+    fn parse(contents: &str) -> Result<Vec<String>, String> {
+        let tokens = ExportList::tokenize(contents)?;
+        let mut symbols = Vec::new();
+
+        // A plain list of symbol names.
+        if !tokens.contains(&"{") {
+            symbols.extend(tokens.into_iter()
+                                 .filter(|&token| token != ";")
+                                 .map(|token| token.to_string()));
+            return Ok(symbols);
+        }
+
+        let mut tokens = tokens.into_iter().peekable();
+        let mut in_node = false;
+        let mut local = false;
+
+        while let Some(token) = tokens.next() {
+            if !in_node {
+                // Outside of a version node, there are only the names of
+                // nodes and of the nodes they depend on.
+                match token {
+                    "{" => {
+                        in_node = true;
+                        local = false;
+                    }
+                    "}" | ":" => return Err(format!("unexpected `{}`", token)),
+                    _ => {}
+                }
+                continue;
+            }
+
+            match token {
+                "}" => in_node = false,
+                ";" => {}
+                "global" | "local" if tokens.peek() == Some(&":") => {
+                    tokens.next();
+                    local = token == "local";
+                }
+                "extern" => {
+                    return Err(format!("`extern {}` blocks are not supported",
+                                       tokens.peek().cloned().unwrap_or("")));
+                }
+                "{" | ":" => return Err(format!("unexpected `{}`", token)),
+                _ if local || token.contains('*') || token.contains('?') ||
+                     token.contains('[') => {}
+                _ => symbols.push(token.trim_matches('"').to_string()),
+            }
+        }
+
+        if in_node {
+            return Err("unterminated version node".to_string());
+        }
+
+        Ok(symbols)
+    }
+}
+
+// Makes the monomorphic items listed in the export list roots of the
+// translation item graph. These are the items that only eager collection
+// would pick up, like provided trait methods that are never called.
+fn add_export_list_roots<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                   memos: &CollectionMemos<'tcx>,
+                                   export_list: &ExportList,
+                                   roots: &mut Vec<TransItem<'tcx>>) {
+    let listed: FxHashSet<&str> = export_list.symbols.iter().map(|s| &s[..]).collect();
+    let known: FxHashSet<_> = roots.iter().cloned().collect();
+
+    for item in collect_roots(scx, memos, TransItemCollectionMode::Eager, &mut Vec::new()) {
+        if !known.contains(&item) && listed.contains(&item.compute_symbol_name(scx)[..]) {
+            debug!("add_export_list_roots: adding {}", item.to_string(scx.tcx()));
+            roots.push(item);
+        }
+    }
+}
+
+// Reports the symbols in the export list that none of the collected items
+// is translated to, they would make the linker fail later on.
+fn check_export_list<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                               export_list: &ExportList,
+                               items: &FxHashSet<TransItem<'tcx>>) {
+    let symbols: FxHashSet<String> = items.iter()
+                                          .map(|item| item.compute_symbol_name(scx))
+                                          .collect();
+
+    for symbol in &export_list.symbols {
+        if !symbols.contains(symbol) {
+            scx.sess().err(&format!("symbol `{}` in the export list `{}` does not correspond \
+                                     to any item translated in this crate",
+                                    symbol,
+                                    export_list.path));
+        }
+    }
+
+    scx.sess().abort_if_errors();
+}
+
+// Collect all monomorphized translation items reachable from `starting_point`
+fn collect_items_rec<'a, 'tcx: 'a>(scx: &SharedCrateContext<'a, 'tcx>,
+                                   starting_point: TransItem<'tcx>,
+                                   state: &mut CollectionState<'tcx>) {
+    if state.visited.contains(&starting_point) {
+        // We've been here already, no need to search again.
+        return;
+    }
+    if !state.fuel.consume(starting_point) {
+        debug!("collect_items_rec({}): out of mono-fuel",
+               starting_point.to_string(scx.tcx()));
+        return;
+    }
+    let stats = scx.collection_stats();
+    let visited_capacity = state.visited.capacity();
+    state.visited.insert(starting_point);
+    if state.visited.capacity() != visited_capacity {
+        stats.bump(&stats.visited_set_growths);
+    }
+    debug!("BEGIN collect_items_rec({})", starting_point.to_string(scx.tcx()));
+
+    if let Some(limit) = scx.collection_flags().trans_item_limit {
+        if state.visited.len() > limit {
+            // This does not return, so the partially built inlining map is
+            // never looked at.
+            report_trans_item_limit(scx, &state.visited, limit);
+        }
+    }
+
+    let mut neighbors = state.neighbour_buffers.pop().unwrap_or_else(|| {
+        stats.bump(&stats.neighbour_buffers_allocated);
+        Vec::new()
+    });
+    let recursion_depth_reset;
+    let mut segment_reset = None;
+
+    match starting_point {
+        TransItem::Static(node_id) => {
+            recursion_depth_reset = None;
+            collect_static_item(scx, node_id, &mut neighbors, state);
+        }
+        TransItem::Fn(instance) => {
+            // Sanity check whether this ended up being collected accidentally
+            debug_assert!(should_trans_locally(scx, &state.memos, &instance));
+
+            if let Some(cnum) = scx.exported_upstream_copy(&instance) {
+                state.available_upstream.insert(starting_point, cnum);
+            }
+
+            // Errors found for instances reached through an instance of an
+            // erroneous function are mere consequences of that error.
+            let follows_error = follows_erroneous_function(&state.instance_stack,
+                                                           &state.recursion_limit_errors,
+                                                           &state.type_length_overflow);
+
+            // Keep track of the monomorphization recursion depth
+            let (reset, within_recursion_limit, recursion_depth) =
+                check_recursion_limit(scx.tcx(),
+                                      instance,
+                                      &mut state.recursion_depths,
+                                      &mut state.recursion_limits,
+                                      &mut state.recursion_limit_errors,
+                                      &mut state.drop_recursion_warning,
+                                      state.current_root,
+                                      follows_error);
+            recursion_depth_reset = Some(reset);
+
+            // The items reached from a closure start a new segment of the
+            // collection stack, see `RecursionDepths`.
+            if is_closure(scx.tcx(), instance.def_id()) {
+                segment_reset = Some(state.recursion_depths.enter_segment());
+            }
+
+            // Instances exceeding the recursion or type-length limit are not
+            // searched any further, their neighbors would only be deeper or
+            // longer still. Neither are instances that would continue an
+            // infinite instantiation cycle, nor bodies that refer to promoted
+            // constants we have no MIR for.
+            if within_recursion_limit &&
+               check_type_length_limit(scx,
+                                       instance,
+                                       state.instance_stack.last().cloned(),
+                                       &mut state.mir_uses,
+                                       &mut state.type_length_overflow,
+                                       state.current_root,
+                                       follows_error) &&
+               check_instantiation_cycle(scx.tcx(),
+                                         &state.instance_stack,
+                                         instance,
+                                         recursion_depth,
+                                         &mut state.recursion_limit_errors,
+                                         state.current_root,
+                                         follows_error) &&
+               promoted_mir_complete(scx.tcx(), instance, &mut state.promoted_mir_complete) {
+                let has_cleanup = collect_neighbours(scx,
+                                                     &state.memos,
+                                                     instance,
+                                                     false,
+                                                     &mut neighbors,
+                                                     &mut state.references,
+                                                     &mut state.mir_uses);
+                collect_promoted_values(scx, instance, &mut neighbors);
+
+                if has_cleanup {
+                    neighbors.extend(state.unwind_lang_items
+                                          .iter()
+                                          .map(|&lang_item| create_fn_trans_item(lang_item)));
+                }
+            }
+
+            if let Some((entry_fn, start_fn)) = state.entry_edge {
+                if instance == entry_fn {
+                    neighbors.push(create_fn_trans_item(start_fn));
+                }
+            }
+
+            state.instance_stack.push(instance);
+        }
+        TransItem::Promoted(..) => {
+            // The body of a promoted value is searched together with the body
+            // of the function it belongs to.
+            recursion_depth_reset = None;
+        }
+    }
+
+    if state.dedup_neighbours {
+        // The same callee, drop glue or vtable method is often referenced
+        // many times by one item. This is the one place where duplicates are
+        // dropped, before they are recorded as inlining candidates and
+        // searched. Keep the first occurrence of each neighbour, so that the
+        // order of the walk stays the same.
+        let seen = &mut state.seen_neighbours;
+        seen.clear();
+        neighbors.retain(|&neighbour| seen.insert(neighbour));
+    }
+
+    record_inlining_canditates(scx.tcx(),
+                               starting_point,
+                               &neighbors[..],
+                               &mut state.inlining_map);
+    if let Some(ref mut upstream_users) = state.upstream_users {
+        record_upstream_users(scx.tcx(), starting_point, &neighbors[..], upstream_users);
+    }
+
+    if let Some(ref mut first_users) = state.first_users {
+        for &neighbour in &neighbors {
+            if !state.visited.contains(&neighbour) {
+                first_users.entry(neighbour).or_insert(starting_point);
+            }
+        }
+    }
+
+    for &neighbour in &neighbors {
+        collect_items_rec(scx, neighbour, state);
+    }
+
+    neighbors.clear();
+    state.neighbour_buffers.push(neighbors);
+
+    if let TransItem::Fn(_) = starting_point {
+        state.instance_stack.pop();
+    }
+
+    if let Some(segment) = segment_reset {
+        state.recursion_depths.leave_segment(segment);
+    }
+
+    if let Some(def_id) = recursion_depth_reset {
+        state.recursion_depths.restore(def_id);
+        if Some(def_id) == scx.tcx().lang_items.drop_in_place_fn() {
+            state.drop_recursion_warning.leave();
+        }
+    }
+
+    debug!("END collect_items_rec({})", starting_point.to_string(scx.tcx()));
+}
+
+fn collect_static_item<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                 node_id: ast::NodeId,
+                                 neighbors: &mut Vec<TransItem<'tcx>>,
+                                 state: &mut CollectionState<'tcx>) {
+    let def_id = scx.tcx().hir.local_def_id(node_id);
+    if !check_static_is_monomorphic(scx, def_id) {
+        return;
+    }
+    let instance = Instance::mono(scx.tcx(), def_id);
+
+    // Sanity check whether this ended up being collected accidentally
+    debug_assert!(should_trans_locally(scx, &state.memos, &instance));
+
+    // Statics are never dropped, so there is no drop glue to collect for
+    // them, see the `static_destructors` lint.
+
+    // The initializer is evaluated at compile time, values promoted in it
+    // included, so these do not get translation items of their own. The
+    // items they refer to end up in the static and are collected here.
+    collect_neighbours(scx,
+                       &state.memos,
+                       instance,
+                       true,
+                       neighbors,
+                       &mut state.references,
+                       &mut state.mir_uses);
+}
+
+// Whether the type of a static is fully known. This is a defensive guard in
+// the spirit of a `span_bug!`: name resolution already rejects statics using
+// the type parameters of their surroundings (E0401), so no test reaches it.
+// Should such a static get through after all, it would have no single
+// instance to translate, and translating it anyway would fail far from the
+// cause. Reports an error at the static and returns false in that case.
+fn check_static_is_monomorphic<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                         def_id: DefId)
+                                         -> bool {
+    let tcx = scx.tcx();
+    let ty = tcx.item_type(def_id);
+    if !ty.needs_subst() {
+        return true;
+    }
+
+    tcx.sess.struct_span_err(local_item_span(tcx, def_id),
+                             &format!("the type `{}` of static `{}` depends on type parameters",
+                                      ty,
+                                      tcx.item_path_str(def_id)))
+            .note("there is no single instance of the static to translate")
+            .emit();
+    false
+}
+
+fn record_inlining_canditates<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                        caller: TransItem<'tcx>,
+                                        callees: &[TransItem<'tcx>],
+                                        inlining_map: &mut InliningMap<'tcx>) {
+    let is_inlining_candidate = |trans_item: &TransItem<'tcx>| {
+        trans_item.instantiation_mode(tcx) == InstantiationMode::LocalCopy
+    };
+
+    let inlining_candidates = callees.into_iter()
+                                     .map(|x| *x)
+                                     .filter(is_inlining_candidate);
+
+    inlining_map.record_inlining_canditates(caller, inlining_candidates);
+}
+
+// Aborts compilation because more than `-Z trans-item-limit` items were
+// collected, listing the functions with the most instances as the likely
+// culprits.
+fn report_trans_item_limit<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                     visited: &FxHashSet<TransItem<'tcx>>,
+                                     limit: usize) -> ! {
+    let tcx = scx.tcx();
+    let mut diag = tcx.sess.struct_fatal(&format!("reached the translation item limit of {}",
+                                                  limit));
+
+    let mut counts: Vec<_> = group_instances_by_def_id(visited.iter())
+        .into_iter()
+        .map(|(def_id, instances)| (instances.len(), def_id_to_string(tcx, def_id)))
+        .collect();
+    counts.sort_by(|a, b| b.cmp(a));
+
+    for &(count, ref name) in counts.iter().take(5) {
+        diag.note(&format!("`{}` has {} instance{}",
+                           name,
+                           count,
+                           if count == 1 { "" } else { "s" }));
+    }
+    diag.help("the limit can be changed with `-Z trans-item-limit=N`");
+    diag.emit();
+    tcx.sess.abort_if_errors();
+    bug!("reaching the translation item limit did not abort compilation")
+}
+
+fn record_upstream_users<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   user: TransItem<'tcx>,
+                                   neighbors: &[TransItem<'tcx>],
+                                   upstream_users: &mut DefIdMap<FxHashSet<DefId>>) {
+    let user_def_id = match user {
+        TransItem::Fn(instance) if instance.def_id().is_local() => instance.def_id(),
+        TransItem::Fn(_) |
+        TransItem::Promoted(..) => return,
+        TransItem::Static(node_id) => tcx.hir.local_def_id(node_id),
+    };
+
+    for neighbor in neighbors {
+        if let TransItem::Fn(instance) = *neighbor {
+            if !instance.def_id().is_local() {
+                upstream_users.entry(instance.def_id())
+                              .or_insert(FxHashSet())
+                              .insert(user_def_id);
+            }
+        }
+    }
+}
+
+// The number of instances of each function on the current collection stack,
+// which `check_recursion_limit()` compares to the recursion limit.
+//
+// Closures split the stack into segments: the depth of a closure counts all
+// its instances on the stack, but the depth of any other function only counts
+// its instances since the innermost closure. Recursion that passes through a
+// closure is thus limited by the depth of the closure alone. Without this,
+// code where generic functions and the closures they create alternate, as in
+// recursive-descent parsers built from combinators, would reach the limit on
+// the generic functions well before the structure is as deep as the limit.
+//
+// Most functions are never on the stack more than once, so their depth is
+// found without any lookup: the functions on the stack are also counted per
+// bucket of def-ids, and only if the bucket of a function is not empty is it
+// looked for. Recursion is mostly direct or nearly so, so only the innermost
+// few entries of the stack are searched first. Only functions that are pushed
+// while their bucket is not empty, that is, that may already be on the stack,
+// get an entry in `innermost`, so a function further down is found there if
+// it is on the stack more than once, and by searching the whole stack if not.
+struct RecursionDepths {
+    // The functions on the collection stack, innermost last, along with the
+    // segment they were entered in, their depth, and, if `set()` recorded the
+    // entry in `innermost`, the index it replaced there.
+    stack: Vec<(DefId, usize, usize, Option<Option<usize>>)>,
+    innermost: DefIdMap<usize>,
+    buckets: [u32; RECURSION_DEPTH_BUCKETS],
+    current_segment: usize,
+    segment_count: usize,
+    // For `-Z trans-collection-stats`: the lookups answered by an empty
+    // bucket, the stack entries searched by all others, and the entries
+    // recorded in `innermost`.
+    bucket_hits: usize,
+    entries_searched: usize,
+    entries_recorded: usize,
+}
+
+const RECURSION_DEPTH_BUCKETS: usize = 1024;
+
+// The number of innermost entries of the collection stack that
+// `RecursionDepths::get()` searches before it looks up the function.
+const RECURSION_DEPTH_SCAN: usize = 8;
+
+impl RecursionDepths {
+    fn new() -> RecursionDepths {
+        RecursionDepths {
+            stack: Vec::new(),
+            innermost: DefIdMap(),
+            buckets: [0; RECURSION_DEPTH_BUCKETS],
+            current_segment: 0,
+            segment_count: 0,
+            bucket_hits: 0,
+            entries_searched: 0,
+            entries_recorded: 0,
+        }
+    }
+
+    fn bucket(def_id: DefId) -> usize {
+        (def_id.index.as_usize() ^ def_id.krate.as_usize().wrapping_mul(31)) %
+            RECURSION_DEPTH_BUCKETS
+    }
+
+    fn get(&mut self, def_id: DefId, is_closure: bool) -> usize {
+        if self.buckets[RecursionDepths::bucket(def_id)] == 0 {
+            self.bucket_hits += 1;
+            return 0;
+        }
+
+        let scanned = cmp::min(self.stack.len(), RECURSION_DEPTH_SCAN);
+        let found = {
+            let innermost = self.stack[self.stack.len() - scanned..]
+                                .iter()
+                                .rev()
+                                .position(|&(other_def_id, ..)| other_def_id == def_id);
+            self.entries_searched += innermost.map_or(scanned, |position| position + 1);
+            match innermost {
+                Some(position) => Some(self.stack.len() - 1 - position),
+                None => match self.innermost.get(&def_id) {
+                    Some(&index) => Some(index),
+                    None => {
+                        // Either the function is on the stack only once, or
+                        // only other functions of its bucket are.
+                        let rest = self.stack.len() - scanned;
+                        let outer = self.stack[..rest]
+                                        .iter()
+                                        .rposition(|&(other_def_id, ..)| other_def_id == def_id);
+                        self.entries_searched += outer.map_or(rest, |index| rest - index);
+                        outer
+                    }
+                },
+            }
+        };
+
+        match found {
+            Some(index) => {
+                let (_, segment, depth, _) = self.stack[index];
+                if is_closure || segment == self.current_segment { depth } else { 0 }
+            }
+            None => 0,
+        }
+    }
+
+    // Pushes the depth of `def_id`, to be popped by `restore()`.
+    fn set(&mut self, def_id: DefId, depth: usize) {
+        let bucket = RecursionDepths::bucket(def_id);
+        let recorded = if self.buckets[bucket] > 0 {
+            self.entries_recorded += 1;
+            Some(self.innermost.insert(def_id, self.stack.len()))
+        } else {
+            None
+        };
+        self.buckets[bucket] += 1;
+        self.stack.push((def_id, self.current_segment, depth, recorded));
+    }
+
+    fn restore(&mut self, def_id: DefId) {
+        let (popped, _, _, recorded) = self.stack.pop().unwrap();
+        debug_assert_eq!(popped, def_id);
+        self.buckets[RecursionDepths::bucket(def_id)] -= 1;
+        match recorded {
+            Some(Some(index)) => { self.innermost.insert(def_id, index); }
+            Some(None) => { self.innermost.remove(&def_id); }
+            None => {}
+        }
+    }
+
+    // Starts a new segment, returning the current one for `leave_segment()`.
+    fn enter_segment(&mut self) -> usize {
+        let previous = self.current_segment;
+        self.segment_count += 1;
+        self.current_segment = self.segment_count;
+        previous
+    }
+
+    fn leave_segment(&mut self, previous: usize) {
+        self.current_segment = previous;
+    }
+}
+
+fn is_closure<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool {
+    tcx.def_key(def_id).disambiguated_data.data == DefPathData::ClosureExpr
+}
+
+// Whether `def_id` is a static of this crate that is declared inside the body
+// of a function or closure, rather than in a module.
+fn is_nested_static<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool {
+    if !def_id.is_local() {
+        return false;
+    }
+    let parent = match tcx.def_key(def_id).parent {
+        Some(index) => DefId { krate: def_id.krate, index: index },
+        None => return false,
+    };
+    match tcx.def_key(parent).disambiguated_data.data {
+        DefPathData::ValueNs(_) | DefPathData::ClosureExpr => true,
+        _ => false,
+    }
+}
+
+// Whether the static `def_id` of this crate is translated only along with the
+// items that refer to it, rather than being a root like most statics, unless
+// it is `#[used]` or has a fixed symbol name.
+fn is_collected_through_users<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool {
+    def_id.is_local() && (roots_limited_to_exports(tcx.sess) || is_nested_static(tcx, def_id))
+}
+
+// Whether the crate is only built as C-compatible libraries. Nothing but the
+// items with a fixed symbol name and the lang items of such a crate can be
+// referred to from outside of it, so only these are roots, and everything else
+// is translated only if it is reachable from them.
+fn roots_limited_to_exports(sess: &Session) -> bool {
+    let crate_types = sess.crate_types.borrow();
+    !crate_types.is_empty() && crate_types.iter().all(|&crate_type| {
+        crate_type == config::CrateTypeCdylib || crate_type == config::CrateTypeStaticlib
+    })
+}
+
+// Returns the function to restore the recursion depth of once its instance
+// has been searched, along with false if the instance must not be searched
+// because it exceeds the recursion limit, and the recursion depth of the
+// function before this instance. The error is not reported right away but only
+// once collection is complete, see `RecursionLimitErrors`.
+fn check_recursion_limit<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   instance: Instance<'tcx>,
+                                   recursion_depths: &mut RecursionDepths,
+                                   recursion_limits: &mut DefIdMap<usize>,
+                                   recursion_limit_errors: &mut RecursionLimitErrors<'tcx>,
+                                   drop_recursion_warning: &mut DropRecursionWarning<'tcx>,
+                                   root: Option<TransItem<'tcx>>,
+                                   follows_error: bool)
+                                   -> (DefId, bool, usize) {
+    let def_id = instance.def_id();
+    let recursion_depth = recursion_depths.get(def_id, is_closure(tcx, def_id));
+    let previous_depth = recursion_depth;
+    let recursion_limit = recursion_limit_for(tcx, def_id, recursion_limits);
+    debug!(" => recursion depth={}, limit={}", recursion_depth, recursion_limit);
+
+    let recursion_depth = if Some(def_id) == tcx.lang_items.drop_in_place_fn() {
+        drop_recursion_warning.enter(tcx, instance, recursion_limit);
+
+        // HACK: drop_in_place creates tight monomorphization loops. Give
+        // it more margin.
+        let recursion_depth = recursion_depth / 4;
+        drop_recursion_warning.max_adjusted_depth =
+            cmp::max(drop_recursion_warning.max_adjusted_depth, recursion_depth);
+        recursion_depth
+    } else {
+        recursion_depth
+    };
+
+    // Code that needs to instantiate the same function recursively
+    // more than the recursion limit is assumed to be causing an
+    // infinite expansion. Once that has been reported for a function, none
+    // of its instances are searched any more, so that we do not go on to
+    // expand every other branch of the runaway recursion.
+    let within_recursion_limit = recursion_depth <= recursion_limit &&
+                                 !recursion_limit_errors.reported.contains(&def_id);
+    if !within_recursion_limit {
+        recursion_limit_errors.record(instance,
+                                      RecursionError::Limit(recursion_limit),
+                                      root,
+                                      follows_error);
+    }
+
+    recursion_depths.set(def_id, recursion_depth + 1);
+
+    (def_id, within_recursion_limit, previous_depth)
+}
+
+// Returns false if `instance` continues an infinite instantiation cycle, see
+// `find_instantiation_cycle()`. Like the recursion limit error, the cycle is
+// reported once collection is complete. A cycle needs two earlier instances of
+// the function, so the collection stack is only searched if the recursion
+// depth of the function, as found by `check_recursion_limit()`, shows them.
+fn check_instantiation_cycle<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                       instance_stack: &[Instance<'tcx>],
+                                       instance: Instance<'tcx>,
+                                       recursion_depth: usize,
+                                       recursion_limit_errors: &mut RecursionLimitErrors<'tcx>,
+                                       root: Option<TransItem<'tcx>>,
+                                       follows_error: bool)
+                                       -> bool {
+    if recursion_depth < 2 {
+        return true;
+    }
+
+    match find_instantiation_cycle(tcx, instance_stack, instance) {
+        Some((first, second)) => {
+            recursion_limit_errors.record(instance,
+                                          RecursionError::Cycle(first, second),
+                                          root,
+                                          follows_error);
+            false
+        }
+        None => true,
+    }
+}
+
+// Looks for two earlier instances of the function of `instance` on the
+// collection stack that form a cycle with it: the second is reached from the
+// first through the same functions as `instance` from the second, and the type
+// arguments of each instance strictly contain those of the previous one. If
+// the functions along the way instantiate each other with type arguments built
+// from their own type parameters alone, the same code instantiated with ever
+// larger types leads to the same again, so such a cycle never ends, however
+// high the recursion limit is. Anything short of that, like chains that are
+// merely deep or that grow through associated types or trait methods, is left
+// to the recursion limit.
+fn find_instantiation_cycle<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                      instance_stack: &[Instance<'tcx>],
+                                      instance: Instance<'tcx>)
+                                      -> Option<(Instance<'tcx>, Instance<'tcx>)> {
+    let def_id = instance.def_id();
+    let mut occurrences = instance_stack.iter()
+                                        .enumerate()
+                                        .rev()
+                                        .filter(|&(_, other)| other.def_id() == def_id)
+                                        .map(|(index, _)| index);
+    let (second, first) = match (occurrences.next(), occurrences.next()) {
+        (Some(second), Some(first)) => (second, first),
+        _ => return None,
+    };
+
+    let path = |from: usize, to: usize| instance_stack[from + 1 .. to].iter().map(|i| i.def_id());
+    if second - first != instance_stack.len() - second ||
+       !path(first, second).eq(path(second, instance_stack.len())) {
+        return None
+    }
+
+    let cycle = &instance_stack[second..];
+    let (first, second) = (instance_stack[first], instance_stack[second]);
+    if grows_into(first, second) &&
+       grows_into(second, instance) &&
+       instantiates_without_impls(tcx, cycle) {
+        Some((first, second))
+    } else {
+        None
+    }
+}
+
+// Returns true if none of the bodies of `instances` refers to an associated
+// type, or calls a trait method, in terms of the type parameters. Both depend
+// on the impls selected for the type arguments, so through them, the type
+// arguments can grow for a while and still reach an impl that ends the chain.
+// The bodies of shims are only built for the types of the instance and cannot
+// tell.
+fn instantiates_without_impls<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                        instances: &[Instance<'tcx>])
+                                        -> bool {
+    instances.iter().all(|instance| {
+        match instance.def {
+            ty::InstanceDef::Item(_) => {
+                let mir = tcx.instance_mir(instance.def);
+                let mut finder = TraitCallFinder { tcx: tcx, found: false };
+                finder.visit_mir(&mir);
+                for promoted in &mir.promoted {
+                    finder.visit_mir(promoted);
+                }
+                !mir.has_projection_types() && !finder.found
+            }
+            _ => false,
+        }
+    })
+}
+
+// Finds references to trait methods whose impl is selected by the type
+// parameters of the body.
+struct TraitCallFinder<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    found: bool,
+}
+
+impl<'a, 'tcx> MirVisitor<'tcx> for TraitCallFinder<'a, 'tcx> {
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: Location) {
+        if let ty::TyFnDef(def_id, substs, _) = constant.ty.sty {
+            if self.tcx.trait_of_item(def_id).is_some() && substs.has_param_types() {
+                self.found = true;
+            }
+        }
+
+        self.super_constant(constant, location);
+    }
+}
+
+// Returns true if every type argument of `smaller` occurs in the respective
+// type argument of `larger`, and `larger` has the longer type arguments.
+fn grows_into<'tcx>(smaller: Instance<'tcx>, larger: Instance<'tcx>) -> bool {
+    let type_length = |instance: Instance<'tcx>| {
+        instance.substs.types().flat_map(|ty| ty.walk()).count()
+    };
+
+    smaller.substs.types().count() == larger.substs.types().count() &&
+    smaller.substs.types()
+                  .zip(larger.substs.types())
+                  .all(|(small, large)| large.walk().any(|ty| ty == small)) &&
+    type_length(larger) > type_length(smaller)
+}
+
+// The reasons an instance is not searched by `check_recursion_limit()` and
+// `check_instantiation_cycle()`.
+#[derive(Clone, Copy)]
+enum RecursionError<'tcx> {
+    // The recursion limit that the instance exceeds.
+    Limit(usize),
+    // The two earlier instances that the instance forms a cycle with.
+    Cycle(Instance<'tcx>, Instance<'tcx>),
+}
+
+// Returns true if an instance of a function that has been found to exceed a
+// limit is on the collection stack. The functions themselves are not searched
+// once they exceed a limit, but their other instances may have been.
+fn follows_erroneous_function<'tcx>(instance_stack: &[Instance<'tcx>],
+                                    recursion_limit_errors: &RecursionLimitErrors<'tcx>,
+                                    type_length_overflow: &TypeLengthOverflow<'tcx>)
+                                    -> bool {
+    let is_erroneous = |def_id| {
+        recursion_limit_errors.reported.contains(&def_id) ||
+        type_length_overflow.reported.contains(&def_id)
+    };
+
+    // Without errors, which is the common case, there is nothing to look for.
+    (!recursion_limit_errors.reported.is_empty() ||
+     !type_length_overflow.reported.is_empty()) &&
+    instance_stack.iter().any(|instance| is_erroneous(instance.def_id()))
+}
+
+// Keeps track of the functions that exceeded the recursion limit or
+// instantiate themselves in an infinite cycle. Every other instance on the way
+// would do so too if it were searched any further, so only the first one of
+// each function is reported, with a note about how many others were left out.
+// Functions that are only reached through an erroneous function are not
+// searched either, but not reported, see `follows_erroneous_function()`.
+struct RecursionLimitErrors<'tcx> {
+    reported: FxHashSet<DefId>,
+    // The first instance of each function that was not searched, in the
+    // order they were found, with the reason, the root it was reached from
+    // and the number of further instances omitted.
+    errors: Vec<(Instance<'tcx>, RecursionError<'tcx>, Option<TransItem<'tcx>>, usize)>,
+}
+
+impl<'tcx> RecursionLimitErrors<'tcx> {
+    fn new() -> RecursionLimitErrors<'tcx> {
+        RecursionLimitErrors {
+            reported: FxHashSet(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn record(&mut self,
+              instance: Instance<'tcx>,
+              error: RecursionError<'tcx>,
+              root: Option<TransItem<'tcx>>,
+              follows_error: bool) {
+        let def_id = instance.def_id();
+        if self.reported.insert(def_id) {
+            if !follows_error {
+                self.errors.push((instance, error, root, 0));
+            }
+        } else {
+            for error in self.errors.iter_mut().filter(|error| error.0.def_id() == def_id) {
+                error.3 += 1;
+            }
+        }
+    }
+
+    // Emits one error per function that was not searched.
+    fn report<'a>(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+        for &(instance, error, root, omitted) in &self.errors {
+            let def_id = instance.def_id();
+            let mut diag = match error {
+                RecursionError::Limit(_) => {
+                    struct_span_err!(tcx.sess,
+                                     local_item_span(tcx, def_id),
+                                     E0593,
+                                     "reached the recursion limit while instantiating `{}`",
+                                     instance_display_name(tcx, instance))
+                }
+                RecursionError::Cycle(..) => {
+                    struct_span_err!(tcx.sess,
+                                     local_item_span(tcx, def_id),
+                                     E0595,
+                                     "found an infinite instantiation cycle while \
+                                      instantiating `{}`",
+                                     instance_display_name(tcx, instance))
+                }
+            };
+            note_instance_origin(tcx, instance, root, &mut diag);
+
+            if let RecursionError::Cycle(first, second) = error {
+                diag.note(&format!("`{}` instantiates `{}`, which instantiates `{}` in the \
+                                    same way, so the type arguments would keep growing \
+                                    forever",
+                                   instance_display_name(tcx, first),
+                                   instance_display_name(tcx, second),
+                                   instance_display_name(tcx, instance)));
+            }
+
+            if omitted > 0 {
+                diag.note(&format!("{} further instantiation{} of this function omitted",
+                                   omitted,
+                                   if omitted == 1 { "" } else { "s" }));
+            }
+
+            // Raising the limit does not help with cycles. Otherwise, the
+            // limit might come from the function, its impl, or the crate.
+            if let RecursionError::Limit(recursion_limit) = error {
+                let limit_attrs = [Some(def_id), tcx.impl_of_method(def_id)]
+                    .iter()
+                    .filter_map(|&def_id| {
+                        def_id.and_then(|def_id| tcx.hir.as_local_node_id(def_id))
+                    })
+                    .map(|node_id| tcx.hir.attrs(node_id))
+                    .find(|attrs| attrs.iter().any(|attr| attr.check_name("recursion_limit")))
+                    .unwrap_or(&tcx.hir.krate().attrs[..]);
+                suggest_raising_limit(&mut diag,
+                                      limit_attrs,
+                                      "recursion_limit",
+                                      recursion_limit * 2);
+            }
+
+            diag.emit();
+        }
+    }
+}
+
+// Returns the recursion limit for instantiating `def_id`. A
+// `#[recursion_limit="N"]` attribute on the function itself or on its impl
+// overrides the limit of the crate. Malformed attributes have already been
+// reported by `check_attr` and are ignored here.
+fn recursion_limit_for<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                 def_id: DefId,
+                                 recursion_limits: &mut DefIdMap<usize>)
+                                 -> usize {
+    if let Some(&limit) = recursion_limits.get(&def_id) {
+        return limit;
+    }
+
+    let limit = {
+        let attr_limit = |def_id| -> Option<usize> {
+            tcx.get_attrs(def_id)
+               .iter()
+               .find(|attr| attr.check_name("recursion_limit"))
+               .and_then(|attr| attr.value_str())
+               .and_then(|s| s.as_str().parse().ok())
+        };
+
+        attr_limit(def_id)
+            .or_else(|| tcx.impl_of_method(def_id).and_then(|impl_def_id| attr_limit(impl_def_id)))
+            .unwrap_or(tcx.sess.recursion_limit.get())
+    };
+
+    recursion_limits.insert(def_id, limit);
+    limit
+}
+
+// Under `-Z verbose`, warns once when a chain of nested `drop_in_place`
+// instances exceeds the recursion limit and is only accepted because of the
+// extra margin `check_recursion_limit` gives to `drop_in_place`. Such chains
+// come from very deeply nested types or runaway drop glue, so the warning
+// names the outermost dropped type.
+struct DropRecursionWarning<'tcx> {
+    // The number of `drop_in_place` instances on the current collection
+    // stack, without any adjustment.
+    depth: usize,
+    // The deepest nesting seen, without and with the adjustment, for
+    // `-Z trans-collection-stats`.
+    max_depth: usize,
+    max_adjusted_depth: usize,
+    outermost_dropped_ty: Option<ty::Ty<'tcx>>,
+    emitted: bool,
+}
+
+impl<'tcx> DropRecursionWarning<'tcx> {
+    fn new() -> DropRecursionWarning<'tcx> {
+        DropRecursionWarning {
+            depth: 0,
+            max_depth: 0,
+            max_adjusted_depth: 0,
+            outermost_dropped_ty: None,
+            emitted: false,
+        }
+    }
+
+    fn enter<'a>(&mut self,
+                 tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                 instance: Instance<'tcx>,
+                 recursion_limit: usize) {
+        if self.depth == 0 {
+            self.outermost_dropped_ty = instance.substs.types().next();
+        }
+        self.depth += 1;
+        self.max_depth = cmp::max(self.max_depth, self.depth);
+        debug!(" => unadjusted drop_in_place depth={}", self.depth);
+
+        if self.emitted || self.depth <= recursion_limit || !tcx.sess.verbose() {
+            return
+        }
+        self.emitted = true;
+
+        let outermost_dropped_ty = match self.outermost_dropped_ty {
+            Some(ty) => ty.to_string(),
+            None => "<unknown>".to_string(),
+        };
+        tcx.sess.warn(&format!("drop glue for `{}` is nested {} levels deep, exceeding the \
+                                recursion limit of {}; this is only accepted because \
+                                `drop_in_place` is given extra margin",
+                               outermost_dropped_ty,
+                               self.depth,
+                               recursion_limit));
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+}
+
+// Returns false if `instance` exceeds the type-length limit. The error is not
+// reported right away but only once collection is complete, see
+// `TypeLengthOverflow`.
+fn check_type_length_limit<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                     instance: Instance<'tcx>,
+                                     caller: Option<Instance<'tcx>>,
+                                     mir_uses: &mut MirUseCache<'tcx>,
+                                     overflow: &mut TypeLengthOverflow<'tcx>,
+                                     root: Option<TransItem<'tcx>>,
+                                     follows_error: bool)
+                                     -> bool
+{
+    let type_length = instance.substs.types().flat_map(|ty| ty.walk()).count();
+    debug!(" => type length={}", type_length);
+
+    // Rust code can easily create exponentially-long types using only a
+    // polynomial recursion depth. Even with the default recursion
+    // depth, you can easily get cases that take >2^60 steps to run,
+    // which means that rustc basically hangs.
+    //
+    // Bail out in these cases to avoid that bad user experience.
+    if type_length > scx.tcx().sess.type_length_limit.get() {
+        // The span is only needed for the first instance of each function.
+        let call_span = if follows_error || overflow.reported.contains(&instance.def_id()) {
+            None
+        } else {
+            caller.and_then(|caller| local_call_span(scx, caller, instance, mir_uses))
+        };
+        overflow.record(instance, type_length, call_span, root, follows_error);
+        false
+    } else {
+        true
+    }
+}
+
+// Finds the call in the body of `caller` that instantiates `callee`, or the
+// cast of it to a function pointer, if `caller` is defined in this crate.
+fn local_call_span<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                             caller: Instance<'tcx>,
+                             callee: Instance<'tcx>,
+                             mir_uses: &mut MirUseCache<'tcx>)
+                             -> Option<Span> {
+    match caller.def {
+        ty::InstanceDef::Item(def_id) if def_id.is_local() => {}
+        _ => return None,
+    }
+
+    let uses = mir_uses.uses(scx.tcx(), caller.def);
+    for &(span, ref mir_use) in &uses.body {
+        let fn_ty = match *mir_use {
+            MirUse::Call(fn_ty) | MirUse::ReifyFnPointer(fn_ty) => fn_ty,
+            _ => continue,
+        };
+        let fn_ty = monomorphize::apply_param_substs(scx, caller.substs, &fn_ty);
+        if let ty::TyFnDef(def_id, substs, _) = fn_ty.sty {
+            if monomorphize::resolve(scx, def_id, substs) == callee {
+                return Some(span);
+            }
+        }
+    }
+    None
+}
+
+// Keeps track of the instances that exceeded the type-length limit, reported
+// once per function. Collection continues after the first one so that the
+// errors can suggest a limit that covers every instance in the crate instead
+// of making the user raise the limit one step at a time. Like with
+// `RecursionLimitErrors`, functions only reached through an erroneous
+// function are not reported.
+struct TypeLengthOverflow<'tcx> {
+    reported: FxHashSet<DefId>,
+    // The first instance of each function that exceeded the limit, with its
+    // type length, the call that instantiated it, the root it was reached
+    // from, the number of instances of the function that exceeded the limit
+    // and the longest of them.
+    overflows: Vec<(Instance<'tcx>, usize, Option<Span>, Option<TransItem<'tcx>>, usize, usize)>,
+    max_type_length: usize,
+}
+
+impl<'tcx> TypeLengthOverflow<'tcx> {
+    fn new() -> TypeLengthOverflow<'tcx> {
+        TypeLengthOverflow {
+            reported: FxHashSet(),
+            overflows: Vec::new(),
+            max_type_length: 0,
+        }
+    }
+
+    fn record(&mut self,
+              instance: Instance<'tcx>,
+              type_length: usize,
+              call_span: Option<Span>,
+              root: Option<TransItem<'tcx>>,
+              follows_error: bool) {
+        let def_id = instance.def_id();
+        if self.reported.insert(def_id) {
+            if !follows_error {
+                self.overflows.push((instance, type_length, call_span, root, 1, type_length));
+            }
+        } else {
+            for overflow in self.overflows.iter_mut().filter(|o| o.0.def_id() == def_id) {
+                overflow.4 += 1;
+                overflow.5 = cmp::max(overflow.5, type_length);
+            }
+        }
+        self.max_type_length = cmp::max(self.max_type_length, type_length);
+    }
+
+    // Emits one type-length error per function that exceeded the limit.
+    fn report<'a>(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+        for &(instance, type_length, call_span, root, count, max_type_length) in &self.overflows {
+            self.report_overflow(tcx,
+                                 instance,
+                                 type_length,
+                                 call_span,
+                                 root,
+                                 count,
+                                 max_type_length);
+        }
+    }
+
+    fn report_overflow<'a>(&self,
+                           tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                           instance: Instance<'tcx>,
+                           type_length: usize,
+                           call_span: Option<Span>,
+                           root: Option<TransItem<'tcx>>,
+                           count: usize,
+                           max_type_length: usize) {
+        let mut diag = struct_span_err!(tcx.sess,
+                                        local_item_span(tcx, instance.def_id()),
+                                        E0594,
+                                        "reached the type-length limit while instantiating `{}`",
+                                        instance_display_name(tcx, instance));
+
+        note_instance_origin(tcx, instance, root, &mut diag);
+        note_growing_type_parameter(tcx, instance, type_length, call_span, &mut diag);
+
+        if count > 1 {
+            diag.note(&format!("{} instances exceeded the type-length limit, the longest \
+                                has length {}",
+                               count,
+                               max_type_length));
+        }
+
+        // Suggest a limit that is large enough for all instances seen so far,
+        // including those of other functions.
+        // Rounding up to a power of two leaves some headroom for growth.
+        let type_length_limit = tcx.sess.type_length_limit.get();
+        let suggested_limit = cmp::max(self.max_type_length, type_length_limit * 2)
+            .next_power_of_two();
+        suggest_raising_limit(&mut diag,
+                              &tcx.hir.krate().attrs[..],
+                              "type_length_limit",
+                              suggested_limit);
+        diag.emit();
+    }
+}
+
+// Returns the span of `def_id` if it is a local item, or an empty span for
+// diagnostics about upstream items.
+fn local_item_span<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> MultiSpan {
+    match tcx.hir.as_local_node_id(def_id) {
+        Some(node_id) => MultiSpan::from_span(tcx.hir.span(node_id)),
+        None => MultiSpan::new(),
+    }
+}
+
+// Suggests raising the limit set by the `name` attribute to `limit`. If one of
+// `attrs` already sets the limit, the suggestion replaces that attribute,
+// otherwise a note asks for a crate attribute to be added.
+fn suggest_raising_limit(diag: &mut DiagnosticBuilder,
+                         attrs: &[ast::Attribute],
+                         name: &str,
+                         limit: usize) {
+    match attrs.iter().find(|attr| attr.check_name(name)) {
+        Some(attr) => {
+            let bang = if attr.style == ast::AttrStyle::Inner { "!" } else { "" };
+            diag.span_suggestion(attr.span,
+                                 &format!("consider raising the `{}`", name),
+                                 format!("#{}[{}=\"{}\"]", bang, name, limit));
+        }
+        None => {
+            diag.note(&format!("consider adding a `#![{}=\"{}\"]` attribute to your crate",
+                               name,
+                               limit));
+        }
+    }
+}
+
+// Tells the user which crate defines `instance` and, if that is not the local
+// crate, from which local item the instantiation was reached. Runaway
+// instantiations often happen deep inside of a library, where the name of the
+// instance alone does not reveal which code in the local crate caused them.
+fn note_instance_origin<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                  instance: Instance<'tcx>,
+                                  root: Option<TransItem<'tcx>>,
+                                  diag: &mut DiagnosticBuilder)
+{
+    let def_id = instance.def_id();
+    diag.note(&format!("`{}` is defined in crate `{}`",
+                       tcx.item_path_str(def_id),
+                       tcx.crate_name(def_id.krate)));
+
+    if def_id.is_local() {
+        return
+    }
+
+    let root_def_id = match root {
+        Some(TransItem::Fn(root_instance)) => root_instance.def_id(),
+        Some(TransItem::Static(node_id)) => tcx.hir.local_def_id(node_id),
+        Some(TransItem::Promoted(..)) | None => return,
+    };
+    let msg = format!("the instantiation was reached while collecting `{}`",
+                      tcx.item_path_str(root_def_id));
+    match tcx.hir.as_local_node_id(root_def_id) {
+        Some(node_id) => diag.span_note(tcx.hir.span(node_id), &msg),
+        None => diag.note(&msg),
+    };
+}
+
+// Points the user at the type parameter that is responsible for most of the
+// type length of `instance`. Blowing the type-length limit almost always means
+// that some parameter grows with every recursive instantiation, so telling the
+// user which one it is helps more than a bigger limit.
+fn note_growing_type_parameter<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                         instance: Instance<'tcx>,
+                                         type_length: usize,
+                                         call_span: Option<Span>,
+                                         diag: &mut DiagnosticBuilder)
+{
+    let mut param_lengths: Vec<_> = instance.substs
+                                            .iter()
+                                            .enumerate()
+                                            .filter_map(|(i, k)| k.as_type().map(|ty| (i, ty)))
+                                            .map(|(i, ty)| (i, ty, ty.walk().count()))
+                                            .collect();
+    if param_lengths.is_empty() {
+        return
+    }
+    param_lengths.sort_by(|a, b| b.2.cmp(&a.2));
+
+    let (index, ty, length) = param_lengths[0];
+    let param_name = type_param_name(tcx, instance.def_id(), index);
+
+    let msg = format!("type parameter `{}` of `{}` has length {}, growing via `{}`",
+                      param_name,
+                      tcx.item_path_str(instance.def_id()),
+                      length,
+                      truncated_name(&ty, TYPE_NAME_DISPLAY_DEPTH));
+    match call_span {
+        Some(span) => diag.span_note(span, &msg),
+        None => diag.note(&msg),
+    };
+
+    // Only mention the other parameters if they contribute a significant
+    // share of the total length.
+    let other_large_params = param_lengths[1..].iter()
+                                               .filter(|&&(_, _, len)| len * 4 >= type_length)
+                                               .count();
+    if other_large_params > 0 {
+        diag.note(&format!("{} other type parameter{} of `{}` {} also large",
+                           other_large_params,
+                           if other_large_params == 1 { "" } else { "s" },
+                           tcx.item_path_str(instance.def_id()),
+                           if other_large_params == 1 { "is" } else { "are" }));
+    }
+}
+
+// The number of nesting levels of a type name that are rendered in
+// diagnostics before the rest is elided with `...`.
+const TYPE_NAME_DISPLAY_DEPTH: usize = 3;
+
+// Renders `instance` for the recursion and type-length errors, whose instances
+// tend to have enormous type arguments, see `display_name`.
+fn instance_display_name<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   instance: Instance<'tcx>)
+                                   -> String {
+    let is_shim = match instance.def {
+        ty::InstanceDef::Item(_) => false,
+        _ => true,
+    };
+    display_name(tcx.item_path_str(instance.def_id()),
+                 instance.substs.types(),
+                 is_shim,
+                 TYPE_NAME_DISPLAY_DEPTH)
+}
+
+// Renders a function with the given type arguments. The path of the function
+// is always shown in full, and so is the outermost type constructor of each
+// type argument, but everything nested deeper than `max_depth` is elided,
+// e.g. `nest::<Wrapper<Wrapper<Wrapper<...>>>, u32>` for `max_depth == 3`.
+// This keeps similar functions apart, which cutting the flat name at some
+// length does not.
+fn display_name<I>(path: String, type_args: I, is_shim: bool, max_depth: usize) -> String
+    where I: IntoIterator, I::Item: fmt::Display
+{
+    let mut output = path;
+
+    let type_args: Vec<_> = type_args.into_iter()
+                                     .map(|ty| truncated_name(&ty, max_depth))
+                                     .collect();
+    if !type_args.is_empty() {
+        output.push_str("::<");
+        output.push_str(&type_args.join(", "));
+        output.push('>');
+    }
+
+    if is_shim {
+        output.push_str(" - shim");
+    }
+
+    output
+}
+
+// Looks up the name of the type parameter at `index` in the substitutions
+// of `def_id`, including the parameters inherited from parent items.
+fn type_param_name<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                             def_id: DefId,
+                             index: usize)
+                             -> String {
+    let mut generics = tcx.item_generics(def_id);
+    loop {
+        if let Some(def) = generics.types.iter().find(|def| def.index as usize == index) {
+            return def.name.to_string();
+        }
+        match generics.parent {
+            Some(parent) => generics = tcx.item_generics(parent),
+            None => return format!("#{}", index),
+        }
+    }
+}
+
+// Renders `value`, usually a type, with everything nested deeper than
+// `max_depth` generic argument lists, tuples, arrays or parameter lists
+// replaced by `...`, e.g. `Wrapper<Wrapper<Wrapper<...>>>` for `max_depth ==
+// 3`. The name is truncated while it is printed, the full name of a type that
+// exceeded the type-length limit can take up megabytes.
+fn truncated_name<T: fmt::Display>(value: &T, max_depth: usize) -> String {
+    let mut output = String::new();
+    {
+        let mut writer = TruncatedName {
+            output: &mut output,
+            max_depth: max_depth,
+            open: Vec::new(),
+            elided: false,
+            last: ' ',
+        };
+        write!(writer, "{}", value).unwrap();
+    }
+    output
+}
+
+struct TruncatedName<'a> {
+    output: &'a mut String,
+    max_depth: usize,
+    // The brackets that have been opened and not closed yet, innermost last.
+    open: Vec<char>,
+    // Whether `...` has been written for the current nesting level.
+    elided: bool,
+    last: char,
+}
+
+impl<'a> fmt::Write for TruncatedName<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            let opening = match c {
+                '>' => '<',
+                ')' => '(',
+                ']' => '[',
+                _ => ' ',
+            };
+            // Only a bracket matching the innermost open one closes it, and
+            // the `>` of `->` is none at all.
+            if opening != ' ' && self.last != '-' && self.open.last() == Some(&opening) {
+                self.open.pop();
+                if self.open.len() < self.max_depth {
+                    self.output.push(c);
+                    self.elided = false;
+                }
+            } else {
+                if self.open.len() < self.max_depth {
+                    self.output.push(c);
+                } else if !self.elided {
+                    self.output.push_str("...");
+                    self.elided = true;
+                }
+                if c == '<' || c == '(' || c == '[' {
+                    self.open.push(c);
+                }
+            }
+            self.last = c;
+        }
+        Ok(())
+    }
+}
+
+// A use of another item in a MIR body, as written in the body, i.e. before
+// the substitutions of the instance being collected are applied. The uses of
+// a body are the same for all of its instances, see `MirUseCache`.
+enum MirUse<'tcx> {
+    // A cast from `source_ty` to `target_ty`, which might need a vtable.
+    Unsize(Ty<'tcx>, Ty<'tcx>),
+    // A cast of a function item to a function pointer.
+    ReifyFnPointer(Ty<'tcx>),
+    // A cast of a closure to a function pointer.
+    ClosureFnPointer(Ty<'tcx>),
+    // A `box` expression for a value of the given type, which calls the
+    // `exchange_malloc` lang item unless the type is zero-sized.
+    Box(Ty<'tcx>),
+    // An assertion, which calls the given panic lang item if it fails.
+    Assert(LangItem),
+    // A constant referring to an item, whose body is searched as if it was
+    // part of this one.
+    Constant(DefId, &'tcx Substs<'tcx>),
+    // A call of a value of the given type.
+    Call(Ty<'tcx>),
+    // A drop of a value of the given type.
+    Drop(Ty<'tcx>),
+    // A use of a static that is not a root by itself, see
+    // `is_collected_through_users()`.
+    Static(DefId),
+}
+
+impl<'tcx> MirUse<'tcx> {
+    // Whether what the use refers to depends on the substs of the instance.
+    fn needs_subst(&self) -> bool {
+        match *self {
+            MirUse::Unsize(source_ty, target_ty) => {
+                source_ty.needs_subst() || target_ty.needs_subst()
+            }
+            MirUse::ReifyFnPointer(ty) |
+            MirUse::ClosureFnPointer(ty) |
+            MirUse::Box(ty) |
+            MirUse::Call(ty) |
+            MirUse::Drop(ty) => ty.needs_subst(),
+            MirUse::Assert(_) |
+            MirUse::Static(_) => false,
+            MirUse::Constant(_, substs) => substs.needs_subst(),
+        }
+    }
+}
+
+// The uses found in a MIR body, and in the bodies of the values promoted in
+// it. The latter are kept apart, along with whether any of them depends on
+// the substs of the instance, see `collect_neighbours()`.
+struct MirUses<'tcx> {
+    body: Vec<(Span, MirUse<'tcx>)>,
+    // The block of each use in `body`, if the body switches on a constant,
+    // which might leave some of its blocks unreachable in some instances.
+    body_blocks: Option<Vec<mir::BasicBlock>>,
+    promoted: Vec<(Vec<(Span, MirUse<'tcx>)>, bool)>,
+    // Whether the body has cleanup blocks, which get landing pads.
+    has_cleanup: bool,
+}
+
+impl<'tcx> MirUses<'tcx> {
+    fn is_empty(&self) -> bool {
+        self.body.is_empty() && self.promoted.iter().all(|&(ref uses, _)| uses.is_empty())
+    }
+}
+
+// Caches the uses found in the MIR body of each instance definition, along
+// with their spans. Generic functions are often instantiated many times, and
+// this way their MIR is only walked once. Resolving the uses for an instance
+// is all that is left to do per instance, see `MirNeighborCollector`.
+//
+// Also caches the neighbours found in the body of each constant instance,
+// which end up as neighbours of every item using the constant, and those
+// found in the promoted values that are the same for all instances.
+struct MirUseCache<'tcx> {
+    // False with `-Z no-mono-mir-cache`, to check that the cache makes no
+    // difference.
+    enabled: bool,
+    uses: FxHashMap<ty::InstanceDef<'tcx>, Rc<MirUses<'tcx>>>,
+    constant_neighbours: FxHashMap<Instance<'tcx>, Rc<Vec<TransItem<'tcx>>>>,
+    // The `const fn`s whose bodies are being searched in place of a call at
+    // compile time, see `MirNeighborCollector::visit_use()`.
+    const_fns_in_progress: FxHashSet<Instance<'tcx>>,
+    promoted_neighbours: FxHashMap<(ty::InstanceDef<'tcx>, mir::Promoted),
+                                   Rc<Vec<TransItem<'tcx>>>>,
+}
+
+impl<'tcx> MirUseCache<'tcx> {
+    fn new(enabled: bool) -> MirUseCache<'tcx> {
+        MirUseCache {
+            enabled: enabled,
+            uses: FxHashMap(),
+            constant_neighbours: FxHashMap(),
+            const_fns_in_progress: FxHashSet(),
+            promoted_neighbours: FxHashMap(),
+        }
+    }
+
+    fn constant_neighbours(&self, constant: Instance<'tcx>) -> Option<Rc<Vec<TransItem<'tcx>>>> {
+        self.constant_neighbours.get(&constant).cloned()
+    }
+
+    fn record_constant_neighbours(&mut self,
+                                  constant: Instance<'tcx>,
+                                  neighbours: &[TransItem<'tcx>]) {
+        if self.enabled {
+            self.constant_neighbours.insert(constant, Rc::new(neighbours.to_vec()));
+        }
+    }
+
+    fn promoted_neighbours(&self,
+                           def: ty::InstanceDef<'tcx>,
+                           promoted: mir::Promoted)
+                           -> Option<Rc<Vec<TransItem<'tcx>>>> {
+        self.promoted_neighbours.get(&(def, promoted)).cloned()
+    }
+
+    fn record_promoted_neighbours(&mut self,
+                                  def: ty::InstanceDef<'tcx>,
+                                  promoted: mir::Promoted,
+                                  neighbours: &[TransItem<'tcx>]) {
+        if self.enabled {
+            self.promoted_neighbours.insert((def, promoted), Rc::new(neighbours.to_vec()));
+        }
+    }
+
+    fn uses<'a>(&mut self,
+                tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                def: ty::InstanceDef<'tcx>)
+                -> Rc<MirUses<'tcx>> {
+        if let Some(uses) = self.uses.get(&def) {
+            return uses.clone();
+        }
+
+        let mir = tcx.instance_mir(def);
+        let mut finder = MirUseFinder {
+            tcx: tcx,
+            mir: &mir,
+            uses: Vec::new(),
+            blocks: Vec::new(),
+        };
+        finder.visit_mir(&mir);
+        let body = mem::replace(&mut finder.uses, Vec::new());
+        let body_blocks = mem::replace(&mut finder.blocks, Vec::new());
+        let switches_on_constant = mir.basic_blocks().iter().any(|data| {
+            switch_constant(data).is_some()
+        });
+
+        let mut promoted_uses = Vec::with_capacity(mir.promoted.len());
+        for promoted in &mir.promoted {
+            finder.mir = promoted;
+            finder.visit_mir(promoted);
+            let uses = mem::replace(&mut finder.uses, Vec::new());
+            finder.blocks.clear();
+            let needs_subst = uses.iter().any(|&(_, ref mir_use)| mir_use.needs_subst());
+            promoted_uses.push((uses, needs_subst));
+        }
+
+        let uses = Rc::new(MirUses {
+            body: body,
+            body_blocks: if switches_on_constant { Some(body_blocks) } else { None },
+            promoted: promoted_uses,
+            has_cleanup: mir.basic_blocks().iter().any(|data| data.is_cleanup),
+        });
+        if self.enabled {
+            self.uses.insert(def, uses.clone());
+        }
+        uses
+    }
+}
+
+// Finds the uses of other items in a MIR body, see `MirUse`.
+struct MirUseFinder<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    mir: &'a mir::Mir<'tcx>,
+    uses: Vec<(Span, MirUse<'tcx>)>,
+    // The block of each of `uses`.
+    blocks: Vec<mir::BasicBlock>,
+}
+
+impl<'a, 'tcx> MirUseFinder<'a, 'tcx> {
+    fn push(&mut self, location: Location, mir_use: MirUse<'tcx>) {
+        let data = &self.mir[location.block];
+        let span = match data.statements.get(location.statement_index) {
+            Some(statement) => statement.source_info.span,
+            None => data.terminator().source_info.span
+        };
+        self.uses.push((span, mir_use));
+        self.blocks.push(location.block);
+    }
+}
+
+impl<'a, 'tcx> MirVisitor<'tcx> for MirUseFinder<'a, 'tcx> {
+
+    fn visit_basic_block_data(&mut self,
+                              block: mir::BasicBlock,
+                              data: &mir::BasicBlockData<'tcx>) {
+        // Cleanup blocks only run when unwinding, which aborts without
+        // landing pads. The MIR of upstream crates built with
+        // `-C panic=unwind`, and the MIR inlined from them, still has them.
+        // Translation skips these blocks too, see `mir::trans_mir()`.
+        if data.is_cleanup && self.tcx.sess.no_landing_pads() {
+            return;
+        }
+
+        self.super_basic_block_data(block, data);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
+        debug!("visiting rvalue {:?}", *rvalue);
+
+        match *rvalue {
+            mir::Rvalue::Cast(mir::CastKind::Unsize, ref operand, target_ty) => {
+                let source_ty = operand.ty(self.mir, self.tcx);
+                self.push(location, MirUse::Unsize(source_ty, target_ty));
+            }
+            mir::Rvalue::Cast(mir::CastKind::ReifyFnPointer, ref operand, _) => {
+                let fn_ty = operand.ty(self.mir, self.tcx);
+                self.push(location, MirUse::ReifyFnPointer(fn_ty));
+            }
+            mir::Rvalue::Cast(mir::CastKind::ClosureFnPointer, ref operand, _) => {
+                let source_ty = operand.ty(self.mir, self.tcx);
+                self.push(location, MirUse::ClosureFnPointer(source_ty));
+            }
+            mir::Rvalue::Box(content_ty) => {
+                self.push(location, MirUse::Box(content_ty));
+            }
+            _ => { /* not interesting */ }
+        }
+
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: Location) {
+        debug!("visiting constant {:?} @ {:?}", *constant, location);
+
+        if let ty::TyFnDef(..) = constant.ty.sty {
+            // function definitions are zero-sized, and only generate
+            // IR when they are called/reified.
+            self.super_constant(constant, location);
+            return
+        }
+
+        if let mir::Literal::Item { def_id, substs } = constant.literal {
+            self.push(location, MirUse::Constant(def_id, substs));
+        }
+
+        self.super_constant(constant, location);
+    }
+
+    fn visit_terminator_kind(&mut self,
+                             block: mir::BasicBlock,
+                             kind: &mir::TerminatorKind<'tcx>,
+                             location: Location) {
+        let tcx = self.tcx;
+        match *kind {
+            mir::TerminatorKind::Call { ref func, .. } => {
+                let callee_ty = func.ty(self.mir, tcx);
+                self.push(location, MirUse::Call(callee_ty));
+            }
+            mir::TerminatorKind::Drop { location: ref lvalue, .. } |
+            mir::TerminatorKind::DropAndReplace { location: ref lvalue, .. } => {
+                let ty = lvalue.ty(self.mir, tcx).to_ty(tcx);
+                self.push(location, MirUse::Drop(ty));
+            }
+            mir::TerminatorKind::Assert { ref msg, .. } => {
+                let lang_item = match *msg {
+                    mir::AssertMessage::BoundsCheck { .. } => PanicBoundsCheckFnLangItem,
+                    mir::AssertMessage::Math(_) => PanicFnLangItem,
+                };
+                self.push(location, MirUse::Assert(lang_item));
+            }
+            mir::TerminatorKind::Goto { .. } |
+            mir::TerminatorKind::SwitchInt { .. } |
+            mir::TerminatorKind::Resume |
+            mir::TerminatorKind::Return |
+            mir::TerminatorKind::Unreachable => {}
+        }
+
+        self.super_terminator_kind(block, kind, location);
+    }
+
+    fn visit_static(&mut self,
+                    static_: &mir::Static<'tcx>,
+                    context: mir::visit::LvalueContext<'tcx>,
+                    location: Location) {
+        if is_collected_through_users(self.tcx, static_.def_id) {
+            self.push(location, MirUse::Static(static_.def_id));
+        }
+
+        self.super_static(static_, context, location);
+    }
+}
+
+// Resolves the uses found in the MIR body of `instance` to the translation
+// items they refer to.
+struct MirNeighborCollector<'a, 'tcx: 'a> {
+    scx: &'a SharedCrateContext<'a, 'tcx>,
+    memos: &'a CollectionMemos<'tcx>,
+    output: &'a mut Vec<TransItem<'tcx>>,
+    references: &'a mut Option<ItemReferences<'tcx>>,
+    // The instance whose MIR is visited.
+    instance: Instance<'tcx>,
+    param_substs: &'tcx Substs<'tcx>,
+    // Whether the visited MIR is evaluated at compile time, see
+    // `collect_neighbours()`.
+    const_context: bool,
+}
+
+impl<'a, 'tcx> MirNeighborCollector<'a, 'tcx> {
+    // Records all items pushed to `output` since it had length `start` as
+    // being referenced from `span` in the given way.
+    fn record_references(&mut self, start: usize, kind: ReferenceKind, span: Span) {
+        if let Some(ref mut references) = *self.references {
+            references.record(&self.output[start..], kind, span);
+        }
+    }
+
+    // Substitutes the parameters of the visited instance into a value found in
+    // its MIR. Most values need no folding at all: the MIR of non-generic
+    // functions, and much of that of generic ones, is monomorphic, and its
+    // regions are already erased and its associated types normalized.
+    fn monomorphize<T>(&self, value: &T) -> T
+        where T: TransNormalize<'tcx>
+    {
+        let stats = self.scx.collection_stats();
+        if !value.needs_subst() && !value.has_erasable_regions() &&
+           !value.has_projection_types() {
+            stats.bump(&stats.param_substs_folds_skipped);
+            return value.clone();
+        }
+
+        stats.bump(&stats.param_substs_folds);
+        monomorphize::apply_param_substs(self.scx, self.param_substs, value)
+    }
+
+    // Looks up a lang item that the MIR at `span` needs, reporting an error
+    // there if no crate defines it. `what` names the construct that needs the
+    // lang item.
+    fn require_lang_item(&self, item: LangItem, what: &str, span: Span) -> Option<DefId> {
+        let tcx = self.scx.tcx();
+        match tcx.lang_items.require(item) {
+            Ok(def_id) => Some(def_id),
+            Err(_) => {
+                tcx.sess.struct_span_err(span,
+                                         &format!("{} require the `{}` lang item",
+                                                  what,
+                                                  LanguageItems::item_name(item as usize)))
+                        .note(&format!("required by `{}`",
+                                       tcx.item_path_str(self.instance.def_id())))
+                        .emit();
+                None
+            }
+        }
+    }
+
+    fn visit_use(&mut self,
+                 span: Span,
+                 mir_use: &MirUse<'tcx>,
+                 mir_uses: &mut MirUseCache<'tcx>) {
+        let tcx = self.scx.tcx();
+        match *mir_use {
+            // When doing an cast from a regular pointer to a fat pointer, we
+            // have to instantiate all methods of the trait being cast to, so we
+            // can build the appropriate vtable.
+            MirUse::Unsize(source_ty, target_ty) => {
+                let target_ty = self.monomorphize(&target_ty);
+                let source_ty = self.monomorphize(&source_ty);
+                match vtable_types_for_unsizing(self.scx, self.memos, source_ty, target_ty) {
+                    // This could also be a different Unsize instruction, like
+                    // from a fixed sized array to a slice. But we are only
+                    // interested in things that produce a vtable.
+                    Ok((source_ty, target_ty)) => if target_ty.is_trait() &&
+                                                     !source_ty.is_trait() {
+                        let start = self.output.len();
+                        let methods = create_trans_items_for_vtable_methods(self.scx,
+                                                                            self.memos,
+                                                                            target_ty,
+                                                                            source_ty,
+                                                                            self.output);
+                        self.record_references(start, ReferenceKind::Vtable, span);
+                        if let Some(ref mut references) = *self.references {
+                            references.record_vtable_cast(self.scx.tcx(),
+                                                          span,
+                                                          source_ty,
+                                                          target_ty,
+                                                          self.instance.def_id(),
+                                                          methods);
+                        }
+                    },
+                    Err(unsupported) => {
+                        // Skip the vtable, so that collection can go on and
+                        // report any other errors.
+                        report_invalid_unsizing(self.scx,
+                                                span,
+                                                (source_ty, target_ty),
+                                                unsupported);
+                    }
+                }
+            }
+            MirUse::ReifyFnPointer(fn_ty) => {
+                let fn_ty = self.monomorphize(&fn_ty);
+                let start = self.output.len();
+                visit_fn_use(self.scx, self.memos, fn_ty, false, span, &mut self.output);
+                self.record_references(start, ReferenceKind::Reify, span);
+            }
+            MirUse::ClosureFnPointer(source_ty) => {
+                let source_ty = self.monomorphize(&source_ty);
+                match source_ty.sty {
+                    ty::TyClosure(def_id, substs) => {
+                        let instance =
+                            monomorphize::resolve_closure_fn_pointer(self.scx, def_id, substs);
+                        let start = self.output.len();
+                        self.output.push(create_fn_trans_item(instance));
+                        self.record_references(start, ReferenceKind::Reify, span);
+                    }
+                    _ => bug!(),
+                }
+            }
+            MirUse::Box(content_ty) => {
+                // Nothing is allocated for zero-sized values, so `no_std`
+                // crates that only box those need no allocator.
+                let content_ty = self.monomorphize(&content_ty);
+                if !glue::box_allocates(self.scx, content_ty) {
+                    return;
+                }
+                if let Some(exchange_malloc_fn_def_id) =
+                        self.require_lang_item(ExchangeMallocFnLangItem,
+                                               "`box` expressions",
+                                               span) {
+                    let instance = Instance::mono(tcx, exchange_malloc_fn_def_id);
+                    if should_trans_locally(self.scx, self.memos, &instance) {
+                        self.output.push(create_fn_trans_item(instance));
+                    }
+                }
+            }
+            MirUse::Assert(lang_item) => {
+                // Assertions evaluated at compile time fail with an error
+                // instead of panicking.
+                if self.const_context {
+                    return;
+                }
+                if let Some(panic_fn_def_id) = self.require_lang_item(lang_item,
+                                                                      "assertions",
+                                                                      span) {
+                    let instance = Instance::mono(tcx, panic_fn_def_id);
+                    let start = self.output.len();
+                    visit_instance_use(self.scx,
+                                       self.memos,
+                                       instance,
+                                       true,
+                                       span,
+                                       &mut self.output);
+                    self.record_references(start, ReferenceKind::Call, span);
+                }
+            }
+            MirUse::Constant(def_id, substs) => {
+                let substs = self.monomorphize(&substs);
+                let instance = monomorphize::resolve(self.scx, def_id, substs);
+
+                // The body of a constant is not an item of its own, so its
+                // neighbours are those of every item using it. Constants
+                // using other constants would otherwise have their bodies
+                // searched over and over. The references found in a body
+                // only need to be recorded once.
+                if let Some(neighbours) = mir_uses.constant_neighbours(instance) {
+                    self.output.extend(neighbours.iter().cloned());
+                    return;
+                }
+
+                let start = self.output.len();
+                collect_neighbours(self.scx,
+                                   self.memos,
+                                   instance,
+                                   true,
+                                   self.output,
+                                   self.references,
+                                   mir_uses);
+                mir_uses.record_constant_neighbours(instance, &self.output[start..]);
+            }
+            MirUse::Call(callee_ty) => {
+                // Calls of intrinsics are treated like any other call. Their
+                // operands are evaluated before the call, function items
+                // passed to them (e.g. to `try`) are reified there.
+                let callee_ty = self.monomorphize(&callee_ty);
+
+                // A `const fn` called at compile time is evaluated from its
+                // MIR and needs no translation item. The items its body
+                // refers to may end up in the resulting value though.
+                // Recursive calls, direct or not, are not searched again,
+                // and neither are calls nested deeper than the recursion
+                // limit, which the evaluation would not get past either.
+                if self.const_context {
+                    if let Some(instance) = const_fn_instance(self.scx, callee_ty) {
+                        let depth = mir_uses.const_fns_in_progress.len();
+                        if instance != self.instance &&
+                           depth < tcx.sess.recursion_limit.get() &&
+                           mir_uses.const_fns_in_progress.insert(instance) {
+                            collect_neighbours(self.scx,
+                                               self.memos,
+                                               instance,
+                                               true,
+                                               self.output,
+                                               self.references,
+                                               mir_uses);
+                            mir_uses.const_fns_in_progress.remove(&instance);
+                        }
+                        return;
+                    }
+                }
+                let start = self.output.len();
+                visit_fn_use(self.scx, self.memos, callee_ty, true, span, &mut self.output);
+
+                if let ty::TyFnDef(def_id, substs, _) = callee_ty.sty {
+                    let instance = monomorphize::resolve(self.scx, def_id, substs);
+                    if let ty::InstanceDef::Virtual(trait_method, _) = instance.def {
+                        if let Some(ref mut references) = *self.references {
+                            references.record_virtual_call(trait_method);
+                        }
+                    }
+                }
+                self.record_references(start, ReferenceKind::Call, span);
+            }
+            MirUse::Drop(ty) => {
+                let ty = self.monomorphize(&ty);
+                visit_drop_use(self.scx, self.memos, ty, true, self.output);
+            }
+            MirUse::Static(def_id) => {
+                let node_id = tcx.hir.as_local_node_id(def_id).unwrap();
+                self.output.push(TransItem::Static(node_id));
+            }
+        }
+    }
+}
+
+fn visit_drop_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                            memos: &CollectionMemos<'tcx>,
+                            ty: ty::Ty<'tcx>,
+                            is_direct_call: bool,
+                            output: &mut Vec<TransItem<'tcx>>)
+{
+    // `resolve_drop_in_place()` relies on this to select the right impls.
+    debug_assert!(is_revealed_for_trans(ty), "unnormalized dropped type {:?}", ty);
+
+    // Dropping a type without drop glue translates to nothing, so there is
+    // no need to resolve it. Only vtables need the empty drop glue of such
+    // types, see `create_trans_items_for_vtable_methods()`.
+    if !scx.type_needs_drop(ty) {
+        return
+    }
+
+    // Trait objects are dropped through the destructor in their vtable, see
+    // `mir::block`, so dropping a `Box<Trait>` only needs the glue of the
+    // box and `box_free`. The destructor is collected along with the vtable,
+    // wherever the object is created, which may well be another crate.
+    if let ty::TyDynamic(..) = ty.sty {
+        if is_direct_call {
+            return
+        }
+    }
+
+    scx.collection_stats().bump(&scx.collection_stats().drop_glue_resolutions);
+    let instance = resolve_drop_in_place(scx, memos, ty);
+    visit_instance_use(scx, memos, instance, is_direct_call, DUMMY_SP, output);
+}
+
+// Same as `monomorphize::resolve_drop_in_place()`, but memoized for the
+// duration of the walk. The same types are dropped over and over in a
+// crate, and resolving `drop_in_place` goes through trait selection.
+fn resolve_drop_in_place<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                   memos: &CollectionMemos<'tcx>,
+                                   ty: ty::Ty<'tcx>)
+                                   -> Instance<'tcx> {
+    let stats = scx.collection_stats();
+    if let Some(&instance) = memos.drop_in_place_instances.borrow().get(&ty) {
+        stats.bump(&stats.drop_in_place_cache_hits);
+        return instance;
+    }
+
+    stats.bump(&stats.drop_in_place_cache_misses);
+    let instance = monomorphize::resolve_drop_in_place(scx, ty);
+    memos.drop_in_place_instances.borrow_mut().insert(ty, instance);
+    instance
+}
+
+fn visit_fn_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                          memos: &CollectionMemos<'tcx>,
+                          ty: ty::Ty<'tcx>,
+                          is_direct_call: bool,
+                          span: Span,
+                          output: &mut Vec<TransItem<'tcx>>)
+{
+    if let ty::TyFnDef(def_id, substs, _) = ty.sty {
+        // See `visit_drop_use()`.
+        debug_assert!(substs.types().all(is_revealed_for_trans),
+                      "unnormalized callee {:?}", ty);
+        let instance = monomorphize::resolve(scx, def_id, substs);
+        visit_instance_use(scx, memos, instance, is_direct_call, span, output);
+    }
+}
+
+// Whether `ty` has no projections left that could be normalized and no
+// `impl Trait` types that could be revealed. Types reaching resolution always
+// do, as `monomorphize::apply_param_substs()` and `monomorphize::field_ty()`
+// normalize them with `Reveal::All` after substituting the parameters.
+fn is_revealed_for_trans<'tcx>(ty: ty::Ty<'tcx>) -> bool {
+    ty.is_normalized_for_trans() && !ty.walk().any(|t| match t.sty {
+        // `Reveal::All` replaces an `impl Trait` type by its concrete type
+        // once its substitutions are known. One that still refers to type
+        // parameters cannot be revealed yet, so it does not count.
+        ty::TyAnon(_, substs) => !substs.needs_subst(),
+        _ => false,
+    })
+}
+
+fn is_const_fn<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> bool {
+    if let Some(node_id) = tcx.hir.as_local_node_id(def_id) {
+        match FnLikeNode::from_node(tcx.hir.get(node_id)) {
+            Some(fn_like) => fn_like.constness() == hir::Constness::Const,
+            None => false,
+        }
+    } else {
+        tcx.sess.cstore.is_const_fn(def_id)
+    }
+}
+
+// Returns the instance called through `callee_ty` if it is a `const fn` with
+// MIR, which a call at compile time evaluates.
+fn const_fn_instance<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                               callee_ty: ty::Ty<'tcx>)
+                               -> Option<Instance<'tcx>> {
+    if let ty::TyFnDef(def_id, substs, _) = callee_ty.sty {
+        if is_const_fn(scx.tcx(), def_id) {
+            let instance = monomorphize::resolve(scx, def_id, substs);
+            if let ty::InstanceDef::Item(def_id) = instance.def {
+                if def_id.is_local() || scx.sess().cstore.is_item_mir_available(def_id) {
+                    return Some(instance);
+                }
+            }
+        }
+    }
+    None
+}
+
+// `span` is the location of the use, it is only used for error reporting.
+fn visit_instance_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                memos: &CollectionMemos<'tcx>,
+                                instance: ty::Instance<'tcx>,
+                                is_direct_call: bool,
+                                span: Span,
+                                output: &mut Vec<TransItem<'tcx>>)
+{
     debug!("visit_item_use({:?}, is_direct_call={:?})", instance, is_direct_call);
-    if !should_trans_locally(scx.tcx(), &instance) {
+    if !should_trans_locally(scx, memos, &instance) {
+        return
+    }
+
+    // Freeing a box that was never allocated is skipped, see
+    // `glue::is_noop_box_free()`.
+    if is_direct_call && glue::is_noop_box_free(scx, &instance) {
         return
     }
 
     match instance.def {
         ty::InstanceDef::Intrinsic(def_id) => {
             if !is_direct_call {
-                bug!("intrinsic {:?} being reified", def_id);
+                // Intrinsics are translated in-line at their call sites,
+                // there is no function that a pointer could point to.
+                let tcx = scx.tcx();
+                tcx.sess.struct_span_err(span,
+                                         &format!("intrinsic `{}` cannot be used as a \
+                                                   function pointer",
+                                                  tcx.item_path_str(def_id)))
+                        .note("intrinsics can only be called directly")
+                        .emit();
+            }
+        }
+        ty::InstanceDef::Virtual(..) => {
+            // don't need to emit shim if we are calling directly. Arguments
+            // passed by value to a virtual call are moved into the callee,
+            // which drops them. The vtable methods are collected with their
+            // MIR, and with it the drop glue of those arguments, and the
+            // caller's own cleanup blocks are `Drop` terminators.
+            if !is_direct_call {
+                output.push(create_fn_trans_item(instance));
             }
         }
-        ty::InstanceDef::Virtual(..) |
         ty::InstanceDef::DropGlue(_, None) => {
             // don't need to emit shim if we are calling directly.
             if !is_direct_call {
+                record_drop_glue_use(scx, instance, DropGlueUse::Indirect);
                 output.push(create_fn_trans_item(instance));
             }
         }
         ty::InstanceDef::DropGlue(_, Some(ty)) => {
+            record_drop_glue_use(scx, instance, if is_direct_call {
+                DropGlueUse::Direct
+            } else {
+                DropGlueUse::Indirect
+            });
+
             match ty.sty {
                 ty::TyArray(ety, _) |
                 ty::TySlice(ety)
                     if is_direct_call =>
                 {
-                    // drop of arrays/slices is translated in-line.
-                    visit_drop_use(scx, ety, false, output);
+                    // drop of arrays/slices is translated in-line. Arrays of
+                    // length zero need no drop glue, see `needs_drop_glue()`.
+                    visit_drop_use(scx, memos, ety, false, output);
                 }
                 _ => {}
             };
             output.push(create_fn_trans_item(instance));
         }
-        ty::InstanceDef::ClosureOnceShim { .. } |
-        ty::InstanceDef::Item(..) |
-        ty::InstanceDef::FnPtrShim(..) => {
-            output.push(create_fn_trans_item(instance));
+        // Tuple struct and variant constructors are items with MIR too, even
+        // those of other crates, which do not export them. Aggregates build
+        // values in-line, so they only get here when used as function values.
+        ty::InstanceDef::ClosureOnceShim { .. } |
+        ty::InstanceDef::Item(..) |
+        ty::InstanceDef::FnPtrShim(..) => {
+            output.push(create_fn_trans_item(instance));
+        }
+    }
+}
+
+/// The ways in which a drop glue item can be required, from the most to the
+/// least direct. Shown by `-Z print-trans-items`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum DropGlueUse {
+    // Called when dropping a value.
+    Direct,
+    // Put into the vtable of a trait object.
+    Vtable,
+    // Reified to a function pointer, or called from other drop glue.
+    Indirect,
+}
+
+// Remembers the most direct way in which the drop glue `instance` is
+// required, for `trans_item_reason()`.
+fn record_drop_glue_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  instance: Instance<'tcx>,
+                                  drop_use: DropGlueUse) {
+    if scx.collection_flags().print_trans_items.is_none() {
+        return
+    }
+
+    let mut uses = scx.drop_glue_uses().borrow_mut();
+    let entry = uses.entry(create_fn_trans_item(instance)).or_insert(drop_use);
+    *entry = cmp::min(*entry, drop_use);
+}
+
+/// Selects the items printed by `-Z print-trans-items=<mode>:<patterns>`.
+/// `patterns` is a comma-separated list of patterns in which `*` matches any
+/// sequence of characters. An item is printed if one of the patterns matches
+/// its whole path, written without disambiguators and without the name of the
+/// local crate, like `my_mod::foo<u32>`. Drop glue can also be matched by the
+/// path of the dropped type.
+pub struct TransItemFilter {
+    patterns: Vec<String>,
+}
+
+impl TransItemFilter {
+    pub fn new(patterns: &str) -> TransItemFilter {
+        TransItemFilter {
+            patterns: patterns.split(',')
+                              .map(|pattern| pattern.trim())
+                              .filter(|pattern| !pattern.is_empty())
+                              .map(|pattern| pattern.to_string())
+                              .collect(),
+        }
+    }
+
+    pub fn matches<'a, 'tcx>(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>, item: TransItem<'tcx>) -> bool {
+        let printer = DefPathBasedNames::new(tcx, true, true);
+        let instance = match item {
+            TransItem::Fn(instance) | TransItem::Promoted(instance, _) => instance,
+            TransItem::Static(node_id) => Instance::mono(tcx, tcx.hir.local_def_id(node_id)),
+        };
+
+        let mut names = vec![];
+        let mut name = String::new();
+        printer.push_instance_as_string(instance, &mut name);
+        names.push(name);
+
+        if let ty::InstanceDef::DropGlue(_, Some(ty)) = instance.def {
+            let mut name = String::new();
+            printer.push_type_name(ty, &mut name);
+            names.push(name);
+        }
+
+        self.patterns.iter().any(|pattern| {
+            names.iter().any(|name| glob_matches(pattern.as_bytes(), name.as_bytes()))
+        })
+    }
+}
+
+// Returns true if `pattern` matches all of `name`, with `*` matching any
+// sequence of characters.
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // The position of the last `*` in `pattern` and the position in `name`
+    // it was tried at, to retry it matching one more character on mismatch.
+    let mut backtrack = None;
+
+    while n < name.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == name[n] {
+            p += 1;
+            n += 1;
+        } else if let Some((star_p, star_n)) = backtrack {
+            backtrack = Some((star_p, star_n + 1));
+            p = star_p + 1;
+            n = star_n + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Explains why a shim or drop glue item exists, or which upstream crate also
+/// exports a copy of an item, for `-Z print-trans-items`. Returns `None` for
+/// the other items the user has written themselves.
+pub fn trans_item_reason<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                   item: TransItem<'tcx>,
+                                   available_upstream: &FxHashMap<TransItem<'tcx>, CrateNum>)
+                                   -> Option<String> {
+    let tcx = scx.tcx();
+    let instance = match item {
+        TransItem::Fn(instance) => instance,
+        TransItem::Static(..) | TransItem::Promoted(..) => return None,
+    };
+
+    match instance.def {
+        ty::InstanceDef::Item(_) => {
+            available_upstream.get(&item).map(|&cnum| {
+                format!("available upstream: `{}` exports a copy", tcx.crate_name(cnum))
+            })
+        }
+        ty::InstanceDef::Intrinsic(_) => None,
+        ty::InstanceDef::FnPtrShim(def_id, fn_ptr_ty) => {
+            Some(format!("shim: calls a `{}` through `{}`",
+                         fn_ptr_ty,
+                         tcx.item_path_str(def_id)))
+        }
+        ty::InstanceDef::ClosureOnceShim { .. } => {
+            match instance.substs.type_at(0).sty {
+                ty::TyClosure(closure_def_id, _) => {
+                    Some(format!("shim: calls the `{:?}` closure `{}` through `FnOnce`",
+                                 tcx.closure_kind(closure_def_id),
+                                 def_id_to_string(tcx, closure_def_id)))
+                }
+                _ => bug!("closure once shim for non-closure {:?}", instance),
+            }
+        }
+        ty::InstanceDef::Virtual(def_id, _) => {
+            Some(format!("shim: calls `{}` through a vtable", tcx.item_path_str(def_id)))
+        }
+        ty::InstanceDef::DropGlue(..) => {
+            let how = match scx.drop_glue_uses().borrow().get(&item) {
+                Some(&DropGlueUse::Direct) => "directly",
+                Some(&DropGlueUse::Vtable) => "by a vtable",
+                Some(&DropGlueUse::Indirect) | None => "indirectly",
+            };
+            Some(format!("glue: drops `{}`, required {}", instance.substs.type_at(0), how))
         }
     }
 }
@@ -641,8 +3379,11 @@ fn visit_instance_use<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
 // Returns true if we should translate an instance in the local crate.
 // Returns false if we can just link to the upstream crate and therefore don't
 // need a translation item.
-fn should_trans_locally<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, instance: &Instance<'tcx>)
+fn should_trans_locally<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  memos: &CollectionMemos<'tcx>,
+                                  instance: &Instance<'tcx>)
                                   -> bool {
+    let tcx = scx.tcx();
     let def_id = match instance.def {
         ty::InstanceDef::Item(def_id) => def_id,
         ty::InstanceDef::ClosureOnceShim { .. } |
@@ -657,7 +3398,22 @@ fn should_trans_locally<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, instance: &Instan
         }
         Some(_) => true,
         None => {
-            if tcx.sess.cstore.is_exported_symbol(def_id) ||
+            // With `-Z share-generics`, link to the instance an upstream
+            // crate already translated and exported.
+            if scx.upstream_monomorphization(instance).is_some() {
+                return false;
+            }
+
+            // Popular upstream generics are referenced from all over the
+            // crate. The answer only depends on the item, not on its substs.
+            let stats = scx.collection_stats();
+            if let Some(&trans_locally) = memos.upstream_trans_locally.borrow().get(&def_id) {
+                stats.bump(&stats.upstream_trans_locally_hits);
+                return trans_locally;
+            }
+            stats.bump(&stats.upstream_trans_locally_misses);
+
+            let trans_locally = if tcx.sess.cstore.is_exported_symbol(def_id) ||
                 tcx.sess.cstore.is_foreign_item(def_id)
             {
                 // We can link to the item in question, no instance needed
@@ -665,14 +3421,36 @@ fn should_trans_locally<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, instance: &Instan
                 false
             } else {
                 if !tcx.sess.cstore.is_item_mir_available(def_id) {
-                    bug!("Cannot create local trans-item for {:?}", def_id)
+                    report_missing_upstream_mir(tcx, def_id);
                 }
                 true
-            }
+            };
+            memos.upstream_trans_locally.borrow_mut().insert(def_id, trans_locally);
+            trans_locally
         }
     }
 }
 
+// The MIR of an upstream item we need to instantiate locally is missing from
+// the crate metadata. This is not a compiler bug in the current session but
+// usually means that the upstream crate was built in an incompatible way.
+fn report_missing_upstream_mir<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, def_id: DefId) -> ! {
+    let crate_name = tcx.sess.cstore.crate_name(def_id.krate);
+    let mut diag = tcx.sess.struct_fatal(&format!("missing MIR for `{}` from crate `{}`",
+                                                  def_id_to_string(tcx, def_id),
+                                                  crate_name));
+    diag.note(&format!("`{}` needs to be instantiated in this crate, but the metadata of \
+                        `{}` does not contain its MIR",
+                       tcx.item_path_str(def_id),
+                       crate_name));
+    diag.help(&format!("the crate `{}` may have been compiled without the required metadata; \
+                        try rebuilding it with the same compiler",
+                       crate_name));
+    diag.emit();
+    tcx.sess.abort_if_errors();
+    bug!("missing MIR for {:?} did not abort compilation", def_id)
+}
+
 /// For given pair of source and target type that occur in an unsizing coercion,
 /// this function finds the pair of types that determines the vtable linking
 /// them.
@@ -709,17 +3487,26 @@ fn should_trans_locally<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>, instance: &Instan
 /// `(SomeStruct, SomeTrait)`.
 ///
 /// Finally, there is also the case of custom unsizing coercions, e.g. for
-/// smart pointers such as `Rc` and `Arc`.
+/// smart pointers such as `Rc` and `Arc`. The coerced field of such a smart
+/// pointer is again one of the cases above, so it is handled recursively: it
+/// can be another smart pointer, or a pointer to a struct like
+/// `ComplexStruct<T>`, as in `MyBox<ComplexStruct<SomeStruct>>` to
+/// `MyBox<ComplexStruct<SomeTrait>>`.
+///
+/// If the coercion ends up at a pair of types that is neither of the above,
+/// that pair is returned as the error.
 fn find_vtable_types_for_unsizing<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                            memos: &CollectionMemos<'tcx>,
                                             source_ty: ty::Ty<'tcx>,
                                             target_ty: ty::Ty<'tcx>)
-                                            -> (ty::Ty<'tcx>, ty::Ty<'tcx>) {
+                                            -> Result<(ty::Ty<'tcx>, ty::Ty<'tcx>),
+                                                      (ty::Ty<'tcx>, ty::Ty<'tcx>)> {
     let ptr_vtable = |inner_source: ty::Ty<'tcx>, inner_target: ty::Ty<'tcx>| {
-        if !scx.type_is_sized(inner_source) {
+        Ok(if !type_is_sized(scx, memos, inner_source) {
             (inner_source, inner_target)
         } else {
-            scx.tcx().struct_lockstep_tails(inner_source, inner_target)
-        }
+            struct_lockstep_tails(scx, memos, inner_source, inner_target)
+        })
     };
     match (&source_ty.sty, &target_ty.sty) {
         (&ty::TyRef(_, ty::TypeAndMut { ty: a, .. }),
@@ -735,9 +3522,7 @@ fn find_vtable_types_for_unsizing<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
         }
 
         (&ty::TyAdt(source_adt_def, source_substs),
-         &ty::TyAdt(target_adt_def, target_substs)) => {
-            assert_eq!(source_adt_def, target_adt_def);
-
+         &ty::TyAdt(target_adt_def, target_substs)) if source_adt_def == target_adt_def => {
             let kind =
                 monomorphize::custom_coerce_unsize_info(scx, source_ty, target_ty);
 
@@ -748,35 +3533,199 @@ fn find_vtable_types_for_unsizing<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
             let source_fields = &source_adt_def.struct_variant().fields;
             let target_fields = &target_adt_def.struct_variant().fields;
 
-            assert!(coerce_index < source_fields.len() &&
-                    source_fields.len() == target_fields.len());
+            // `CoerceUnsized` impls are checked to coerce one of the fields.
+            debug_assert!(coerce_index < source_fields.len() &&
+                          source_fields.len() == target_fields.len());
+
+            // The fields are normalized, as the pointers they hold may be
+            // projections or `impl Trait` types. Goes through the cache, so
+            // that each level of nested smart pointers is only analyzed once.
+            let tcx = scx.tcx();
+            vtable_types_for_unsizing(scx,
+                                      memos,
+                                      monomorphize::field_ty(tcx,
+                                                             source_substs,
+                                                             &source_fields[coerce_index]),
+                                      monomorphize::field_ty(tcx,
+                                                             target_substs,
+                                                             &target_fields[coerce_index]))
+        }
+        _ => Err((source_ty, target_ty))
+    }
+}
+
+/// The results of `find_vtable_types_for_unsizing()` and
+/// `create_trans_items_for_vtable_methods()` for each pair of types, so that
+/// coercing the same type to the same trait object again and again is cheap.
+/// Types are interned, so the results never go stale during collection.
+struct VtableCache<'tcx> {
+    types: FxHashMap<(ty::Ty<'tcx>, ty::Ty<'tcx>),
+                     Result<(ty::Ty<'tcx>, ty::Ty<'tcx>), (ty::Ty<'tcx>, ty::Ty<'tcx>)>>,
+    methods: FxHashMap<(ty::Ty<'tcx>, ty::Ty<'tcx>), Rc<Vec<(DefId, TransItem<'tcx>)>>>,
+    // Whether the pointee of a coerced pointer is sized, and the tails of
+    // the structs it is coerced between. Both walk the whole type.
+    sized: FxHashMap<ty::Ty<'tcx>, bool>,
+    lockstep_tails: FxHashMap<(ty::Ty<'tcx>, ty::Ty<'tcx>), (ty::Ty<'tcx>, ty::Ty<'tcx>)>,
+}
 
-            find_vtable_types_for_unsizing(scx,
-                                           source_fields[coerce_index].ty(scx.tcx(),
-                                                                          source_substs),
-                                           target_fields[coerce_index].ty(scx.tcx(),
-                                                                          target_substs))
+impl<'tcx> VtableCache<'tcx> {
+    fn new() -> VtableCache<'tcx> {
+        VtableCache {
+            types: FxHashMap(),
+            methods: FxHashMap(),
+            sized: FxHashMap(),
+            lockstep_tails: FxHashMap(),
         }
-        _ => bug!("find_vtable_types_for_unsizing: invalid coercion {:?} -> {:?}",
-                  source_ty,
-                  target_ty)
     }
 }
 
+// Same as `SharedCrateContext::type_is_sized()`, but memoized unless
+// `-Z no-mono-vtable-cache` is given.
+fn type_is_sized<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                           memos: &CollectionMemos<'tcx>,
+                           ty: ty::Ty<'tcx>)
+                           -> bool {
+    if scx.collection_flags().no_mono_vtable_cache {
+        return scx.type_is_sized(ty);
+    }
+
+    if let Some(&sized) = memos.vtables.borrow().sized.get(&ty) {
+        return sized;
+    }
+
+    let sized = scx.type_is_sized(ty);
+    memos.vtables.borrow_mut().sized.insert(ty, sized);
+    sized
+}
+
+// Same as `TyCtxt::struct_lockstep_tails()`, but memoized unless
+// `-Z no-mono-vtable-cache` is given.
+fn struct_lockstep_tails<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                   memos: &CollectionMemos<'tcx>,
+                                   source_ty: ty::Ty<'tcx>,
+                                   target_ty: ty::Ty<'tcx>)
+                                   -> (ty::Ty<'tcx>, ty::Ty<'tcx>) {
+    if scx.collection_flags().no_mono_vtable_cache {
+        return find_struct_lockstep_tails(scx, source_ty, target_ty);
+    }
+
+    let stats = scx.collection_stats();
+    let key = (source_ty, target_ty);
+    if let Some(&tails) = memos.vtables.borrow().lockstep_tails.get(&key) {
+        stats.bump(&stats.lockstep_tails_cache_hits);
+        return tails;
+    }
+
+    stats.bump(&stats.lockstep_tails_cache_misses);
+    let tails = find_struct_lockstep_tails(scx, source_ty, target_ty);
+    memos.vtables.borrow_mut().lockstep_tails.insert(key, tails);
+    tails
+}
+
+// The tails are the types of the last fields, which are not normalized.
+fn find_struct_lockstep_tails<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                        source_ty: ty::Ty<'tcx>,
+                                        target_ty: ty::Ty<'tcx>)
+                                        -> (ty::Ty<'tcx>, ty::Ty<'tcx>) {
+    let tcx = scx.tcx();
+    let (source_tail, target_tail) = tcx.struct_lockstep_tails(source_ty, target_ty);
+    (tcx.normalize_associated_type(&source_tail), tcx.normalize_associated_type(&target_tail))
+}
+
+// Same as `find_vtable_types_for_unsizing()`, but memoized unless
+// `-Z no-mono-vtable-cache` is given.
+fn vtable_types_for_unsizing<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                       memos: &CollectionMemos<'tcx>,
+                                       source_ty: ty::Ty<'tcx>,
+                                       target_ty: ty::Ty<'tcx>)
+                                       -> Result<(ty::Ty<'tcx>, ty::Ty<'tcx>),
+                                                 (ty::Ty<'tcx>, ty::Ty<'tcx>)> {
+    if scx.collection_flags().no_mono_vtable_cache {
+        return find_vtable_types_for_unsizing(scx, memos, source_ty, target_ty);
+    }
+
+    let stats = scx.collection_stats();
+    if let Some(&result) = memos.vtables.borrow().types.get(&(source_ty, target_ty)) {
+        stats.bump(&stats.unsizing_cache_hits);
+        return result;
+    }
+
+    stats.bump(&stats.unsizing_cache_misses);
+    let result = find_vtable_types_for_unsizing(scx, memos, source_ty, target_ty);
+    memos.vtables.borrow_mut().types.insert((source_ty, target_ty), result);
+    result
+}
+
+fn report_invalid_unsizing<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                     span: Span,
+                                     (source_ty, target_ty): (ty::Ty<'tcx>, ty::Ty<'tcx>),
+                                     unsupported: (ty::Ty<'tcx>, ty::Ty<'tcx>)) {
+    let mut diag = scx.sess().struct_span_err(span,
+                                              &format!("cannot translate the unsizing \
+                                                        coercion from `{}` to `{}`",
+                                                       source_ty,
+                                                       target_ty));
+    if unsupported != (source_ty, target_ty) {
+        diag.note(&format!("through its `CoerceUnsized` impls, it amounts to a coercion \
+                            from `{}` to `{}`",
+                           unsupported.0,
+                           unsupported.1));
+    }
+    diag.note("only coercions between pointers, and between structs with a pointer \
+               among their fields, can be unsized");
+    diag.emit();
+}
+
 fn create_fn_trans_item<'a, 'tcx>(instance: Instance<'tcx>) -> TransItem<'tcx> {
     debug!("create_fn_trans_item(instance={})", instance);
     TransItem::Fn(instance)
 }
 
 /// Creates a `TransItem` for each method that is referenced by the vtable for
-/// the given trait/impl pair.
+/// the given trait/impl pair. Returns the method items created, each paired
+/// with the trait method it implements.
 fn create_trans_items_for_vtable_methods<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                                   memos: &CollectionMemos<'tcx>,
                                                    trait_ty: ty::Ty<'tcx>,
                                                    impl_ty: ty::Ty<'tcx>,
-                                                   output: &mut Vec<TransItem<'tcx>>) {
+                                                   output: &mut Vec<TransItem<'tcx>>)
+                                                   -> Rc<Vec<(DefId, TransItem<'tcx>)>> {
     assert!(!trait_ty.needs_subst() && !trait_ty.has_escaping_regions() &&
             !impl_ty.needs_subst() && !impl_ty.has_escaping_regions());
 
+    let vtable_methods = vtable_methods(scx, memos, trait_ty, impl_ty);
+    output.extend(vtable_methods.iter().map(|&(_, item)| item));
+
+    if let ty::TyDynamic(..) = trait_ty.sty {
+        // Also add the destructor. The vtable has a slot for it even if the
+        // type has no drop glue, so this does not go through
+        // `visit_drop_use()`, which would skip such types.
+        let instance = resolve_drop_in_place(scx, memos, impl_ty);
+        visit_instance_use(scx, memos, instance, false, DUMMY_SP, output);
+        record_drop_glue_use(scx, instance, DropGlueUse::Vtable);
+    }
+
+    vtable_methods
+}
+
+// Finds the methods in the vtable for the given trait/impl pair that are
+// translated locally, memoized unless `-Z no-mono-vtable-cache` is given.
+fn vtable_methods<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                            memos: &CollectionMemos<'tcx>,
+                            trait_ty: ty::Ty<'tcx>,
+                            impl_ty: ty::Ty<'tcx>)
+                            -> Rc<Vec<(DefId, TransItem<'tcx>)>> {
+    let use_cache = !scx.collection_flags().no_mono_vtable_cache;
+    let stats = scx.collection_stats();
+    if use_cache {
+        if let Some(methods) = memos.vtables.borrow().methods.get(&(trait_ty, impl_ty)) {
+            stats.bump(&stats.vtable_methods_cache_hits);
+            return methods.clone();
+        }
+        stats.bump(&stats.vtable_methods_cache_misses);
+    }
+
+    let mut vtable_methods = Vec::new();
     if let ty::TyDynamic(ref trait_ty, ..) = trait_ty.sty {
         if let Some(principal) = trait_ty.principal() {
             let poly_trait_ref = principal.with_self_ty(scx.tcx(), impl_ty);
@@ -785,24 +3734,62 @@ fn create_trans_items_for_vtable_methods<'a, 'tcx>(scx: &SharedCrateContext<'a,
             // Walk all methods of the trait, including those of its supertraits
             let methods = traits::get_vtable_methods(scx.tcx(), poly_trait_ref);
             let methods = methods.filter_map(|method| method)
-                .map(|(def_id, substs)| monomorphize::resolve(scx, def_id, substs))
-                .filter(|&instance| should_trans_locally(scx.tcx(), &instance))
-                .map(|instance| create_fn_trans_item(instance));
-            output.extend(methods);
+                .map(|(def_id, substs)| (def_id, monomorphize::resolve(scx, def_id, substs)))
+                .filter(|&(_, instance)| should_trans_locally(scx, memos, &instance))
+                .map(|(def_id, instance)| {
+                    debug_assert_has_mir(scx, &instance);
+                    (def_id, create_fn_trans_item(instance))
+                });
+            vtable_methods.extend(methods);
         }
-        // Also add the destructor
-        visit_drop_use(scx, impl_ty, false, output);
     }
+
+    let vtable_methods = Rc::new(vtable_methods);
+    if use_cache {
+        memos.vtables.borrow_mut().methods.insert((trait_ty, impl_ty), vtable_methods.clone());
+    }
+    vtable_methods
 }
 
 //=-----------------------------------------------------------------------------
 // Root Collection
 //=-----------------------------------------------------------------------------
 
+// Returns true for the test functions that `-Z compile-test-filter` leaves out
+// of the test harness. They are not referenced by anything and are not
+// translated either, which is what makes the filter save compile time. The
+// harness marks them, so that both agree on which tests match.
+fn excluded_by_test_filter<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                     item: &hir::Item)
+                                     -> bool {
+    is_harness_test(scx, item) && attr::contains_name(&item.attrs, "rustc_test_filtered_out")
+}
+
+// Whether the item is a `#[test]` or `#[bench]` function that the test
+// harness built by `--test` runs. These are always roots, unless excluded by
+// `-Z compile-test-filter`.
+fn is_harness_test<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>, item: &hir::Item) -> bool {
+    scx.sess().opts.test &&
+    (attr::contains_name(&item.attrs, "test") || attr::contains_name(&item.attrs, "bench"))
+}
+
+// The harness rejects generic tests already, this keeps them from being
+// skipped without notice if it ever does not.
+fn report_generic_test_fn<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>, span: Span) {
+    scx.sess().struct_span_err(span, "test functions cannot be generic over types")
+              .note("the test harness needs a single instance of the function to run")
+              .emit();
+}
+
 struct RootCollector<'b, 'a: 'b, 'tcx: 'a + 'b> {
     scx: &'b SharedCrateContext<'a, 'tcx>,
+    memos: &'b CollectionMemos<'tcx>,
     mode: TransItemCollectionMode,
     output: &'b mut Vec<TransItem<'tcx>>,
+    default_impls: &'b mut Vec<EagerDefaultImpl<'tcx>>,
+    // Whether only the items that are referred to from outside of the crate
+    // by symbol name are roots, see `roots_limited_to_exports()`.
+    exports_only: bool,
 }
 
 impl<'b, 'a, 'v> ItemLikeVisitor<'v> for RootCollector<'b, 'a, 'v> {
@@ -821,8 +3808,10 @@ impl<'b, 'a, 'v> ItemLikeVisitor<'v> for RootCollector<'b, 'a, 'v> {
             hir::ItemImpl(..) => {
                 if self.mode == TransItemCollectionMode::Eager {
                     create_trans_items_for_default_impls(self.scx,
+                                                         self.memos,
                                                          item,
-                                                         self.output);
+                                                         self.output,
+                                                         self.default_impls);
                 }
             }
 
@@ -835,29 +3824,66 @@ impl<'b, 'a, 'v> ItemLikeVisitor<'v> for RootCollector<'b, 'a, 'v> {
                         debug!("RootCollector: ADT drop-glue for {}",
                                def_id_to_string(self.scx.tcx(), def_id));
 
+                        // Types without drop glue get no root, whatever the
+                        // mode: nothing ever calls their empty glue, except
+                        // through vtables, which collect it themselves.
                         let ty = def_ty(self.scx, def_id, Substs::empty());
-                        visit_drop_use(self.scx, ty, true, self.output);
+                        debug_assert!(!ty.needs_subst(),
+                                      "drop glue root for non-monomorphic type {:?}", ty);
+                        visit_drop_use(self.scx, self.memos, ty, true, self.output);
                     }
                 }
             }
             hir::ItemStatic(..) => {
-                debug!("RootCollector: ItemStatic({})",
-                       def_id_to_string(self.scx.tcx(),
-                                        self.scx.tcx().hir.local_def_id(item.id)));
-                self.output.push(TransItem::Static(item.id));
+                // Statics are roots even if nothing refers to them, which
+                // `#[used]` statics rely on (see `TransItem::is_used_static`).
+                // Those inside function bodies, and all of them if only the
+                // exports are roots, are only translated along with an item
+                // that refers to them, unless something outside of the
+                // crate's code might.
+                let def_id = self.scx.tcx().hir.local_def_id(item.id);
+                let is_used = attr::contains_name(&item.attrs, "used");
+                debug!("RootCollector: ItemStatic({}, used: {})",
+                       def_id_to_string(self.scx.tcx(), def_id),
+                       is_used);
+                let is_root = is_used ||
+                              has_extern_indicator(self.scx, &item.attrs) ||
+                              !self.exports_only &&
+                              (!is_nested_static(self.scx.tcx(), def_id) ||
+                               self.scx.exported_symbols().contains(&item.id));
+                if is_root && check_static_is_monomorphic(self.scx, def_id) {
+                    self.output.push(TransItem::Static(item.id));
+                }
             }
             hir::ItemConst(..) => {
                 // const items only generate translation items if they are
                 // actually used somewhere. Just declaring them is insufficient.
             }
-            hir::ItemFn(.., ref generics, _) => {
-                if !generics.is_type_parameterized() {
+            hir::ItemFn(_, _, constness, _, ref generics, _) => {
+                // Code outside of Rust may link against it, whatever else
+                // is going on, and so may other crates in the case of weak
+                // lang items. Generic functions cannot be linked against
+                // and are left to the `no_mangle_generic_items` lint. Tests
+                // are run by the harness, which only gets to them through
+                // the descriptors it builds; its `main` is the entry
+                // function, see `entry_fn_instances()`.
+                let is_extern = has_extern_indicator(self.scx, &item.attrs) ||
+                                is_weak_lang_item(&item.attrs);
+                let is_test = is_harness_test(self.scx, item);
+                if is_test && generics.is_type_parameterized() {
+                    report_generic_test_fn(self.scx, item.span);
+                } else if !generics.is_type_parameterized() &&
+                          (is_extern ||
+                           self.exports_only && attr::contains_name(&item.attrs, "lang") ||
+                           !self.exports_only &&
+                           !excluded_by_test_filter(self.scx, item) &&
+                           (is_test || !is_compile_time_only(self.scx, item.id, constness))) {
                     let def_id = self.scx.tcx().hir.local_def_id(item.id);
 
                     debug!("RootCollector: ItemFn({})",
                            def_id_to_string(self.scx.tcx(), def_id));
 
-                    let instance = Instance::mono(self.scx.tcx(), def_id);
+                    let instance = create_root_fn_instance(self.scx.tcx(), def_id);
                     self.output.push(TransItem::Fn(instance));
                 }
             }
@@ -873,6 +3899,7 @@ impl<'b, 'a, 'v> ItemLikeVisitor<'v> for RootCollector<'b, 'a, 'v> {
         match ii.node {
             hir::ImplItemKind::Method(hir::MethodSig {
                 ref generics,
+                constness,
                 ..
             }, _) => {
                 let hir_map = &self.scx.tcx().hir;
@@ -889,13 +3916,18 @@ impl<'b, 'a, 'v> ItemLikeVisitor<'v> for RootCollector<'b, 'a, 'v> {
                     }
                 };
 
-                if !generics.is_type_parameterized() && !is_impl_generic {
+                let is_extern = has_extern_indicator(self.scx, &ii.attrs);
+                let is_generic = generics.is_type_parameterized() || is_impl_generic;
+                if !is_generic &&
+                   (is_extern ||
+                    !self.exports_only &&
+                    !is_compile_time_only(self.scx, ii.id, constness)) {
                     let def_id = self.scx.tcx().hir.local_def_id(ii.id);
 
                     debug!("RootCollector: MethodImplItem({})",
                            def_id_to_string(self.scx.tcx(), def_id));
 
-                    let instance = Instance::mono(self.scx.tcx(), def_id);
+                    let instance = create_root_fn_instance(self.scx.tcx(), def_id);
                     self.output.push(TransItem::Fn(instance));
                 }
             }
@@ -904,9 +3936,50 @@ impl<'b, 'a, 'v> ItemLikeVisitor<'v> for RootCollector<'b, 'a, 'v> {
     }
 }
 
+// Whether the item has `#[no_mangle]` or `#[export_name]`, so that code
+// outside of Rust can link against it. Such functions are always roots unless
+// they are generic.
+fn has_extern_indicator<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  attrs: &[ast::Attribute])
+                                  -> bool {
+    attr::contains_extern_indicator(scx.sess().diagnostic(), attrs)
+}
+
+// Whether the item defines a weak lang item like `panic_fmt`. Other crates
+// refer to these by a fixed symbol name, nothing in this crate calls them.
+fn is_weak_lang_item(attrs: &[ast::Attribute]) -> bool {
+    weak_lang_items::link_name(attrs).is_some()
+}
+
+// Whether the function is a `const fn` that other crates cannot call. Such
+// functions are not roots: calls to them that are evaluated at compile time
+// need no translation item, and the others lead to it anyway.
+fn is_compile_time_only<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  node_id: ast::NodeId,
+                                  constness: hir::Constness)
+                                  -> bool {
+    constness == hir::Constness::Const && !scx.exported_symbols().contains(&node_id)
+}
+
+// Creates the instance of a function that is a root of the translation item
+// graph. Roots have no type parameters but they may still be generic over
+// lifetimes, e.g. through early-bound lifetimes in the signature of the entry
+// function. Those don't matter for translation and are erased.
+fn create_root_fn_instance<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                     def_id: DefId)
+                                     -> Instance<'tcx> {
+    let substs = Substs::for_item(tcx, def_id, |_, _| tcx.mk_region(ty::ReErased), |def, _| {
+        let span = tcx.hir.span_if_local(def_id).unwrap_or(DUMMY_SP);
+        span_bug!(span, "root function {:?} has type parameter `{}`", def_id, def.name)
+    });
+    Instance::new(def_id, substs)
+}
+
 fn create_trans_items_for_default_impls<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                                  memos: &CollectionMemos<'tcx>,
                                                   item: &'tcx hir::Item,
-                                                  output: &mut Vec<TransItem<'tcx>>) {
+                                                  output: &mut Vec<TransItem<'tcx>>,
+                                                  default_impls: &mut Vec<EagerDefaultImpl<'tcx>>) {
     let tcx = scx.tcx();
     match item.node {
         hir::ItemImpl(_,
@@ -924,31 +3997,39 @@ fn create_trans_items_for_default_impls<'a, 'tcx>(scx: &SharedCrateContext<'a, '
                    def_id_to_string(tcx, impl_def_id));
 
             if let Some(trait_ref) = tcx.impl_trait_ref(impl_def_id) {
+                let provided_methods = tcx.provided_trait_methods(trait_ref.def_id);
+                if provided_methods.is_empty() {
+                    return
+                }
+
                 let callee_substs = tcx.erase_regions(&trait_ref.substs);
                 let overridden_methods: FxHashSet<_> =
                     impl_item_refs.iter()
                                   .map(|iiref| iiref.name)
                                   .collect();
-                for method in tcx.provided_trait_methods(trait_ref.def_id) {
-                    if overridden_methods.contains(&method.name) {
-                        continue;
-                    }
-
-                    if !tcx.item_generics(method.def_id).types.is_empty() {
-                        continue;
-                    }
-
-                    let instance =
-                        monomorphize::resolve(scx, method.def_id, callee_substs);
 
-                    let predicates = tcx.item_predicates(instance.def_id()).predicates
-                        .subst(tcx, instance.substs);
-                    if !traits::normalize_and_test_predicates(tcx, predicates) {
-                        continue;
-                    }
-
-                    if should_trans_locally(tcx, &instance) {
-                        output.push(create_fn_trans_item(instance));
+                for method in provided_methods {
+                    match default_impl_instance(scx,
+                                                memos,
+                                                &method,
+                                                callee_substs,
+                                                &overridden_methods) {
+                        Ok(instance) => {
+                            output.push(create_fn_trans_item(instance));
+                            default_impls.push(EagerDefaultImpl {
+                                method: method.def_id,
+                                impl_def_id: impl_def_id,
+                                instance: instance,
+                            });
+                        }
+                        Err(skip) => {
+                            if scx.collection_flags().print_default_impl_skips {
+                                println!("DEFAULT_IMPL_SKIPPED {} in {}: {}",
+                                         def_id_to_string(tcx, method.def_id),
+                                         def_id_to_string(tcx, impl_def_id),
+                                         skip.describe());
+                            }
+                        }
                     }
                 }
             }
@@ -959,24 +4040,693 @@ fn create_trans_items_for_default_impls<'a, 'tcx>(scx: &SharedCrateContext<'a, '
     }
 }
 
+// A provided trait method instantiated eagerly for an impl, listed by
+// `-Z print-eager-default-impls`.
+struct EagerDefaultImpl<'tcx> {
+    // The provided method in the trait.
+    method: DefId,
+    impl_def_id: DefId,
+    // What the method resolved to for the impl. This is not necessarily the
+    // trait's version of the method, a more general impl might provide a
+    // `default fn` for it.
+    instance: Instance<'tcx>,
+}
+
+// Implements `-Z print-eager-default-impls`: lists which instance every
+// eagerly instantiated provided method resolved to, one line per method like
+// `EAGER_DEFAULT_IMPL Trait::method for Type => fn instance`.
+fn print_eager_default_impls<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                       default_impls: &[EagerDefaultImpl<'tcx>]) {
+    let tcx = scx.tcx();
+    let mut lines: Vec<_> = default_impls.iter().map(|default_impl| {
+        format!("EAGER_DEFAULT_IMPL {} for {} => {}",
+                tcx.item_path_str(default_impl.method),
+                tcx.item_type(default_impl.impl_def_id),
+                create_fn_trans_item(default_impl.instance).to_string(tcx))
+    }).collect();
+    lines.sort();
+
+    for line in lines {
+        println!("{}", line);
+    }
+}
+
+// The reasons for not instantiating a provided trait method eagerly for an
+// impl, see `default_impl_instance()`.
+enum DefaultImplSkip<'tcx> {
+    // The impl provides its own version of the method.
+    Overridden,
+    // The method has type parameters of its own, we don't know which
+    // instances will be needed.
+    GenericMethod,
+    // The where clauses of the method do not hold for the impl, so it can
+    // never be called. Contains the failing predicate, if a single one can be
+    // blamed.
+    PredicatesUnsatisfied(Option<ty::Predicate<'tcx>>),
+    // The instance is not translated in this crate.
+    NotTranslatedLocally,
+}
+
+impl<'tcx> DefaultImplSkip<'tcx> {
+    fn describe(&self) -> String {
+        match *self {
+            DefaultImplSkip::Overridden => {
+                "overridden by the impl".to_string()
+            }
+            DefaultImplSkip::GenericMethod => {
+                "the method has type parameters of its own".to_string()
+            }
+            DefaultImplSkip::PredicatesUnsatisfied(Some(ref predicate)) => {
+                format!("the predicate `{}` does not hold", predicate)
+            }
+            DefaultImplSkip::PredicatesUnsatisfied(None) => {
+                "the predicates of the method do not hold".to_string()
+            }
+            DefaultImplSkip::NotTranslatedLocally => {
+                "the method is not translated in this crate".to_string()
+            }
+        }
+    }
+}
+
+// Returns the instance of the provided trait method `method` to translate
+// eagerly for the impl with the trait substitutions `callee_substs`, or the
+// reason why there is none.
+fn default_impl_instance<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                   memos: &CollectionMemos<'tcx>,
+                                   method: &ty::AssociatedItem,
+                                   callee_substs: &'tcx Substs<'tcx>,
+                                   overridden_methods: &FxHashSet<ast::Name>)
+                                   -> Result<Instance<'tcx>, DefaultImplSkip<'tcx>> {
+    let tcx = scx.tcx();
+    let is_generic = !tcx.item_generics(method.def_id).types.is_empty();
+
+    if overridden_methods.contains(&method.name) {
+        // The impl's own version of the method is a root of its own, check
+        // that resolution agrees that it replaces the provided one.
+        if cfg!(debug_assertions) && !is_generic {
+            let instance = monomorphize::resolve(scx, method.def_id, callee_substs);
+            debug_assert!(instance.def_id() != method.def_id,
+                          "overridden method `{}` resolved to the provided version",
+                          def_id_to_string(tcx, method.def_id));
+        }
+        return Err(DefaultImplSkip::Overridden);
+    }
+
+    if is_generic {
+        return Err(DefaultImplSkip::GenericMethod);
+    }
+
+    let instance = monomorphize::resolve(scx, method.def_id, callee_substs);
+    debug!("default_impl_instance: `{}` resolved to `{}`",
+           def_id_to_string(tcx, method.def_id),
+           instance);
+
+    // Most provided methods have no predicates of their own, and do not need
+    // the trait machinery to tell that these hold.
+    let stats = scx.collection_stats();
+    let predicates = tcx.item_predicates(instance.def_id()).predicates;
+    if predicates.is_empty() {
+        stats.bump(&stats.default_impl_predicates_skipped);
+    } else {
+        stats.bump(&stats.default_impl_predicates_tested);
+        let predicates = predicates.subst(tcx, instance.substs);
+        if !traits::normalize_and_test_predicates(tcx, predicates.clone()) {
+            let failing = predicates.into_iter().find(|predicate| {
+                !traits::normalize_and_test_predicates(tcx, vec![predicate.clone()])
+            });
+            return Err(DefaultImplSkip::PredicatesUnsatisfied(failing));
+        }
+    }
+
+    if !should_trans_locally(scx, memos, &instance) {
+        debug!("default_impl_instance: `{}` has no MIR available locally", instance);
+        return Err(DefaultImplSkip::NotTranslatedLocally);
+    }
+
+    debug_assert_has_mir(scx, &instance);
+    Ok(instance)
+}
+
+// Checks that a method instance picked for a vtable or for eager translation
+// has a body. `monomorphize::resolve()` selects the most specialized impl
+// that applies and takes the method from the closest impl or trait providing
+// it, so this only fails if that goes wrong.
+fn debug_assert_has_mir<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  instance: &Instance<'tcx>) {
+    if cfg!(debug_assertions) {
+        if let ty::InstanceDef::Item(def_id) = instance.def {
+            assert!(scx.tcx().maybe_item_mir(def_id).is_some(), "no MIR for `{}`", instance);
+        }
+    }
+}
+
 /// Scan the MIR in order to find function calls, closures, and drop-glue
+///
+/// `const_context` tells whether the body is evaluated at compile time, like
+/// the initializers of statics and constants. The values promoted in a body
+/// always are. Returns whether the body has cleanup blocks, whose landing pads
+/// refer to `unwind_lang_items()`.
 fn collect_neighbours<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                memos: &CollectionMemos<'tcx>,
                                 instance: Instance<'tcx>,
-                                output: &mut Vec<TransItem<'tcx>>)
+                                const_context: bool,
+                                output: &mut Vec<TransItem<'tcx>>,
+                                references: &mut Option<ItemReferences<'tcx>>,
+                                mir_uses: &mut MirUseCache<'tcx>)
+                                -> bool
 {
-    let mir = scx.tcx().instance_mir(instance.def);
+    let uses = mir_uses.uses(scx.tcx(), instance.def);
+
+    // Getters, constructors and many shims do not refer to any other item,
+    // so there is nothing to resolve for them.
+    let stats = scx.collection_stats();
+    if uses.is_empty() {
+        stats.bump(&stats.trivial_bodies_skipped);
+        return uses.has_cleanup;
+    }
+    stats.bump(&stats.mir_bodies_visited);
+
+    match uses.body_blocks {
+        // The uses in the blocks that a constant discriminant rules out in
+        // this instance are skipped, as they are not translated either.
+        Some(ref blocks) => {
+            let reachable = {
+                let mir = scx.tcx().instance_mir(instance.def);
+                reachable_blocks(scx, &mir, instance.substs)
+            };
+            let body = uses.body.iter()
+                                .zip(blocks)
+                                .filter(|&(_, bb)| reachable.contains(bb.index()))
+                                .map(|(mir_use, _)| mir_use);
+            visit_mir_uses(scx,
+                           memos,
+                           instance,
+                           const_context,
+                           body,
+                           output,
+                           references,
+                           mir_uses);
+        }
+        None => {
+            visit_mir_uses(scx,
+                           memos,
+                           instance,
+                           const_context,
+                           &uses.body,
+                           output,
+                           references,
+                           mir_uses);
+        }
+    }
+
+    // Many promoted values, like references to literals or to non-generic
+    // functions, do not depend on the substs, so their neighbours are the
+    // same for all instances. Each promoted value is visited on its own, so
+    // that the neighbours recorded for it do not depend on those of the
+    // rest of the body.
+    for (index, &(ref promoted_uses, needs_subst)) in uses.promoted.iter().enumerate() {
+        let promoted = mir::Promoted::new(index);
+        if !needs_subst {
+            if let Some(neighbours) = mir_uses.promoted_neighbours(instance.def, promoted) {
+                output.extend(neighbours.iter().cloned());
+                continue;
+            }
+        }
+
+        let start = output.len();
+        visit_mir_uses(scx, memos, instance, true, promoted_uses, output, references,
+                       mir_uses);
+        if !needs_subst {
+            mir_uses.record_promoted_neighbours(instance.def, promoted, &output[start..]);
+        }
+    }
+
+    uses.has_cleanup
+}
 
-    let mut visitor = MirNeighborCollector {
+fn visit_mir_uses<'a, 'b, 'tcx: 'b, I>(scx: &SharedCrateContext<'a, 'tcx>,
+                                       memos: &CollectionMemos<'tcx>,
+                                       instance: Instance<'tcx>,
+                                       const_context: bool,
+                                       uses: I,
+                                       output: &mut Vec<TransItem<'tcx>>,
+                                       references: &mut Option<ItemReferences<'tcx>>,
+                                       mir_uses: &mut MirUseCache<'tcx>)
+    where I: IntoIterator<Item=&'b (Span, MirUse<'tcx>)>
+{
+    let mut collector = MirNeighborCollector {
         scx: scx,
-        mir: &mir,
+        memos: memos,
         output: output,
-        param_substs: instance.substs
+        references: references,
+        instance: instance,
+        param_substs: instance.substs,
+        const_context: const_context,
+    };
+
+    for &(span, ref mir_use) in uses {
+        collector.visit_use(span, mir_use, mir_uses);
+    }
+}
+
+// Creates translation items for the values in the body of `instance` that
+// have been promoted to `'static` references. Each of them ends up in a global
+// of its own, which the item gives a name and a place next to the function.
+// If the function promotes the same value more than once, the constant cache
+// hands all of these promotions the same global.
+fn collect_promoted_values<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                     instance: Instance<'tcx>,
+                                     output: &mut Vec<TransItem<'tcx>>)
+{
+    let mir = scx.tcx().instance_mir(instance.def);
+
+    for (index, promoted) in mir.promoted.iter_enumerated() {
+        // Other promoted values, like the shuffle indices of SIMD intrinsics,
+        // are not kept in a global of their own.
+        if let ty::TyRef(..) = promoted.return_ty.sty {
+            output.push(TransItem::Promoted(instance, index));
+        }
+    }
+}
+
+// Checks that the MIR of an upstream function comes with all the promoted
+// constants its body refers to. These are encoded into the crate metadata
+// together with the body, but a crate built by a broken or mismatched
+// compiler might lack them, and translating the function would then ICE.
+// Reports an error and returns false if some are missing.
+fn promoted_mir_complete<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                                   instance: Instance<'tcx>,
+                                   cache: &mut DefIdMap<bool>)
+                                   -> bool {
+    let def_id = match instance.def {
+        ty::InstanceDef::Item(def_id) if !def_id.is_local() => def_id,
+        _ => return true
+    };
+
+    if let Some(&complete) = cache.get(&def_id) {
+        return complete;
+    }
+
+    let mir = tcx.instance_mir(instance.def);
+    let mut finder = PromotedUseFinder { required: 0 };
+    finder.visit_mir(&mir);
+
+    let complete = finder.required <= mir.promoted.len();
+    if !complete {
+        let krate = tcx.crate_name(def_id.krate);
+        tcx.sess.struct_err(&format!("the MIR of `{}` in crate `{}` refers to {} promoted \
+                                      constants, but only {} are present in the crate's \
+                                      metadata",
+                                     tcx.item_path_str(def_id),
+                                     krate,
+                                     finder.required,
+                                     mir.promoted.len()))
+                .note(&format!("the metadata of crate `{}` is incomplete, try recompiling it \
+                                with the current compiler",
+                               krate))
+                .emit();
+    }
+
+    cache.insert(def_id, complete);
+    complete
+}
+
+// Finds the number of promoted constants a MIR body needs, i.e. the highest
+// index of a promoted constant used in the body plus one.
+struct PromotedUseFinder {
+    required: usize,
+}
+
+impl<'tcx> MirVisitor<'tcx> for PromotedUseFinder {
+    fn visit_constant(&mut self, constant: &mir::Constant<'tcx>, location: Location) {
+        if let mir::Literal::Promoted { index } = constant.literal {
+            self.required = cmp::max(self.required, index.index() + 1);
+        }
+
+        self.super_constant(constant, location);
+    }
+}
+
+//=-----------------------------------------------------------------------------
+// Reporting
+//=-----------------------------------------------------------------------------
+
+// Groups the function translation items in `items` by the `DefId` of the
+// function they are an instance of.
+fn group_instances_by_def_id<'b, 'tcx, I>(items: I) -> DefIdMap<Vec<Instance<'tcx>>>
+    where I: Iterator<Item=&'b TransItem<'tcx>>, 'tcx: 'b
+{
+    let mut groups = DefIdMap();
+    for item in items {
+        if let TransItem::Fn(instance) = *item {
+            groups.entry(instance.def_id()).or_insert(Vec::new()).push(instance);
+        }
+    }
+    groups
+}
+
+const DEFAULT_MONOMORPHIZATION_BLOAT_THRESHOLD: usize = 100;
+
+// Returns the level of `lint` at the local item `def_id`, or for the whole
+// crate if the item is upstream.
+fn item_level_source<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
+                               def_id: DefId,
+                               lint: &'static lint::Lint)
+                               -> lint::LevelSource {
+    match tcx.hir.as_local_node_id(def_id) {
+        Some(node_id) => lint::node_level_source(tcx.sess, &tcx.hir, node_id, lint),
+        None => lint::crate_level_source(tcx.sess, tcx.hir.krate(), lint),
+    }
+}
+
+// Implements the `monomorphization_bloat` lint, which fires for functions
+// with more instances than `-Z monomorphization-bloat-threshold` in the
+// collected set. The lint level is looked up at the function, upstream
+// functions get the level of the crate.
+fn check_monomorphization_bloat<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                          state: &CollectionState<'tcx>) {
+    let tcx = scx.tcx();
+    let threshold = scx.collection_flags()
+                       .monomorphization_bloat_threshold
+                       .unwrap_or(DEFAULT_MONOMORPHIZATION_BLOAT_THRESHOLD);
+
+    let mut bloated: Vec<_> = group_instances_by_def_id(state.visited.iter())
+        .into_iter()
+        .map(|(def_id, instances)| {
+            // Shims are not what the user wrote, don't count them.
+            let instances: Vec<_> = instances.into_iter()
+                                             .filter(|instance| match instance.def {
+                                                 ty::InstanceDef::Item(_) => true,
+                                                 _ => false,
+                                             })
+                                             .collect();
+            (def_id_to_string(tcx, def_id), def_id, instances)
+        })
+        .filter(|&(_, _, ref instances)| instances.len() > threshold)
+        .map(|(name, def_id, instances)| {
+            let level_source = item_level_source(tcx, def_id, MONOMORPHIZATION_BLOAT);
+            (name, def_id, instances, level_source)
+        })
+        .filter(|&(_, _, _, level_source)| level_source.0 != lint::Allow)
+        .collect();
+    bloated.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let printer = DefPathBasedNames::new(tcx, true, true);
+    let lints = tcx.sess.lint_store.borrow();
+
+    for (_, def_id, instances, level_source) in bloated {
+        let span = if let Some(span) = tcx.hir.span_if_local(def_id) {
+            MultiSpan::from_span(span)
+        } else {
+            // There is no span for upstream functions, point at the local
+            // items using them instead.
+            let mut spans: Vec<_> = state.upstream_users
+                                         .as_ref()
+                                         .and_then(|users| users.get(&def_id))
+                                         .into_iter()
+                                         .flat_map(|users| users.iter())
+                                         .filter_map(|&user| tcx.hir.span_if_local(user))
+                                         .collect();
+            spans.sort();
+            MultiSpan::from_spans(spans)
+        };
+
+        let msg = format!("`{}` is instantiated {} times",
+                          tcx.item_path_str(def_id),
+                          instances.len());
+        let mut diag = lint::raw_struct_lint(tcx.sess,
+                                             &lints,
+                                             MONOMORPHIZATION_BLOAT,
+                                             level_source,
+                                             Some(span),
+                                             &msg);
+
+        let mut examples: Vec<_> = instances.iter()
+                                            .map(|&instance| {
+                                                let mut output = String::new();
+                                                printer.push_instance_as_string(instance,
+                                                                                &mut output);
+                                                output
+                                            })
+                                            .collect();
+        examples.sort();
+        examples.truncate(3);
+        diag.note(&format!("instances include `{}`", examples.join("`, `")));
+        diag.emit();
+    }
+}
+
+// Implements the `uncalled_vtable_methods` lint, which fires for trait object
+// coercions whose vtable instantiates methods that are neither called
+// directly nor through any trait object of the same trait. The size of such
+// methods is estimated by the number of statements in their MIR. The lint
+// level is looked up at the function containing the coercion, and only the
+// coercions where it is not allowed have been recorded.
+fn check_uncalled_vtable_methods<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                           references: &ItemReferences<'tcx>) {
+    let tcx = scx.tcx();
+    let mut casts: Vec<_> = references.vtable_casts
+        .iter()
+        .filter_map(|(&(span, source_ty, target_ty), &(user, ref methods))| {
+            let level_source = item_level_source(tcx, user, UNCALLED_VTABLE_METHODS);
+            let mut uncalled: Vec<_> = methods.iter()
+                .filter(|&&(trait_method, item)| {
+                    !references.called.contains(&item) &&
+                    !references.virtually_called.contains(&trait_method)
+                })
+                .filter_map(|&(_, item)| match item {
+                    TransItem::Fn(instance) => {
+                        let mir = tcx.instance_mir(instance.def);
+                        let size = mir.basic_blocks()
+                                      .iter()
+                                      .map(|data| data.statements.len() + 1)
+                                      .sum::<usize>();
+                        Some((item.to_string(tcx), size))
+                    }
+                    _ => None
+                })
+                .collect();
+
+            if uncalled.is_empty() {
+                None
+            } else {
+                uncalled.sort();
+                Some((span, source_ty.to_string(), target_ty.to_string(), uncalled, level_source))
+            }
+        })
+        .collect();
+    casts.sort_by(|a, b| (a.0, &a.1, &a.2, &a.3).cmp(&(b.0, &b.1, &b.2, &b.3)));
+
+    let lints = tcx.sess.lint_store.borrow();
+
+    for (span, source_ty, target_ty, uncalled, level_source) in casts {
+        let msg = format!("coercing `{}` to `{}` instantiates {} method{} that {} never called",
+                          source_ty,
+                          target_ty,
+                          uncalled.len(),
+                          if uncalled.len() == 1 { "" } else { "s" },
+                          if uncalled.len() == 1 { "is" } else { "are" });
+        let mut diag = lint::raw_struct_lint(tcx.sess,
+                                             &lints,
+                                             UNCALLED_VTABLE_METHODS,
+                                             level_source,
+                                             Some(MultiSpan::from_span(span)),
+                                             &msg);
+
+        let mut total_size = 0;
+        for (method, size) in uncalled {
+            diag.note(&format!("`{}` is only referenced from the vtable ({} MIR statements)",
+                               method,
+                               size));
+            total_size += size;
+        }
+        diag.note(&format!("about {} MIR statements are translated without ever being executed",
+                           total_size));
+        diag.emit();
+    }
+}
+
+/// Prints the families of instances of the same function whose substitutions
+/// only differ in the lengths of array types (`-Z print-mono-array-families`).
+/// Every family could be replaced by a single instance if the function took
+/// a slice instead of the array, so each family member but one is a
+/// potential saving.
+pub fn print_array_length_families<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                             items: &FxHashSet<TransItem<'tcx>>) {
+    let tcx = scx.tcx();
+    let mut families = Vec::new();
+
+    for (def_id, instances) in group_instances_by_def_id(items.iter()) {
+        if instances.len() < 2 {
+            continue
+        }
+
+        let mut by_erased_substs = FxHashMap();
+        for instance in instances {
+            if let ty::InstanceDef::Item(_) = instance.def {
+                let mut lengths = Vec::new();
+                let erased_substs = instance.substs.fold_with(&mut ty::fold::BottomUpFolder {
+                    tcx: tcx,
+                    fldop: |ty| match ty.sty {
+                        ty::TyArray(element_ty, length) => {
+                            lengths.push(length);
+                            tcx.mk_array(element_ty, 0)
+                        }
+                        _ => ty
+                    }
+                });
+
+                if !lengths.is_empty() {
+                    by_erased_substs.entry(erased_substs)
+                                    .or_insert(Vec::new())
+                                    .push(lengths);
+                }
+            }
+        }
+
+        for (_, mut lengths) in by_erased_substs {
+            if lengths.len() > 1 {
+                lengths.sort();
+                families.push((def_id_to_string(tcx, def_id), lengths));
+            }
+        }
+    }
+
+    if families.is_empty() {
+        return
+    }
+
+    families.sort();
+
+    let mut potential_savings = 0;
+    for &(ref name, ref lengths) in &families {
+        let lengths: Vec<_> = lengths.iter()
+                                     .map(|lengths| {
+                                         let lengths: Vec<_> = lengths.iter()
+                                                                      .map(|l| l.to_string())
+                                                                      .collect();
+                                         lengths.join("/")
+                                     })
+                                     .collect();
+        println!("ARRAY_LENGTH_FAMILY fn {} lengths=[{}]", name, lengths.join(", "));
+        potential_savings += lengths.len() - 1;
+    }
+
+    scx.sess().note_without_error(&format!(
+        "{} function instance{} only differ from another instance of the same function \
+         in array lengths; consider passing slices instead of arrays to share one instance",
+        potential_savings,
+        if potential_savings == 1 { "" } else { "s" }));
+}
+
+// Implements `-Z print-address-only-items`: lists the functions that are only
+// ever turned into function pointers or put into vtables, but never called
+// directly anywhere in the program. Such functions can only be invoked
+// indirectly and often end up in registries or dispatch tables that are never
+// used in full.
+fn print_address_only_items<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                      references: &ItemReferences<'tcx>) {
+    let sess = scx.sess();
+
+    // In a library, direct calls might still come from downstream crates.
+    if sess.crate_types.borrow().iter().any(|&ct| ct != config::CrateTypeExecutable) {
+        sess.warn("-Z print-address-only-items is only accurate for executables, \
+                   functions might still be called from other crates");
+    }
+
+    let mut items: Vec<_> = references.address_taken
+        .iter()
+        .filter(|&(item, _)| {
+            match *item {
+                TransItem::Fn(Instance { def: ty::InstanceDef::Item(_), .. }) => {
+                    !references.called.contains(item)
+                }
+                _ => false
+            }
+        })
+        .map(|(item, &(kind, span))| {
+            let kind = match kind {
+                ReferenceKind::Reify => "fn-ptr",
+                ReferenceKind::Vtable => "vtable",
+                ReferenceKind::Call => bug!("called item recorded as address-taken"),
+            };
+            (item.to_string(scx.tcx()), kind, sess.codemap().span_to_string(span))
+        })
+        .collect();
+
+    items.sort();
+
+    for (item, kind, span) in items {
+        println!("ADDRESS_ONLY_ITEM {} ({}) @@ {}", item, kind, span);
+    }
+}
+
+// Implements `-Z print-cross-crate-mono-chains`: follows every collected item
+// back along the path it has first been discovered on and prints the crates
+// defining the functions along that path, aggregated over all items. Long
+// chains show across how many crate boundaries a local use instantiates
+// generic code, and thus where type erasure would save the most work.
+fn print_cross_crate_mono_chains<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                           state: &CollectionState<'tcx>) {
+    let tcx = scx.tcx();
+    let first_users = state.first_users.as_ref().unwrap();
+
+    let defining_crate = |item: TransItem<'tcx>| {
+        match item {
+            TransItem::Fn(Instance { def: ty::InstanceDef::Item(def_id), .. }) |
+            TransItem::Promoted(Instance { def: ty::InstanceDef::Item(def_id), .. }, _) => {
+                Some(def_id.krate)
+            }
+            TransItem::Static(_) => Some(LOCAL_CRATE),
+            // Shims and glue are instantiated on behalf of their users.
+            _ => None,
+        }
     };
 
-    visitor.visit_mir(&mir);
-    for promoted in &mir.promoted {
-        visitor.mir = promoted;
-        visitor.visit_mir(promoted);
+    let mut chains = FxHashMap();
+    for &item in &state.visited {
+        let mut crates = Vec::new();
+        let mut current = Some(item);
+        while let Some(user) = current {
+            if let Some(krate) = defining_crate(user) {
+                if crates.last() != Some(&krate) {
+                    crates.push(krate);
+                }
+            }
+            current = first_users.get(&user).cloned();
+        }
+
+        if crates.len() < 2 {
+            continue
+        }
+        crates.reverse();
+
+        let item_name = item.to_string(tcx);
+        let entry = chains.entry(crates).or_insert((0, item_name.clone()));
+        entry.0 += 1;
+        if item_name < entry.1 {
+            entry.1 = item_name;
+        }
+    }
+
+    let mut chains: Vec<_> = chains.into_iter()
+        .map(|(crates, (count, example))| {
+            let names: Vec<_> = crates.iter()
+                                      .map(|&krate| tcx.crate_name(krate).to_string())
+                                      .collect();
+            (crates.len(), count, names.join(" -> "), example)
+        })
+        .collect();
+    // Longest chains first, the most common ones first among equally long ones.
+    chains.sort_by(|a, b| (b.0, b.1, &a.2).cmp(&(a.0, a.1, &b.2)));
+
+    for (length, count, crates, example) in chains {
+        println!("CROSS_CRATE_MONO_CHAIN length={} items={} {} (e.g. {})",
+                 length,
+                 count,
+                 crates,
+                 example);
     }
 }
 
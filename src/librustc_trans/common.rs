@@ -536,6 +536,17 @@ pub fn requests_inline<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
     instance: &ty::Instance<'tcx>
 ) -> bool {
+    // Functions that must not be inlined keep a single definition shared by
+    // all codegen units, even closures, which would otherwise be copied into
+    // each of them. Duplicating a naked function is never what its author
+    // wants either.
+    if let ty::InstanceDef::Item(_) = instance.def {
+        let attrs = instance.def.attrs(tcx);
+        if attr::find_inline_attr(None, &attrs[..]) == attr::InlineAttr::Never ||
+           attr::contains_name(&attrs[..], "naked") {
+            return false
+        }
+    }
     if is_inline_instance(tcx, instance) {
         return true
     }
@@ -14,10 +14,11 @@ use rustc::dep_graph::{DepGraph, DepGraphSafe, DepNode, DepTrackingMap,
                        DepTrackingMapConfig, WorkProduct};
 use middle::cstore::LinkMeta;
 use rustc::hir;
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{CrateNum, DefId};
 use rustc::traits;
 use debuginfo;
 use callee;
+use collector::{CollectionStats, DropGlueUse};
 use base;
 use declare;
 use monomorphize::Instance;
@@ -34,6 +35,7 @@ use session::config;
 use symbol_map::SymbolMap;
 use util::nodemap::{NodeSet, DefIdMap, FxHashMap, FxHashSet};
 
+use std::cmp;
 use std::ffi::{CStr, CString};
 use std::cell::{Cell, RefCell};
 use std::marker::PhantomData;
@@ -77,6 +79,18 @@ pub struct SharedCrateContext<'a, 'tcx: 'a> {
     use_dll_storage_attrs: bool,
 
     translation_items: RefCell<FxHashSet<TransItem<'tcx>>>,
+    /// How the drop glue items have been required, only recorded for
+    /// `-Z print-trans-items`.
+    drop_glue_uses: RefCell<FxHashMap<TransItem<'tcx>, DropGlueUse>>,
+    collection_stats: CollectionStats,
+    /// The generic instances this crate exports for other crates to link
+    /// to with `-Z share-generics`, filled once the translation items have
+    /// been partitioned.
+    exported_generics: RefCell<Vec<(DefId, &'tcx Substs<'tcx>)>>,
+    /// The upstream crate exporting each generic instance with
+    /// `-Z share-generics`, loaded from the crates' metadata on first use.
+    upstream_monomorphizations: RefCell<Option<FxHashMap<(DefId, &'tcx Substs<'tcx>),
+                                                         CrateNum>>>,
     trait_cache: RefCell<DepTrackingMap<TraitSelectionCache<'tcx>>>,
     project_cache: RefCell<DepTrackingMap<ProjectionCache<'tcx>>>,
 }
@@ -132,6 +146,10 @@ pub struct LocalCrateContext<'tcx> {
     /// to constants.)
     statics_to_rauw: RefCell<Vec<(ValueRef, ValueRef)>>,
 
+    /// Statics that will be placed in the llvm.used variable
+    /// See http://llvm.org/docs/LangRef.html#the-llvm-used-global-variable for details
+    used_statics: RefCell<Vec<ValueRef>>,
+
     lltypes: RefCell<FxHashMap<Ty<'tcx>, Type>>,
     llsizingtypes: RefCell<FxHashMap<Ty<'tcx>, Type>>,
     type_hashcodes: RefCell<FxHashMap<Ty<'tcx>, String>>,
@@ -474,6 +492,11 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
             check_overflow: check_overflow,
             use_dll_storage_attrs: use_dll_storage_attrs,
             translation_items: RefCell::new(FxHashSet()),
+            drop_glue_uses: RefCell::new(FxHashMap()),
+            collection_stats:
+                CollectionStats::new(tcx.sess.opts.debugging_opts.trans_collection_stats),
+            exported_generics: RefCell::new(Vec::new()),
+            upstream_monomorphizations: RefCell::new(None),
             trait_cache: RefCell::new(DepTrackingMap::new(tcx.dep_graph.clone())),
             project_cache: RefCell::new(DepTrackingMap::new(tcx.dep_graph.clone())),
         }
@@ -535,6 +558,76 @@ impl<'b, 'tcx> SharedCrateContext<'b, 'tcx> {
         &self.translation_items
     }
 
+    pub fn drop_glue_uses(&self) -> &RefCell<FxHashMap<TransItem<'tcx>, DropGlueUse>> {
+        &self.drop_glue_uses
+    }
+
+    /// The `-Z` flags that control or report on translation item collection,
+    /// the ones marked `[COLLECTION]` in `session::config`.
+    pub fn collection_flags(&self) -> &config::DebuggingOptions {
+        &self.tcx.sess.opts.debugging_opts
+    }
+
+    pub fn collection_stats(&self) -> &CollectionStats {
+        &self.collection_stats
+    }
+
+    pub fn exported_generics(&self) -> &RefCell<Vec<(DefId, &'tcx Substs<'tcx>)>> {
+        &self.exported_generics
+    }
+
+    /// The upstream crate whose translation of `instance` this crate links
+    /// to, instead of instantiating it again, with `-Z share-generics`. Only
+    /// makes a difference for generic instances, monomorphic upstream items
+    /// are linked to either way, see `collector::should_trans_locally()`.
+    pub fn upstream_monomorphization(&self, instance: &Instance<'tcx>) -> Option<CrateNum> {
+        if !self.collection_flags().share_generics {
+            return None;
+        }
+        self.exported_upstream_copy(instance)
+    }
+
+    /// The upstream crate that exports a translated copy of `instance`
+    /// other crates can link to, if there is one. Shims and drop glue are
+    /// always translated locally.
+    pub fn exported_upstream_copy(&self, instance: &Instance<'tcx>) -> Option<CrateNum> {
+        let def_id = match instance.def {
+            ty::InstanceDef::Item(def_id) if !def_id.is_local() => def_id,
+            _ => return None,
+        };
+        if instance.substs.types().next().is_none() {
+            // Only the monomorphic items upstream crates export themselves
+            // can be linked to, `#[inline]` functions never are.
+            let cstore = &self.sess().cstore;
+            return if cstore.is_exported_symbol(def_id) && !cstore.is_foreign_item(def_id) {
+                Some(def_id.krate)
+            } else {
+                None
+            };
+        }
+
+        let mut upstream = self.upstream_monomorphizations.borrow_mut();
+        if upstream.is_none() {
+            let cstore = &self.sess().cstore;
+            let mut map = FxHashMap();
+            for cnum in cstore.crates() {
+                // Plugins and custom derive crates aren't linked in.
+                if cstore.plugin_registrar_fn(cnum).is_some() ||
+                   cstore.derive_registrar_fn(cnum).is_some() {
+                    continue;
+                }
+                for key in cstore.exported_monomorphizations(self.tcx, cnum) {
+                    // Any crate exporting the instance will do, pick the
+                    // same one every time.
+                    let krate = map.entry(key).or_insert(cnum);
+                    *krate = cmp::min(*krate, cnum);
+                }
+            }
+            *upstream = Some(map);
+        }
+        upstream.as_ref().unwrap().get(&(def_id, instance.substs)).cloned()
+    }
+
     pub fn metadata_symbol_name(&self) -> String {
         format!("rust_metadata_{}_{}",
                 self.link_meta().crate_name,
@@ -587,6 +680,7 @@ impl<'tcx> LocalCrateContext<'tcx> {
                 impl_method_cache: RefCell::new(FxHashMap()),
                 closure_bare_wrapper_cache: RefCell::new(FxHashMap()),
                 statics_to_rauw: RefCell::new(Vec::new()),
+                used_statics: RefCell::new(Vec::new()),
                 lltypes: RefCell::new(FxHashMap()),
                 llsizingtypes: RefCell::new(FxHashMap()),
                 type_hashcodes: RefCell::new(FxHashMap()),
@@ -754,6 +848,10 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         &self.local().statics_to_rauw
     }
 
+    pub fn used_statics<'a>(&'a self) -> &'a RefCell<Vec<ValueRef>> {
+        &self.local().used_statics
+    }
+
     pub fn lltypes<'a>(&'a self) -> &'a RefCell<FxHashMap<Ty<'tcx>, Type>> {
         &self.local().lltypes
     }
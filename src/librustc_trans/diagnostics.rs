@@ -46,4 +46,124 @@ extern "platform-intrinsic" {
 unsafe { simd_add(i32x1(0), i32x1(1)); } // ok!
 ```
 "##,
+
+E0593: r##"
+A generic function was instantiated recursively more often than the recursion
+limit allows. Erroneous code example:
+
+```compile_fail,E0593
+#![recursion_limit="8"]
+
+trait Descend {
+    fn descend();
+}
+
+impl Descend for () {
+    fn descend() {}
+}
+
+impl<T: Descend> Descend for (T,) {
+    fn descend() {
+        T::descend();
+    }
+}
+
+fn main() {
+    <((((((((((((),),),),),),),),),),),) as Descend>::descend();
+    // error: reached the recursion limit while instantiating
+    //        `<(T,) as Descend>...`
+}
+```
+
+Every instance of `descend` instantiates it again with a shorter type, until
+the type is `()`. The recursion is finite, but deeper than the limit. The limit
+can be raised with the `#![recursion_limit="N"]` crate attribute:
+
+```
+#![recursion_limit="16"]
+
+trait Descend {
+    fn descend();
+}
+
+impl Descend for () {
+    fn descend() {}
+}
+
+impl<T: Descend> Descend for (T,) {
+    fn descend() {
+        T::descend();
+    }
+}
+
+fn main() {
+    <((((((((((((),),),),),),),),),),),) as Descend>::descend(); // ok!
+}
+```
+
+Recursion that instantiates a function with ever larger types never ends,
+see E0595.
+"##,
+
+E0594: r##"
+A generic function was instantiated with type arguments longer than the
+type-length limit allows. Erroneous code example:
+
+```compile_fail,E0594
+#![type_length_limit="4"]
+
+fn nest<T>(n: u32, value: T) {
+    if n > 0 {
+        nest(n - 1, ((value,),));
+    }
+}
+
+fn main() {
+    nest(3, ());
+    // error: reached the type-length limit while instantiating
+    //        `nest::<(((((),),),),)>...`
+}
+```
+
+Very long types are usually the result of a type growing with every
+recursive call, see E0595. If the types are legitimately long, the limit can
+be raised with the `#![type_length_limit="N"]` crate attribute.
+"##,
+
+E0595: r##"
+A generic function instantiates itself with ever larger type arguments, which
+would never end. Erroneous code example:
+
+```compile_fail,E0595
+fn nest<T>(n: u32, value: T) {
+    if n > 0 {
+        nest(n - 1, Some(value));
+    }
+}
+
+fn main() {
+    nest(3, ());
+    // error: found an infinite instantiation cycle while instantiating
+    //        `nest::<std::option::Option<std::option::Option<()>>>`
+}
+```
+
+Every call of `nest` instantiates it with a new, longer type, so the compiler
+would have to generate infinitely many instances, no matter how many times
+`nest` is actually called at runtime. Such functions have to be rewritten so
+that the type arguments do not grow with every recursive call, for example by
+using a trait object:
+
+```
+fn nest(n: u32, value: Box<std::fmt::Debug>) {
+    if n > 0 {
+        nest(n - 1, Box::new(Some(value)));
+    }
+}
+
+fn main() {
+    nest(3, Box::new(()));
+}
+```
+"##,
 }
@@ -21,7 +21,7 @@ use rustc::ty::{self, Ty, TypeFoldable};
 use common::*;
 use machine::*;
 use meth;
-use monomorphize;
+use monomorphize::{self, Instance};
 use type_of::{sizing_type_of, align_of};
 use value::Value;
 use builder::Builder;
@@ -45,25 +45,41 @@ pub fn needs_drop_glue<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>, t: Ty<'tcx>
     }
     match t.sty {
         ty::TyAdt(def, _) if def.is_box() => {
+            // `Box<ZeroSizeType>` does not allocate.
             let typ = t.boxed_ty();
-            if !scx.type_needs_drop(typ) && scx.type_is_sized(typ) {
-                scx.tcx().infer_ctxt((), traits::Reveal::All).enter(|infcx| {
-                    let layout = t.layout(&infcx).unwrap();
-                    if layout.size(&scx.tcx().data_layout).bytes() == 0 {
-                        // `Box<ZeroSizeType>` does not allocate.
-                        false
-                    } else {
-                        true
-                    }
-                })
-            } else {
-                true
-            }
+            scx.type_needs_drop(typ) || box_allocates(scx, typ)
         }
+        // There are no elements to drop. The glue of types containing such
+        // an array is still needed for their other fields.
+        ty::TyArray(_, 0) => false,
         _ => true
     }
 }
 
+/// Whether a box of a value of type `t` is allocated. Values of zero-sized
+/// types are not, and neither `exchange_malloc` nor `box_free` are called for
+/// them. Types whose size is not known are assumed to be allocated.
+pub fn box_allocates<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>, t: Ty<'tcx>) -> bool {
+    if t.needs_subst() || !scx.type_is_sized(t) {
+        return true;
+    }
+    scx.tcx().infer_ctxt((), traits::Reveal::All).enter(|infcx| {
+        match t.layout(&infcx) {
+            Ok(layout) => layout.size(&scx.tcx().data_layout).bytes() != 0,
+            Err(_) => true,
+        }
+    })
+}
+
+/// Whether `instance` is the `box_free` lang item for a box that has never
+/// been allocated, see `box_allocates()`. Calls to it are skipped.
+pub fn is_noop_box_free<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  instance: &Instance<'tcx>)
+                                  -> bool {
+    Some(instance.def_id()) == scx.tcx().lang_items.box_free_fn() &&
+    !box_allocates(scx, instance.substs.type_at(0))
+}
+
 pub fn size_and_align_of_dst<'a, 'tcx>(bcx: &Builder<'a, 'tcx>, t: Ty<'tcx>, info: ValueRef)
                                        -> (ValueRef, ValueRef) {
     debug!("calculate size of DST: {}; with lost info: {:?}",
@@ -67,6 +67,7 @@ pub use rustc::lint;
 pub use rustc::util;
 
 pub use base::trans_crate;
+pub use collector::print_collection_flags;
 pub use disr::Disr;
 
 pub mod back {
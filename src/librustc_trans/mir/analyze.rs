@@ -17,7 +17,12 @@ use rustc::middle::const_val::ConstVal;
 use rustc::mir::{self, Location, TerminatorKind, Literal};
 use rustc::mir::visit::{Visitor, LvalueContext};
 use rustc::mir::traversal;
+use rustc::ty::{self, TypeFoldable};
+use rustc::ty::subst::Substs;
+use rustc_const_math::ConstInt;
 use common;
+use context::SharedCrateContext;
+use monomorphize;
 use super::MirContext;
 use super::rvalue;
 
@@ -287,3 +292,162 @@ pub fn cleanup_kinds<'a, 'tcx>(mir: &mir::Mir<'tcx>) -> IndexVec<mir::BasicBlock
     debug!("cleanup_kinds: result={:?}", result);
     result
 }
+
+/// The blocks of `mir` that can be reached once the substs of the instance
+/// are applied. A `SwitchInt` on a constant only leads to the target its
+/// value selects, see `constant_switch_target()`; all other terminators lead
+/// to all of their successors. The collector only searches these blocks for
+/// neighbours, and only these blocks get translated.
+pub fn reachable_blocks<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                  mir: &mir::Mir<'tcx>,
+                                  param_substs: &'tcx Substs<'tcx>)
+                                  -> BitVector {
+    let mut reachable = BitVector::new(mir.basic_blocks().len());
+    let mut worklist = vec![mir::START_BLOCK];
+    reachable.insert(mir::START_BLOCK.index());
+
+    while let Some(bb) = worklist.pop() {
+        let data = &mir[bb];
+        let successors = match constant_switch_target(scx, param_substs, data) {
+            Some(target) => vec![target],
+            None => data.terminator().successors().into_owned(),
+        };
+        for succ in successors {
+            if reachable.insert(succ.index()) {
+                worklist.push(succ);
+            }
+        }
+    }
+
+    reachable
+}
+
+/// The constant a block ending in a `SwitchInt` switches on, if any: either
+/// the discriminant itself, or the constant last assigned to the local used
+/// as the discriminant within the block, as in the MIR of a `match`.
+///
+/// This is conservative: any other statement that might write to the local
+/// after the constant is assigned rules it out.
+pub fn switch_constant<'a, 'tcx>(data: &'a mir::BasicBlockData<'tcx>)
+                                 -> Option<&'a Literal<'tcx>> {
+    let local = match data.terminator().kind {
+        TerminatorKind::SwitchInt { ref discr, .. } => match *discr {
+            mir::Operand::Constant(ref constant) => return Some(&constant.literal),
+            mir::Operand::Consume(mir::Lvalue::Local(local)) => local,
+            mir::Operand::Consume(_) => return None,
+        },
+        _ => return None,
+    };
+
+    for statement in data.statements.iter().rev() {
+        match statement.kind {
+            mir::StatementKind::Assign(mir::Lvalue::Local(dest), ref rvalue) => {
+                if dest != local {
+                    continue;
+                }
+                return match *rvalue {
+                    mir::Rvalue::Use(mir::Operand::Constant(ref constant)) => {
+                        Some(&constant.literal)
+                    }
+                    _ => None,
+                };
+            }
+            mir::StatementKind::StorageLive(_) |
+            mir::StatementKind::StorageDead(_) |
+            mir::StatementKind::Nop => {}
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+/// The only target of the `SwitchInt` ending `data` that can be taken, if it
+/// switches on a constant whose value is known, see `switch_constant()`.
+/// After substitution, this includes associated constants of type
+/// parameters, which allows dispatching on properties of a type without
+/// instantiating the paths for all types.
+///
+/// Constants are only looked through if their body is a plain value, or
+/// another such constant. Anything else keeps all targets.
+pub fn constant_switch_target<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                        param_substs: &'tcx Substs<'tcx>,
+                                        data: &mir::BasicBlockData<'tcx>)
+                                        -> Option<mir::BasicBlock> {
+    let value = match switch_constant(data) {
+        Some(literal) => constant_int(scx, param_substs, literal, 0),
+        None => None,
+    };
+    let value = match value {
+        Some(value) => value,
+        None => return None,
+    };
+
+    let (values, targets) = match data.terminator().kind {
+        TerminatorKind::SwitchInt { ref values, ref targets, .. } => (values, targets),
+        _ => bug!("switch_constant() returned a constant for a non-switch"),
+    };
+    let (otherwise, targets) = targets.split_last().unwrap();
+    let target = values.iter().zip(targets).find(|&(v, _)| *v == value);
+    Some(target.map_or(*otherwise, |(_, &target)| target))
+}
+
+// Constants are rarely defined in terms of more than a couple of others.
+const CONSTANT_LOOKUP_DEPTH: usize = 8;
+
+fn constant_int<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                          param_substs: &'tcx Substs<'tcx>,
+                          literal: &Literal<'tcx>,
+                          depth: usize)
+                          -> Option<ConstInt> {
+    let (def_id, substs) = match *literal {
+        Literal::Value { ref value } => return value.to_const_int(),
+        Literal::Item { def_id, substs } => (def_id, substs),
+        Literal::Promoted { .. } => return None,
+    };
+    if depth == CONSTANT_LOOKUP_DEPTH {
+        return None;
+    }
+
+    let substs = monomorphize::apply_param_substs(scx, param_substs, &substs);
+    if substs.needs_subst() {
+        return None;
+    }
+    let instance = monomorphize::resolve(scx, def_id, substs);
+    let def_id = match instance.def {
+        ty::InstanceDef::Item(def_id) => def_id,
+        _ => return None,
+    };
+    let mir = match scx.tcx().maybe_item_mir(def_id) {
+        Some(mir) => mir,
+        None => return None,
+    };
+
+    // The body of such a constant is a single block assigning the value to
+    // the return pointer.
+    if mir.basic_blocks().len() != 1 {
+        return None;
+    }
+    let data = &mir[mir::START_BLOCK];
+    match data.terminator().kind {
+        TerminatorKind::Return => {}
+        _ => return None,
+    }
+
+    let mut value = None;
+    for statement in &data.statements {
+        match statement.kind {
+            mir::StatementKind::Assign(mir::Lvalue::Local(local),
+                                       mir::Rvalue::Use(mir::Operand::Constant(ref constant)))
+                if local == mir::RETURN_POINTER && value.is_none() => {
+                value = Some(&constant.literal);
+            }
+            mir::StatementKind::StorageLive(_) |
+            mir::StatementKind::StorageDead(_) |
+            mir::StatementKind::Nop => {}
+            _ => return None,
+        }
+    }
+
+    value.and_then(|literal| constant_int(scx, instance.substs, literal, depth + 1))
+}
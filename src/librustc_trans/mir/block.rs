@@ -21,6 +21,7 @@ use builder::Builder;
 use common::{self, Funclet};
 use common::{C_bool, C_str_slice, C_struct, C_u32, C_uint, C_undef};
 use consts;
+use glue;
 use machine::llalign_of_min;
 use meth;
 use monomorphize;
@@ -34,7 +35,7 @@ use syntax::symbol::Symbol;
 use std::cmp;
 
 use super::{MirContext, LocalRef};
-use super::analyze::CleanupKind;
+use super::analyze::{self, CleanupKind};
 use super::constant::Const;
 use super::lvalue::{Alignment, LvalueRef};
 use super::operand::OperandRef;
@@ -136,6 +137,16 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
             }
 
             mir::TerminatorKind::SwitchInt { ref discr, switch_ty, ref values, ref targets } => {
+                // The targets a constant discriminant does not select are
+                // not translated, see `analyze::reachable_blocks()`.
+                let scx = self.ccx.shared();
+                if let Some(target) = analyze::constant_switch_target(scx,
+                                                                      self.param_substs,
+                                                                      data) {
+                    funclet_br(self, bcx, target);
+                    return;
+                }
+
                 let discr = self.trans_operand(&bcx, discr);
                 if switch_ty == bcx.tcx().types.bool {
                     let lltrue = llblock(self, targets[0]);
@@ -207,6 +218,7 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
             }
 
             mir::TerminatorKind::Drop { ref location, target, unwind } => {
+                let unwind = self.unwind_target(unwind);
                 let ty = location.ty(&self.mir, bcx.tcx()).to_ty(bcx.tcx());
                 let ty = self.monomorphize(&ty);
                 let drop_fn = monomorphize::resolve_drop_in_place(bcx.ccx.shared(), ty);
@@ -267,6 +279,7 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
             }
 
             mir::TerminatorKind::Assert { ref cond, expected, ref msg, target, cleanup } => {
+                let cleanup = self.unwind_target(cleanup);
                 let cond = self.trans_operand(&bcx, cond).immediate();
                 let mut const_cond = common::const_to_opt_u128(cond, false).map(|c| c == 1);
 
@@ -389,7 +402,8 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
                 bug!("undesugared DropAndReplace in trans: {:?}", data);
             }
 
-            mir::TerminatorKind::Call { ref func, ref args, ref destination, ref cleanup } => {
+            mir::TerminatorKind::Call { ref func, ref args, ref destination, cleanup } => {
+                let cleanup = self.unwind_target(cleanup);
                 // Create the callee. This is a fn ptr or zero-sized and hence a kind of scalar.
                 let callee = self.trans_operand(&bcx, func);
 
@@ -435,6 +449,16 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
                     return;
                 }
 
+                // Boxes of zero-sized values are never allocated, there is
+                // nothing to free either.
+                if let Some(ref instance) = instance {
+                    if glue::is_noop_box_free(bcx.ccx.shared(), instance) {
+                        let &(_, target) = destination.as_ref().unwrap();
+                        funclet_br(self, bcx, target);
+                        return;
+                    }
+                }
+
                 let extra_args = &args[sig.inputs().len()..];
                 let extra_args = extra_args.iter().map(|op_arg| {
                     let op_ty = op_arg.ty(&self.mir, bcx.tcx());
@@ -553,7 +577,7 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
                 };
 
                 // Many different ways to call a function handled here
-                if let &Some(cleanup) = cleanup {
+                if let Some(cleanup) = cleanup {
                     let ret_bcx = if let Some((_, target)) = *destination {
                         self.blocks[target]
                     } else {
@@ -799,6 +823,16 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
         bcx.llbb()
     }
 
+    /// The block to unwind to from a terminator. Without landing pads,
+    /// unwinding aborts, so the cleanup blocks are not translated at all.
+    fn unwind_target(&self, unwind: Option<mir::BasicBlock>) -> Option<mir::BasicBlock> {
+        if self.ccx.sess().no_landing_pads() {
+            None
+        } else {
+            unwind
+        }
+    }
+
     fn unreachable_block(&mut self) -> BasicBlockRef {
         self.unreachable_block.unwrap_or_else(|| {
             let bl = self.new_block("unreachable");
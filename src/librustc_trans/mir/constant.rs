@@ -20,7 +20,7 @@ use rustc::mir;
 use rustc::mir::tcx::LvalueTy;
 use rustc::ty::{self, layout, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::cast::{CastTy, IntTy};
-use rustc::ty::subst::{Kind, Substs, Subst};
+use rustc::ty::subst::Substs;
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 use {abi, adt, base, Disr, machine};
 use callee;
@@ -30,13 +30,14 @@ use common::{C_array, C_bool, C_bytes, C_floating_f64, C_integral, C_big_integra
 use common::{C_null, C_struct, C_str_slice, C_undef, C_uint, C_vector, is_undef};
 use common::const_to_opt_u128;
 use consts;
-use monomorphize;
+use monomorphize::{self, Instance};
 use type_of;
 use type_::Type;
 use value::Value;
 
 use syntax_pos::Span;
 
+use std::ffi::CString;
 use std::fmt;
 use std::ptr;
 
@@ -572,19 +573,9 @@ impl<'a, 'tcx> MirConstContext<'a, 'tcx> {
                     mir::CastKind::ClosureFnPointer => {
                         match operand.ty.sty {
                             ty::TyClosure(def_id, substs) => {
-                                // Get the def_id for FnOnce::call_once
-                                let fn_once = tcx.lang_items.fn_once_trait().unwrap();
-                                let call_once = tcx
-                                    .global_tcx().associated_items(fn_once)
-                                    .find(|it| it.kind == ty::AssociatedKind::Method)
-                                    .unwrap().def_id;
-                                // Now create its substs [Closure, Tuple]
-                                let input = tcx.closure_type(def_id)
-                                    .subst(tcx, substs.substs).input(0);
-                                let input = tcx.erase_late_bound_regions_and_normalize(&input);
-                                let substs = tcx.mk_substs([operand.ty, input]
-                                    .iter().cloned().map(Kind::from));
-                                callee::resolve_and_get_fn(self.ccx, call_once, substs)
+                                let instance = monomorphize::resolve_closure_fn_pointer(
+                                    self.ccx.shared(), def_id, substs);
+                                callee::get_fn(self.ccx, instance)
                             }
                             _ => {
                                 bug!("{} cannot be cast to a fn ptr", operand.ty)
@@ -953,6 +944,48 @@ pub fn trans_static_initializer<'a, 'tcx>(
         .map(|c| c.llval)
 }
 
+/// Translates the promoted value `index` of `instance` and gives the global
+/// holding it the name `symbol_name`. Without this, the global would be named
+/// after the order in which constants are translated. The constant cache
+/// hands the global out again when the function itself refers to the value,
+/// and to every other promotion of the same value in the codegen unit; such
+/// a shared global keeps the name of the promotion translated last, which is
+/// the same in every compilation as items are translated in a fixed order.
+pub fn trans_promoted<'a, 'tcx>(
+    ccx: &CrateContext<'a, 'tcx>,
+    instance: Instance<'tcx>,
+    index: mir::Promoted,
+    symbol_name: &str)
+{
+    let mir = ccx.tcx().instance_mir(instance.def);
+    let value = match MirConstContext::new(ccx,
+                                           &mir.promoted[index],
+                                           instance.substs,
+                                           IndexVec::new()).trans() {
+        Ok(value) => value,
+        // Errors are reported when translating the function itself.
+        Err(_) => return
+    };
+
+    let ptr = if common::type_is_fat_ptr(ccx, value.ty) {
+        const_get_elt(value.llval, &[0])
+    } else {
+        value.llval
+    };
+
+    unsafe {
+        // Leave the globals of statics alone, a promoted value might just be
+        // a reference to one of those.
+        if llvm::LLVMIsAGlobalVariable(ptr).is_null() ||
+           ccx.statics().borrow().contains_key(&ptr) {
+            return
+        }
+
+        let name = CString::new(symbol_name).unwrap();
+        llvm::LLVMSetValueName(ptr, name.as_ptr());
+    }
+}
+
 /// Construct a constant value, suitable for initializing a
 /// GlobalVariable, given a case and constant values for its fields.
 /// Note that this may have a different LLVM type (and different
@@ -34,7 +34,8 @@ use std::iter;
 use rustc_data_structures::bitvec::BitVector;
 use rustc_data_structures::indexed_vec::{IndexVec, Idx};
 
-pub use self::constant::trans_static_initializer;
+pub use self::constant::{trans_static_initializer, trans_promoted};
+pub use self::analyze::{reachable_blocks, switch_constant};
 
 use self::analyze::CleanupKind;
 use self::lvalue::{Alignment, LvalueRef};
@@ -328,8 +329,18 @@ pub fn trans_mir<'a, 'tcx: 'a>(
     let rpo = traversal::reverse_postorder(&mir);
     let mut visited = BitVector::new(mir.basic_blocks().len());
 
-    // Translate the body of each block using reverse postorder
-    for (bb, _) in rpo {
+    // Translate the body of each block using reverse postorder. Cleanup
+    // blocks are only reached by unwinding, which aborts without landing
+    // pads. The MIR of upstream crates built with `-C panic=unwind` still
+    // has them, but the collector skips them as well, so the drop glue they
+    // call may not even exist. The same goes for the blocks only reached
+    // through the targets a constant `SwitchInt` does not select.
+    let no_landing_pads = ccx.sess().no_landing_pads();
+    let reachable = analyze::reachable_blocks(ccx.shared(), mir, instance.substs);
+    for (bb, data) in rpo {
+        if no_landing_pads && data.is_cleanup || !reachable.contains(bb.index()) {
+            continue;
+        }
         visited.insert(bb.index());
         mircx.trans_block(bb, &funclets);
     }
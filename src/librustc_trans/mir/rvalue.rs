@@ -19,6 +19,7 @@ use middle::lang_items::ExchangeMallocFnLangItem;
 use base;
 use builder::Builder;
 use callee;
+use glue;
 use common::{self, val_ty, C_bool, C_null, C_uint};
 use common::{C_integral};
 use adt;
@@ -194,8 +195,8 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
                     mir::CastKind::ClosureFnPointer => {
                         match operand.ty.sty {
                             ty::TyClosure(def_id, substs) => {
-                                let instance = monomorphize::resolve_closure(
-                                    bcx.ccx.shared(), def_id, substs, ty::ClosureKind::FnOnce);
+                                let instance = monomorphize::resolve_closure_fn_pointer(
+                                    bcx.ccx.shared(), def_id, substs);
                                 OperandValue::Immediate(callee::get_fn(bcx.ccx, instance))
                             }
                             _ => {
@@ -443,16 +444,22 @@ impl<'a, 'tcx> MirContext<'a, 'tcx> {
                 let llty_ptr = llty.ptr_to();
                 let box_ty = bcx.tcx().mk_box(content_ty);
 
-                // Allocate space:
-                let def_id = match bcx.tcx().lang_items.require(ExchangeMallocFnLangItem) {
-                    Ok(id) => id,
-                    Err(s) => {
-                        bcx.sess().fatal(&format!("allocation of `{}` {}", box_ty, s));
-                    }
+                let val = if !glue::box_allocates(bcx.ccx.shared(), content_ty) {
+                    // Like `exchange_malloc` does for zero-sized values, use
+                    // the alignment as a non-null, well-aligned address.
+                    bcx.inttoptr(llalign, llty_ptr)
+                } else {
+                    // Allocate space:
+                    let def_id = match bcx.tcx().lang_items.require(ExchangeMallocFnLangItem) {
+                        Ok(id) => id,
+                        Err(s) => {
+                            bcx.sess().fatal(&format!("allocation of `{}` {}", box_ty, s));
+                        }
+                    };
+                    let instance = ty::Instance::mono(bcx.tcx(), def_id);
+                    let r = callee::get_fn(bcx.ccx, instance);
+                    bcx.pointercast(bcx.call(r, &[llsize, llalign], None), llty_ptr)
                 };
-                let instance = ty::Instance::mono(bcx.tcx(), def_id);
-                let r = callee::get_fn(bcx.ccx, instance);
-                let val = bcx.pointercast(bcx.call(r, &[llsize, llalign], None), llty_ptr);
 
                 let operand = OperandRef {
                     val: OperandValue::Immediate(val),
@@ -104,6 +104,21 @@ pub fn resolve_closure<'a, 'tcx> (
     }
 }
 
+/// Resolves the function that a closure cast to a function pointer points to.
+///
+/// Only closures without upvars can be cast, so their environment is zero-sized.
+/// This is what makes the `FnOnce` entry point suitable whatever the kind of the
+/// closure is: it takes the environment by value, which does not show up in the
+/// signature. The body of an `Fn` or `FnMut` closure takes it by reference, so
+/// for these, this is the shim calling the body (which in turn is an item of its
+/// own), and only for `FnOnce` closures the body itself.
+pub fn resolve_closure_fn_pointer<'a, 'tcx>(scx: &SharedCrateContext<'a, 'tcx>,
+                                            def_id: DefId,
+                                            substs: ty::ClosureSubsts<'tcx>)
+                                            -> Instance<'tcx> {
+    resolve_closure(scx, def_id, substs, ty::ClosureKind::FnOnce)
+}
+
 /// Attempts to resolve an obligation. The result is a shallow vtable resolution -- meaning that we
 /// do not (necessarily) resolve all nested obligations on the impl. Note that type check should
 /// guarantee to us that all nested obligations *could be* resolved if we wanted to.
@@ -102,7 +102,7 @@
 //! source-level module, functions from the same module will be available for
 //! inlining, even when they are not marked #[inline].
 
-use collector::InliningMap;
+use collector::{self, InliningMap};
 use common;
 use context::SharedCrateContext;
 use llvm;
@@ -194,6 +194,7 @@ impl<'tcx> CodegenUnit<'tcx> {
                TransItem::Static(node_id) => {
                     exported_symbols.contains(&node_id)
                }
+               TransItem::Promoted(..) => false,
             };
             exported.hash(&mut state);
         }
@@ -240,7 +241,8 @@ impl<'tcx> CodegenUnit<'tcx> {
 
         fn local_node_id(tcx: TyCtxt, trans_item: TransItem) -> Option<NodeId> {
             match trans_item {
-                TransItem::Fn(instance) => {
+                TransItem::Fn(instance) |
+                TransItem::Promoted(instance, _) => {
                     tcx.hir.as_local_node_id(instance.def_id())
                 }
                 TransItem::Static(node_id) => Some(node_id),
@@ -282,6 +284,9 @@ pub fn partition<'a, 'tcx, I>(scx: &SharedCrateContext<'a, 'tcx>,
     // translation items have to go into each codegen unit. These additional
     // translation items can be drop-glue, functions from external crates, and
     // local functions the definition of which is marked with #[inline].
+    if cfg!(debug_assertions) {
+        collector::assert_inlining_map_closed(tcx, inlining_map);
+    }
     let post_inlining = place_inlined_translation_items(initial_partitioning,
                                                         inlining_map);
 
@@ -339,6 +344,9 @@ fn place_root_translation_items<'a, 'tcx, I>(scx: &SharedCrateContext<'a, 'tcx>,
                     match trans_item {
                         TransItem::Fn(..) |
                         TransItem::Static(..) => llvm::ExternalLinkage,
+                        // Promoted values are only referenced from within
+                        // the function they belong to.
+                        TransItem::Promoted(..) => llvm::InternalLinkage,
                     }
                 }
             };
@@ -484,6 +492,10 @@ fn characteristic_def_id_of_trans_item<'a, 'tcx>(scx: &SharedCrateContext<'a, 't
             Some(def_id)
         }
         TransItem::Static(node_id) => Some(tcx.hir.local_def_id(node_id)),
+        TransItem::Promoted(instance, _) => {
+            // Promoted values are placed next to their function.
+            characteristic_def_id_of_trans_item(scx, TransItem::Fn(instance))
+        }
     }
 }
 
@@ -96,7 +96,8 @@ impl<'tcx> SymbolMap<'tcx> {
         fn get_span<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                               trans_item: TransItem<'tcx>) -> Option<Span> {
             match trans_item {
-                TransItem::Fn(Instance { def, .. }) => {
+                TransItem::Fn(Instance { def, .. }) |
+                TransItem::Promoted(Instance { def, .. }, _) => {
                     tcx.hir.as_local_node_id(def.def_id())
                 }
                 TransItem::Static(node_id) => Some(node_id),
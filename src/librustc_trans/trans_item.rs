@@ -25,6 +25,7 @@ use monomorphize::Instance;
 use rustc::dep_graph::DepNode;
 use rustc::hir;
 use rustc::hir::def_id::DefId;
+use rustc::mir;
 use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::subst::Substs;
 use rustc_const_eval::fatal_const_eval_err;
@@ -38,7 +39,10 @@ use std::iter;
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum TransItem<'tcx> {
     Fn(Instance<'tcx>),
-    Static(NodeId)
+    Static(NodeId),
+    // A value that has been promoted to a `'static` reference within the
+    // body of the given function.
+    Promoted(Instance<'tcx>, mir::Promoted),
 }
 
 /// Describes how a translation item will be instantiated in object files.
@@ -95,6 +99,14 @@ impl<'a, 'tcx> TransItem<'tcx> {
 
                 base::trans_instance(&ccx, instance);
             }
+            TransItem::Promoted(instance, index) => {
+                let _task = ccx.tcx().dep_graph.in_task(
+                    DepNode::TransCrateItem(instance.def_id())); // (*)
+
+                let symbol_name = ccx.symbol_map()
+                                     .get_or_compute(ccx.shared(), *self);
+                ::mir::trans_promoted(&ccx, instance, index, &symbol_name);
+            }
         }
 
         debug!("END IMPLEMENTING '{} ({})' in cgu {}",
@@ -123,6 +135,10 @@ impl<'a, 'tcx> TransItem<'tcx> {
             TransItem::Fn(instance) => {
                 TransItem::predefine_fn(ccx, instance, linkage, &symbol_name);
             }
+            TransItem::Promoted(..) => {
+                // The global is only created once the value is translated,
+                // see `define()`. Nothing refers to it by name before that.
+            }
         }
 
         debug!("END PREDEFINING '{} ({})' in cgu {}",
@@ -185,6 +201,11 @@ impl<'a, 'tcx> TransItem<'tcx> {
                 let def_id = scx.tcx().hir.local_def_id(node_id);
                 symbol_names::symbol_name(Instance::mono(scx.tcx(), def_id), scx)
             }
+            TransItem::Promoted(instance, index) => {
+                // The '.' makes sure that this can't conflict with the name of
+                // any user-defined item.
+                format!("{}.promoted.{}", symbol_names::symbol_name(instance, scx), index.index())
+            }
         }
     }
 
@@ -202,6 +223,8 @@ impl<'a, 'tcx> TransItem<'tcx> {
                 }
             }
             TransItem::Static(..) => InstantiationMode::GloballyShared,
+            // Promoted values are instantiated wherever their function is.
+            TransItem::Promoted(instance, _) => TransItem::Fn(instance).instantiation_mode(tcx),
         }
     }
 
@@ -210,7 +233,8 @@ impl<'a, 'tcx> TransItem<'tcx> {
             TransItem::Fn(ref instance) => {
                 instance.substs.types().next().is_some()
             }
-            TransItem::Static(..)   => false,
+            TransItem::Static(..) |
+            TransItem::Promoted(..) => false,
         }
     }
 
@@ -218,6 +242,7 @@ impl<'a, 'tcx> TransItem<'tcx> {
         let def_id = match *self {
             TransItem::Fn(ref instance) => instance.def_id(),
             TransItem::Static(node_id) => tcx.hir.local_def_id(node_id),
+            TransItem::Promoted(..) => return None,
         };
 
         let attributes = tcx.get_attrs(def_id);
@@ -237,6 +262,17 @@ impl<'a, 'tcx> TransItem<'tcx> {
         }
     }
 
+    /// Whether this is a `#[used]` static, which has to be kept in the object
+    /// file even though nothing refers to it.
+    pub fn is_used_static(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>) -> bool {
+        match *self {
+            TransItem::Static(node_id) => {
+                attr::contains_name(tcx.hir.attrs(node_id), "used")
+            }
+            TransItem::Fn(..) | TransItem::Promoted(..) => false,
+        }
+    }
+
     pub fn to_string(&self, tcx: TyCtxt<'a, 'tcx, 'tcx>) -> String {
         let hir_map = &tcx.hir;
 
@@ -249,6 +285,9 @@ impl<'a, 'tcx> TransItem<'tcx> {
                 let instance = Instance::new(def_id, tcx.intern_substs(&[]));
                 to_string_internal(tcx, "static ", instance)
             },
+            TransItem::Promoted(instance, index) => {
+                to_string_internal(tcx, &format!("promoted[{}] of fn ", index.index()), instance)
+            },
         };
 
         fn to_string_internal<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
@@ -273,6 +312,12 @@ impl<'a, 'tcx> TransItem<'tcx> {
             TransItem::Static(id) => {
                 format!("Static({:?})", id)
             }
+            TransItem::Promoted(instance, index) => {
+                format!("Promoted({:?}, {}, {:?})",
+                         instance.def,
+                         instance.substs.as_ptr() as usize,
+                         index)
+            }
         }
     }
 }
@@ -62,6 +62,8 @@ struct TestCtxt<'a> {
     ext_cx: ExtCtxt<'a>,
     testfns: Vec<Test>,
     reexport_test_harness_main: Option<Symbol>,
+    // Only tests whose path contains this are added to the harness.
+    test_filter: Option<String>,
     is_test_crate: bool,
     ctxt: SyntaxContext,
 
@@ -74,6 +76,7 @@ struct TestCtxt<'a> {
 pub fn modify_for_testing(sess: &ParseSess,
                           resolver: &mut Resolver,
                           should_test: bool,
+                          test_filter: Option<String>,
                           krate: ast::Crate,
                           span_diagnostic: &errors::Handler) -> ast::Crate {
     // Check for #[reexport_test_harness_main = "some_name"] which
@@ -85,7 +88,12 @@ pub fn modify_for_testing(sess: &ParseSess,
                                            "reexport_test_harness_main");
 
     if should_test {
-        generate_test_harness(sess, resolver, reexport_test_harness_main, krate, span_diagnostic)
+        generate_test_harness(sess,
+                              resolver,
+                              reexport_test_harness_main,
+                              test_filter,
+                              krate,
+                              span_diagnostic)
     } else {
         krate
     }
@@ -121,12 +129,20 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
         }
         debug!("current path: {}", path_name_i(&self.cx.path));
 
+        let mut filtered_out = false;
         if is_test_fn(&self.cx, &i) || is_bench_fn(&self.cx, &i) {
             match i.node {
                 ast::ItemKind::Fn(_, ast::Unsafety::Unsafe, _, _, _, _) => {
                     let diag = self.cx.span_diagnostic;
                     panic!(diag.span_fatal(i.span, "unsafe functions cannot be used for tests"));
                 }
+                _ if !self.cx.matches_test_filter() => {
+                    // Tests left out by `-Z compile-test-filter` are not
+                    // referenced by the harness, and the marker attribute
+                    // keeps trans from translating them either.
+                    debug!("this test function is filtered out");
+                    filtered_out = true;
+                }
                 _ => {
                     debug!("this is a test function");
                     let test = Test {
@@ -142,7 +158,12 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
             }
         }
 
+        let span = i.span;
         let mut item = i.unwrap();
+        if filtered_out {
+            item.attrs.push(mk_allow_dead_code_attr());
+            item.attrs.push(mk_filtered_out_attr(&self.cx, span));
+        }
         // We don't want to recurse into anything other than mods, since
         // mods or tests inside of functions will break things
         if let ast::ItemKind::Mod(module) = item.node {
@@ -193,13 +214,7 @@ impl fold::Folder for EntryPointCleaner {
             EntryPointType::MainAttr |
             EntryPointType::Start =>
                 folded.map(|ast::Item {id, ident, attrs, node, vis, span}| {
-                    let allow_str = Symbol::intern("allow");
-                    let dead_code_str = Symbol::intern("dead_code");
-                    let word_vec = vec![attr::mk_list_word_item(dead_code_str)];
-                    let allow_dead_code_item = attr::mk_list_item(allow_str, word_vec);
-                    let allow_dead_code = attr::mk_attr_outer(DUMMY_SP,
-                                                              attr::mk_attr_id(),
-                                                              allow_dead_code_item);
+                    let allow_dead_code = mk_allow_dead_code_attr();
 
                     ast::Item {
                         id: id,
@@ -225,6 +240,22 @@ impl fold::Folder for EntryPointCleaner {
     fn fold_mac(&mut self, mac: ast::Mac) -> ast::Mac { mac }
 }
 
+// Creates `#[allow(dead_code)]`.
+fn mk_allow_dead_code_attr() -> ast::Attribute {
+    let allow_str = Symbol::intern("allow");
+    let dead_code_str = Symbol::intern("dead_code");
+    let word_vec = vec![attr::mk_list_word_item(dead_code_str)];
+    let allow_dead_code_item = attr::mk_list_item(allow_str, word_vec);
+    attr::mk_attr_outer(DUMMY_SP, attr::mk_attr_id(), allow_dead_code_item)
+}
+
+// Creates `#[rustc_test_filtered_out]`, which marks the tests that the harness
+// leaves out, so that trans does not have to match the filter again.
+fn mk_filtered_out_attr(cx: &TestCtxt, span: Span) -> ast::Attribute {
+    let filtered_out_item = attr::mk_word_item(Symbol::intern("rustc_test_filtered_out"));
+    attr::mk_attr_outer(ignored_span(cx, span), attr::mk_attr_id(), filtered_out_item)
+}
+
 fn mk_reexport_mod(cx: &mut TestCtxt,
                    parent: ast::NodeId,
                    tests: Vec<Ident>,
@@ -271,6 +302,7 @@ fn mk_reexport_mod(cx: &mut TestCtxt,
 fn generate_test_harness(sess: &ParseSess,
                          resolver: &mut Resolver,
                          reexport_test_harness_main: Option<Symbol>,
+                         test_filter: Option<String>,
                          krate: ast::Crate,
                          sd: &errors::Handler) -> ast::Crate {
     // Remove the entry points
@@ -285,6 +317,7 @@ fn generate_test_harness(sess: &ParseSess,
         path: Vec::new(),
         testfns: Vec::new(),
         reexport_test_harness_main: reexport_test_harness_main,
+        test_filter: test_filter,
         is_test_crate: is_test_crate(&krate),
         toplevel_reexport: None,
         ctxt: SyntaxContext::empty().apply_mark(mark),
@@ -307,6 +340,20 @@ fn generate_test_harness(sess: &ParseSess,
     }.fold_crate(krate)
 }
 
+impl<'a> TestCtxt<'a> {
+    // Whether the test at the current path is to be added to the harness.
+    // Like the filter given to the test binary at runtime, the filter of
+    // `-Z compile-test-filter` matches any test whose path contains it, so
+    // that the same filter can be passed to both. It is not a regular
+    // expression, the compiler does not depend on a regex engine.
+    fn matches_test_filter(&self) -> bool {
+        match self.test_filter {
+            Some(ref filter) => path_name_i(&self.path).contains(&filter[..]),
+            None => true,
+        }
+    }
+}
+
 /// Craft a span that will be ignored by the stability lint's
 /// call to codemap's is_internal check.
 /// The expanded code calls some unstable functions in the test crate.
@@ -0,0 +1,58 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+#![feature(allocator)]
+#![allocator]
+#![crate_type = "rlib"]
+#![no_std]
+
+// Test that the functions of an allocator crate are roots even though
+// nothing in the crate calls them: the allocation shims of other crates refer
+// to them by their fixed symbol names. The items they use are collected
+// through them.
+
+fn null<T>(_: T) -> *mut u8 {
+    0 as *mut u8
+}
+
+//~ TRANS_ITEM fn allocator_crate_roots::__rust_allocate[0]
+//~ TRANS_ITEM fn allocator_crate_roots::null[0]<usize>
+#[no_mangle]
+pub extern fn __rust_allocate(size: usize, _align: usize) -> *mut u8 {
+    null(size)
+}
+
+//~ TRANS_ITEM fn allocator_crate_roots::__rust_deallocate[0]
+#[no_mangle]
+pub extern fn __rust_deallocate(_ptr: *mut u8, _old_size: usize, _align: usize) {}
+
+//~ TRANS_ITEM fn allocator_crate_roots::__rust_reallocate[0]
+//~ TRANS_ITEM fn allocator_crate_roots::null[0]<*mut u8>
+#[no_mangle]
+pub extern fn __rust_reallocate(ptr: *mut u8, _old_size: usize, _size: usize,
+                                _align: usize) -> *mut u8 {
+    null(ptr)
+}
+
+//~ TRANS_ITEM fn allocator_crate_roots::__rust_reallocate_inplace[0]
+#[no_mangle]
+pub extern fn __rust_reallocate_inplace(_ptr: *mut u8, old_size: usize,
+                                        _size: usize, _align: usize) -> usize {
+    old_size
+}
+
+//~ TRANS_ITEM fn allocator_crate_roots::__rust_usable_size[0]
+#[no_mangle]
+pub extern fn __rust_usable_size(size: usize, _align: usize) -> usize {
+    size
+}
@@ -0,0 +1,54 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+// aux-build:cgu_assoc_type_callees.rs
+
+// Check that calls and drops whose types are associated types of the type
+// parameters are resolved once the parameters are known, across crates.
+
+extern crate cgu_assoc_type_callees;
+
+use cgu_assoc_type_callees::{Describe, Source, describe_source};
+
+struct Token(u32);
+
+impl Describe for Token {
+    //~ TRANS_ITEM fn assoc_type_callees::{{impl}}[0]::describe[0]
+    fn describe(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Drop for Token {
+    //~ TRANS_ITEM fn assoc_type_callees::{{impl}}[1]::drop[0]
+    fn drop(&mut self) {}
+}
+
+struct Factory;
+
+impl Source for Factory {
+    type Item = Token;
+
+    //~ TRANS_ITEM fn assoc_type_callees::{{impl}}[2]::take[0]
+    fn take(self) -> Token {
+        Token(1)
+    }
+}
+
+//~ TRANS_ITEM fn assoc_type_callees::main[0]
+fn main() {
+    //~ TRANS_ITEM fn cgu_assoc_type_callees::describe_source[0]<assoc_type_callees::Factory[0]>
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<assoc_type_callees::Token[0]>
+    let _ = describe_source(Factory);
+
+    //~ TRANS_ITEM fn cgu_assoc_type_callees::Source[0]::describe_taken[0]<assoc_type_callees::Factory[0]>
+    let _ = Factory.describe_taken();
+}
@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+#![crate_type = "lib"]
+
+pub trait Describe {
+    fn describe(&self) -> u32;
+}
+
+pub trait Source {
+    type Item: Describe;
+
+    fn take(self) -> Self::Item;
+
+    fn describe_taken(self) -> u32 where Self: Sized {
+        let item = self.take();
+        item.describe()
+    }
+}
+
+pub fn describe_source<S: Source>(source: S) -> u32 {
+    let item = source.take();
+    item.describe()
+}
@@ -9,6 +9,7 @@
 // except according to those terms.
 
 #![crate_type = "lib"]
+#![feature(closure_to_fn_coercion)]
 
 #[inline]
 pub fn inlined_fn(x: i32, y: i32) -> i32 {
@@ -31,3 +32,19 @@ pub fn non_inlined_fn(x: i32, y: i32) -> i32 {
 
     closure(x, y)
 }
+
+pub fn call_once<F: FnOnce(i32) -> i32>(f: F, x: i32) -> i32 {
+    f(x)
+}
+
+pub fn through_fn_once<T>(x: i32, _: T) -> i32 {
+
+    call_once(|a| a + 1, x)
+}
+
+pub fn through_fn_pointer<T>(x: i32, _: T) -> i32 {
+
+    let f: fn(i32) -> i32 = |a| a * 2;
+
+    f(x)
+}
@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+
+use std::ops::Deref;
+use std::ptr;
+
+pub struct SmartPtr<T>(pub T);
+
+impl<T> Deref for SmartPtr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[inline]
+pub fn copy_from<T: Copy>(src: &SmartPtr<T>, dst: &mut T) {
+    unsafe {
+        ptr::copy_nonoverlapping(&**src, dst, 1);
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(no_core, lang_items)]
+#![no_core]
+#![crate_type = "lib"]
+
+#[lang = "sized"]
+pub trait Sized {}
+
+#[lang = "copy"]
+pub trait Copy {}
+
+#[lang = "add"]
+pub trait Add<RHS = Self> {
+    type Output;
+    fn add(self, rhs: RHS) -> Self::Output;
+}
+
+impl Add for u32 {
+    type Output = u32;
+    fn add(self, rhs: u32) -> u32 { self + rhs }
+}
+
+#[lang = "div"]
+pub trait Div<RHS = Self> {
+    type Output;
+    fn div(self, rhs: RHS) -> Self::Output;
+}
+
+impl Div for u32 {
+    type Output = u32;
+    fn div(self, rhs: u32) -> u32 { self / rhs }
+}
+
+// Inlined, so that the crates using them translate their own copies.
+#[inline]
+#[lang = "panic"]
+pub fn panic(_expr_file_line: &(&'static str, &'static str, u32)) -> ! {
+    loop {}
+}
+
+#[inline]
+#[lang = "panic_bounds_check"]
+pub fn panic_bounds_check(_file_line: &(&'static str, u32), _index: usize, _len: usize) -> ! {
+    loop {}
+}
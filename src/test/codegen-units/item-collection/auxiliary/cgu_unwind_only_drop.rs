@@ -0,0 +1,25 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+#![crate_type = "lib"]
+
+pub struct Heavy<T>(pub T);
+
+impl<T> Drop for Heavy<T> {
+    fn drop(&mut self) {}
+}
+
+#[inline(never)]
+pub fn may_panic() {}
+
+// `heavy` is only dropped if `may_panic()` unwinds.
+pub fn consume<T>(heavy: Heavy<T>) {
+    may_panic();
+    ::std::mem::forget(heavy);
+}
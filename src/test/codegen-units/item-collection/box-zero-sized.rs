@@ -0,0 +1,46 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Check that boxing and dropping zero-sized values needs neither the
+// `exchange_malloc` lang item nor an instance of `box_free`, so that a
+// `no_std` crate that only boxes such values needs no allocator.
+
+#![feature(lang_items, box_syntax)]
+#![no_std]
+#![crate_type = "lib"]
+#![deny(dead_code)]
+
+#[lang = "owned_box"]
+pub struct Box<T: ?Sized>(*mut T);
+
+#[lang = "box_free"]
+unsafe fn box_free<T: ?Sized>(_ptr: *mut T) {}
+
+pub struct Unit;
+
+//~ TRANS_ITEM fn box_zero_sized::boxed[0]<box_zero_sized::Unit>
+fn boxed<T>(value: T) -> Box<T> {
+    box value
+}
+
+//~ TRANS_ITEM fn box_zero_sized::make_unit[0]
+pub fn make_unit() -> Box<Unit> {
+    boxed(Unit)
+}
+
+//~ TRANS_ITEM fn box_zero_sized::make_empty_array[0]
+pub fn make_empty_array() -> Box<[u64; 0]> {
+    box []
+}
+
+//~ TRANS_ITEM fn box_zero_sized::drop_unit[0]
+pub fn drop_unit(_unit: Box<Unit>) {}
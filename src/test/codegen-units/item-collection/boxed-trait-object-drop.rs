@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Check that dropping a boxed trait object collects the drop glue of the box
+// and `box_free`, but no drop glue for the trait object itself, which is
+// dropped through the destructor in its vtable.
+
+#![feature(lang_items)]
+#![no_std]
+#![crate_type = "lib"]
+#![deny(dead_code)]
+
+#[lang = "owned_box"]
+pub struct Box<T: ?Sized>(*mut T);
+
+//~ TRANS_ITEM fn boxed_trait_object_drop::box_free[0]<boxed_trait_object_drop::Trait[0]>
+#[lang = "box_free"]
+unsafe fn box_free<T: ?Sized>(_ptr: *mut T) {}
+
+pub trait Trait {
+    fn method(&self);
+}
+
+//~ TRANS_ITEM fn boxed_trait_object_drop::drop_boxed[0]
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<boxed_trait_object_drop::Box[0]<boxed_trait_object_drop::Trait[0]>>
+pub fn drop_boxed(_boxed: Box<Trait>) {}
@@ -0,0 +1,52 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+#![feature(closure_to_fn_coercion)]
+
+//~ TRANS_ITEM fn closure_fn_pointers::as_fn_once[0]<closure_fn_pointers::main[0]::{{closure}}[1]>
+fn as_fn_once<F: FnOnce() -> u32>(f: F) -> F { f }
+
+trait Make {
+    fn make() -> u32;
+}
+
+impl Make for u8 {
+    //~ TRANS_ITEM fn closure_fn_pointers::{{impl}}[0]::make[0]
+    fn make() -> u32 { 8 }
+}
+
+// The closure is resolved with the type parameters of the instance that
+// casts it.
+//~ TRANS_ITEM fn closure_fn_pointers::generic[0]<u8>
+//~ TRANS_ITEM fn closure_fn_pointers::generic[0]::{{closure}}[0]<u8>
+//~ TRANS_ITEM fn core::ops[0]::FnOnce[0]::call_once[0]<closure_fn_pointers::generic[0]::{{closure}}[0]<u8>, ()>
+fn generic<T: Make>() -> u32 {
+    let make: fn() -> u32 = || T::make();
+    make()
+}
+
+//~ TRANS_ITEM fn closure_fn_pointers::main[0]
+fn main() {
+    // The body of an `Fn` closure takes its environment by reference, so the
+    // function pointer points to the `FnOnce` shim calling it.
+    //~ TRANS_ITEM fn closure_fn_pointers::main[0]::{{closure}}[0]
+    //~ TRANS_ITEM fn core::ops[0]::FnOnce[0]::call_once[0]<closure_fn_pointers::main[0]::{{closure}}[0], ()>
+    let by_ref: fn() -> u32 = || 1;
+
+    // The body of an `FnOnce` closure can be pointed to directly.
+    //~ TRANS_ITEM fn closure_fn_pointers::main[0]::{{closure}}[1]
+    let by_value: fn() -> u32 = as_fn_once(|| 2);
+
+    let _ = by_ref() + by_value() + generic::<u8>();
+}
@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+#![feature(const_fn)]
+
+pub struct Callback(fn() -> u32);
+
+//~ TRANS_ITEM fn const_fn_in_static::generic[0]<u8>
+fn generic<T>() -> u32 { 0 }
+
+// Only called at compile time, so there is no translation item for it, but
+// the function pointer it stores ends up in the static.
+const fn make_callback() -> Callback {
+    Callback(generic::<u8>)
+}
+
+//~ TRANS_ITEM static const_fn_in_static::CALLBACK[0]
+static CALLBACK: Callback = make_callback();
+
+//~ TRANS_ITEM fn const_fn_in_static::main[0]
+fn main() {
+    let _ = (CALLBACK.0)();
+}
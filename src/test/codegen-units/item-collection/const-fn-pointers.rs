@@ -0,0 +1,63 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that the functions that constants refer to are collected, whether
+// through arrays of function pointers, trait objects, or generic associated
+// constants. The functions are generic, so that nothing else makes them
+// roots.
+
+#![feature(associated_consts)]
+
+//~ TRANS_ITEM fn const_fn_pointers::alpha[0]<u8>
+fn alpha<T>() -> u32 { 1 }
+
+//~ TRANS_ITEM fn const_fn_pointers::beta[0]<u16>
+fn beta<T>() -> u32 { 2 }
+
+const HANDLERS: [fn() -> u32; 2] = [alpha::<u8>, beta::<u16>];
+
+trait Handler {
+    fn handle(&self) -> u32;
+}
+
+struct Generic<T>(T);
+
+impl<T> Handler for Generic<T> {
+    //~ TRANS_ITEM fn const_fn_pointers::{{impl}}[0]::handle[0]<u32>
+    fn handle(&self) -> u32 { 3 }
+}
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<const_fn_pointers::Generic[0]<u32>>
+const HANDLER: &'static Handler = &Generic(0u32);
+
+trait Registry {
+    const ENTRY: fn() -> u32;
+    const DEFAULT_ENTRY: fn() -> u32 = gamma::<Self>;
+}
+
+struct Marker<T>(T);
+
+impl<T> Registry for Marker<T> {
+    const ENTRY: fn() -> u32 = delta::<T>;
+}
+
+//~ TRANS_ITEM fn const_fn_pointers::gamma[0]<const_fn_pointers::Marker[0]<i8>>
+fn gamma<T: ?Sized>() -> u32 { 4 }
+
+//~ TRANS_ITEM fn const_fn_pointers::delta[0]<i8>
+fn delta<T>() -> u32 { 5 }
+
+//~ TRANS_ITEM fn const_fn_pointers::main[0]
+fn main() {
+    let _ = (HANDLERS[0])() + (HANDLERS[1])() + HANDLER.handle();
+    let _ = (<Marker<i8> as Registry>::ENTRY)() + (<Marker<i8> as Registry>::DEFAULT_ENTRY)();
+}
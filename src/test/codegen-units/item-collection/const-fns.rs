@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+#![feature(const_fn)]
+
+// Only called at compile time, in an array length and in a constant, so
+// there is no translation item for it.
+const fn len() -> usize { 4 }
+
+const LEN: usize = len();
+
+//~ TRANS_ITEM fn const_fns::double[0]
+const fn double(x: u32) -> u32 { x * 2 }
+
+//~ TRANS_ITEM fn const_fns::triple[0]
+const fn triple(x: u32) -> u32 { x * 3 }
+
+//~ TRANS_ITEM fn const_fns::main[0]
+fn main() {
+    let array = [0u8; len()];
+    // Called at runtime.
+    let doubled = double(array.len() as u32 + LEN as u32);
+    // Reified.
+    let f: fn(u32) -> u32 = triple;
+    let _ = doubled + f(1);
+}
@@ -0,0 +1,93 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+#![feature(associated_consts)]
+
+trait Layout {
+    const IS_ZST: bool;
+    const KIND: u8 = 2;
+}
+
+impl Layout for () {
+    const IS_ZST: bool = true;
+    const KIND: u8 = 0;
+}
+
+impl Layout for u32 {
+    const IS_ZST: bool = false;
+}
+
+const SMALL: u8 = 1;
+
+impl Layout for u16 {
+    const IS_ZST: bool = false;
+    const KIND: u8 = SMALL;
+}
+
+//~ TRANS_ITEM fn constant_switch_arms::zst_path[0]<()>
+//~ TRANS_ITEM fn constant_switch_arms::zst_path[0]<u8>
+fn zst_path<T>() {}
+
+//~ TRANS_ITEM fn constant_switch_arms::normal_path[0]<u32>
+//~ TRANS_ITEM fn constant_switch_arms::normal_path[0]<u8>
+fn normal_path<T>() {}
+
+//~ TRANS_ITEM fn constant_switch_arms::small_path[0]<u16>
+fn small_path<T>() {}
+
+// Only the arm selected by the associated constant is instantiated.
+//~ TRANS_ITEM fn constant_switch_arms::dispatch[0]<()>
+//~ TRANS_ITEM fn constant_switch_arms::dispatch[0]<u32>
+fn dispatch<T: Layout>() {
+    if T::IS_ZST {
+        zst_path::<T>()
+    } else {
+        normal_path::<T>()
+    }
+}
+
+// The same goes for a `match`, including when the constant is defaulted in
+// the trait or defined in terms of another constant.
+//~ TRANS_ITEM fn constant_switch_arms::match_dispatch[0]<()>
+//~ TRANS_ITEM fn constant_switch_arms::match_dispatch[0]<u32>
+//~ TRANS_ITEM fn constant_switch_arms::match_dispatch[0]<u16>
+fn match_dispatch<T: Layout>() {
+    match T::KIND {
+        0 => zst_path::<T>(),
+        1 => small_path::<T>(),
+        _ => normal_path::<T>(),
+    }
+}
+
+// A discriminant that is not a constant keeps all arms.
+//~ TRANS_ITEM fn constant_switch_arms::runtime_dispatch[0]<u8>
+fn runtime_dispatch<T>(zst: bool) {
+    if zst {
+        zst_path::<T>()
+    } else {
+        normal_path::<T>()
+    }
+}
+
+//~ TRANS_ITEM fn constant_switch_arms::main[0]
+fn main() {
+    dispatch::<()>();
+    dispatch::<u32>();
+
+    match_dispatch::<()>();
+    match_dispatch::<u32>();
+    match_dispatch::<u16>();
+
+    runtime_dispatch::<u8>(false);
+}
@@ -8,11 +8,6 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// In the current version of the collector that still has to support
-// legacy-trans, closures do not generate their own TransItems, so we are
-// ignoring this test until MIR trans has taken over completely
-// ignore-test
-
 // ignore-tidy-linelength
 // compile-flags:-Zprint-trans-items=eager
 
@@ -35,6 +30,19 @@ fn main() {
     // Nothing should be generated for this call, we just link to the instance
     // in the extern crate.
     let _ = cgu_extern_closures::non_inlined_fn(6, 7);
-}
 
-//~ TRANS_ITEM drop-glue i8
+    // An `Fn` closure called through `FnOnce` is called by a shim, which in
+    // turn calls the body of the closure.
+    //~ TRANS_ITEM fn cgu_extern_closures::through_fn_once[0]<u8>
+    //~ TRANS_ITEM fn cgu_extern_closures::through_fn_once[0]::{{closure}}[0]<u8>
+    //~ TRANS_ITEM fn cgu_extern_closures::call_once[0]<cgu_extern_closures::through_fn_once[0]::{{closure}}[0]<u8>>
+    //~ TRANS_ITEM fn core::ops[0]::FnOnce[0]::call_once[0]<cgu_extern_closures::through_fn_once[0]::{{closure}}[0]<u8>, (i32)>
+    let _ = cgu_extern_closures::through_fn_once(8, 9u8);
+
+    // Likewise for the shim that a function pointer to an `Fn` closure points
+    // to.
+    //~ TRANS_ITEM fn cgu_extern_closures::through_fn_pointer[0]<u8>
+    //~ TRANS_ITEM fn cgu_extern_closures::through_fn_pointer[0]::{{closure}}[0]<u8>
+    //~ TRANS_ITEM fn core::ops[0]::FnOnce[0]::call_once[0]<cgu_extern_closures::through_fn_pointer[0]::{{closure}}[0]<u8>, (i32)>
+    let _ = cgu_extern_closures::through_fn_pointer(10, 11u8);
+}
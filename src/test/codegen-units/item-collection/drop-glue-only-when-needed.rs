@@ -0,0 +1,54 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+
+// Test that dropping values of types without drop glue does not create drop
+// glue items, while vtables still get one for their destructor slot.
+
+trait Trait {
+    fn get(&self) -> u32;
+}
+
+struct CopyPair(u32, u64);
+
+impl Trait for CopyPair {
+    //~ TRANS_ITEM fn drop_glue_only_when_needed::{{impl}}[0]::get[0]
+    fn get(&self) -> u32 { self.0 }
+}
+
+struct WithDrop(u32);
+
+impl Drop for WithDrop {
+    //~ TRANS_ITEM fn drop_glue_only_when_needed::{{impl}}[1]::drop[0]
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<drop_glue_only_when_needed::WithDrop[0]> @@ drop_glue_only_when_needed.cgu-0[Internal]
+
+//~ TRANS_ITEM fn drop_glue_only_when_needed::consume[0]<u32>
+//~ TRANS_ITEM fn drop_glue_only_when_needed::consume[0]<&str>
+//~ TRANS_ITEM fn drop_glue_only_when_needed::consume[0]<drop_glue_only_when_needed::CopyPair[0]>
+//~ TRANS_ITEM fn drop_glue_only_when_needed::consume[0]<drop_glue_only_when_needed::WithDrop[0]>
+fn consume<T>(_value: T) {}
+
+//~ TRANS_ITEM fn drop_glue_only_when_needed::main[0]
+fn main() {
+    consume(0u32);
+    consume("str");
+    consume(CopyPair(0, 1));
+    consume(WithDrop(0));
+
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<drop_glue_only_when_needed::CopyPair[0]> @@ drop_glue_only_when_needed.cgu-0[Internal]
+    let _ = &CopyPair(1, 2) as &Trait;
+}
@@ -0,0 +1,58 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+
+// Test that the drop glue of an enum whose variants share field types still
+// references the drop glue of every field type with a destructor, and nothing
+// for the variants without one.
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<drop_glue_shared_variant_fields::StructWithDrop[0]> @@ drop_glue_shared_variant_fields.cgu-0[Internal]
+struct StructWithDrop(u32);
+
+impl Drop for StructWithDrop {
+    //~ TRANS_ITEM fn drop_glue_shared_variant_fields::{{impl}}[0]::drop[0]
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<drop_glue_shared_variant_fields::Wrapper[0]<u8>> @@ drop_glue_shared_variant_fields.cgu-0[Internal]
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<drop_glue_shared_variant_fields::Wrapper[0]<u16>> @@ drop_glue_shared_variant_fields.cgu-0[Internal]
+struct Wrapper<T>(T);
+
+impl<T> Drop for Wrapper<T> {
+    //~ TRANS_ITEM fn drop_glue_shared_variant_fields::{{impl}}[1]::drop[0]<u8>
+    //~ TRANS_ITEM fn drop_glue_shared_variant_fields::{{impl}}[1]::drop[0]<u16>
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<drop_glue_shared_variant_fields::ManyVariants[0]> @@ drop_glue_shared_variant_fields.cgu-0[Internal]
+enum ManyVariants {
+    A(StructWithDrop, Wrapper<u8>),
+    B(Wrapper<u8>, StructWithDrop),
+    C(StructWithDrop, Wrapper<u16>, u32),
+    D(Wrapper<u16>, Wrapper<u8>),
+    E(u32, u64),
+    F(StructWithDrop),
+    G,
+}
+
+//~ TRANS_ITEM fn drop_glue_shared_variant_fields::main[0]
+fn main() {
+    let _a = ManyVariants::A(StructWithDrop(0), Wrapper(0));
+    let _b = ManyVariants::B(Wrapper(0), StructWithDrop(0));
+    let _c = ManyVariants::C(StructWithDrop(0), Wrapper(0), 0);
+    let _d = ManyVariants::D(Wrapper(0), Wrapper(0));
+    let _e = ManyVariants::E(0, 0);
+    let _f = ManyVariants::F(StructWithDrop(0));
+    let _g = ManyVariants::G;
+}
@@ -0,0 +1,61 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Test that eager mode only adds the drop glue of the non-generic types of
+// the crate as roots if they have a destructor somewhere in their structure.
+
+#![allow(dead_code)]
+#![crate_type="lib"]
+#![feature(untagged_unions)]
+
+pub struct Plain {
+    a: u32,
+    b: (u8, char),
+}
+
+pub enum PlainEnum {
+    A(Plain),
+    B([u64; 4]),
+}
+
+pub union PlainUnion {
+    a: u32,
+    b: f32,
+}
+
+pub struct WithDrop;
+
+impl Drop for WithDrop {
+    //~ TRANS_ITEM fn eager_drop_glue_roots::{{impl}}[0]::drop[0]
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<eager_drop_glue_roots::WithDrop[0]>
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<eager_drop_glue_roots::ContainsDrop[0]>
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<(u32, eager_drop_glue_roots::WithDrop[0])>
+pub struct ContainsDrop {
+    plain: Plain,
+    nested: (u32, WithDrop),
+}
+
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<eager_drop_glue_roots::SomeVariantDrops[0]>
+pub enum SomeVariantDrops {
+    Plain(Plain),
+    Dropping(ContainsDrop),
+}
+
+// Generic types are not roots, whether they need drop glue or not.
+pub struct Generic<T> {
+    value: T,
+}
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+#![feature(start)]
+
+// `'b: 'a` makes both lifetimes early-bound, so the function is generic over
+// lifetimes although it has no type parameters.
+//~ TRANS_ITEM fn entry_fn_with_lifetimes::first[0]
+fn first<'a, 'b: 'a>(args: &'a [&'b str]) -> &'a str {
+    args[0]
+}
+
+//~ TRANS_ITEM fn entry_fn_with_lifetimes::start[0]
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let arg = "arg";
+    first(&[arg]).len() as isize - 3
+}
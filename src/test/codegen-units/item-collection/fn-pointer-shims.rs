@@ -0,0 +1,35 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that calling a function pointer needs no shim, while using it as a
+// closure trait object needs the shims in the vtable. Calling a function
+// pointer through a closure trait in generic code needs the shim too, see
+// `function-as-argument.rs`.
+
+#![deny(dead_code)]
+
+//~ TRANS_ITEM fn fn_pointer_shims::function[0]
+fn function() {}
+
+//~ TRANS_ITEM fn fn_pointer_shims::main[0]
+fn main() {
+    let pointer: fn() = function;
+    pointer();
+
+    //~ TRANS_ITEM fn core::ops[0]::Fn[0]::call[0]<fn(), ()>
+    //~ TRANS_ITEM fn core::ops[0]::FnMut[0]::call_mut[0]<fn(), ()>
+    //~ TRANS_ITEM fn core::ops[0]::FnOnce[0]::call_once[0]<fn(), ()>
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<fn()>
+    let object: &Fn() = &pointer;
+    object();
+}
@@ -0,0 +1,44 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Test that the items referenced from the promoted values of a generic
+// function are collected for each of its instances, whether the promoted
+// values depend on the type parameters or not.
+
+#![deny(dead_code)]
+
+//~ TRANS_ITEM fn generic_promoted_values::plain[0]
+fn plain() -> u32 { 0 }
+
+//~ TRANS_ITEM fn generic_promoted_values::generic[0]<u8>
+//~ TRANS_ITEM fn generic_promoted_values::generic[0]<u64>
+fn generic<T>() -> u32 { 1 }
+
+//~ TRANS_ITEM fn generic_promoted_values::user[0]<u8>
+//~ TRANS_ITEM fn generic_promoted_values::user[0]<u64>
+fn user<T>() -> u32 {
+    let independent: &'static fn() -> u32 = &(plain as fn() -> u32);
+    let dependent: &'static fn() -> u32 = &(generic::<T> as fn() -> u32);
+    independent() + dependent()
+}
+
+//~ TRANS_ITEM promoted[0] of fn generic_promoted_values::user[0]<u8>
+//~ TRANS_ITEM promoted[1] of fn generic_promoted_values::user[0]<u8>
+//~ TRANS_ITEM promoted[0] of fn generic_promoted_values::user[0]<u64>
+//~ TRANS_ITEM promoted[1] of fn generic_promoted_values::user[0]<u64>
+
+//~ TRANS_ITEM fn generic_promoted_values::main[0]
+fn main() {
+    user::<u8>();
+    user::<u64>();
+}
@@ -0,0 +1,56 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that `impl Trait` values are dropped and coerced to trait objects as
+// the types they stand for, even when they go through generic functions.
+
+#![feature(conservative_impl_trait)]
+
+trait Speak {
+    fn speak(&self) -> u32;
+}
+
+struct Loud<T>(T);
+
+impl<T> Speak for Loud<T> {
+    //~ TRANS_ITEM fn impl_trait_drop::{{impl}}[0]::speak[0]<u8>
+    //~ TRANS_ITEM fn impl_trait_drop::{{impl}}[0]::speak[0]<u16>
+    fn speak(&self) -> u32 {
+        1
+    }
+}
+
+impl<T> Drop for Loud<T> {
+    //~ TRANS_ITEM fn impl_trait_drop::{{impl}}[1]::drop[0]<u8>
+    //~ TRANS_ITEM fn impl_trait_drop::{{impl}}[1]::drop[0]<u16>
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn impl_trait_drop::make[0]<u8>
+//~ TRANS_ITEM fn impl_trait_drop::make[0]<u16>
+fn make<T>(x: T) -> impl Speak {
+    Loud(x)
+}
+
+//~ TRANS_ITEM fn impl_trait_drop::consume[0]<impl_trait_drop::Loud[0]<u8>>
+fn consume<S: Speak>(s: S) -> u32 {
+    s.speak()
+}
+
+//~ TRANS_ITEM fn impl_trait_drop::main[0]
+fn main() {
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<impl_trait_drop::Loud[0]<u8>>
+    let _ = consume(make(0u8));
+
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<impl_trait_drop::Loud[0]<u16>>
+    let _ = (&make(0u16) as &Speak).speak();
+}
@@ -0,0 +1,50 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Check that the fallbacks for 128-bit arithmetic are roots in the crate
+// defining them, like `compiler_builtins`. Nothing in MIR refers to them:
+// a 128-bit division is a `BinaryOp`, which LLVM turns into a call of the
+// fallback by its symbol name on targets without native support. Other
+// crates cannot translate these non-generic functions themselves, so their
+// users add nothing to collect.
+
+#![feature(i128_type)]
+#![crate_type = "staticlib"]
+#![deny(dead_code)]
+
+use std::ops::{Div, Rem};
+
+fn div_rem<T>(a: T, b: T) -> (T, T)
+    where T: Div<Output=T> + Rem<Output=T> + Copy
+{
+    (a / b, a % b)
+}
+
+//~ TRANS_ITEM fn integer_fallback_roots::udivti3[0]
+//~ TRANS_ITEM fn integer_fallback_roots::div_rem[0]<u128>
+#[export_name = "__udivti3"]
+pub extern "C" fn udivti3(a: u128, b: u128) -> u128 {
+    div_rem(a, b).0
+}
+
+//~ TRANS_ITEM fn integer_fallback_roots::umodti3[0]
+#[export_name = "__umodti3"]
+pub extern "C" fn umodti3(a: u128, b: u128) -> u128 {
+    div_rem(a, b).1
+}
+
+// Only the fallbacks are roots of a `staticlib`, so this is not collected.
+// Where it is, its division adds no neighbours either.
+pub fn divide(a: u128, b: u128) -> u128 {
+    a / b
+}
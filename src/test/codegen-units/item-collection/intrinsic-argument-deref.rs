@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+
+// aux-build:cgu_intrinsic_deref.rs
+extern crate cgu_intrinsic_deref;
+
+use cgu_intrinsic_deref::{SmartPtr, copy_from};
+
+//~ TRANS_ITEM fn intrinsic_argument_deref::main[0]
+fn main() {
+    let src = SmartPtr(1u32);
+    let mut dst = 0u32;
+
+    // The argument of `copy_nonoverlapping` is produced by a call to
+    // `Deref::deref` inside an upstream #[inline] function.
+    //~ TRANS_ITEM fn cgu_intrinsic_deref::copy_from[0]<u32>
+    //~ TRANS_ITEM fn cgu_intrinsic_deref::{{impl}}[0]::deref[0]<u32>
+    copy_from(&src, &mut dst);
+}
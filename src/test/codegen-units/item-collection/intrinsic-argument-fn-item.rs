@@ -0,0 +1,32 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+#![feature(core_intrinsics)]
+
+use std::intrinsics;
+
+// Function items passed to an intrinsic are reified before the call, like
+// for any other call, so they are collected without looking at the operands
+// of intrinsic calls.
+
+//~ TRANS_ITEM fn intrinsic_argument_fn_item::callback[0]<u32>
+fn callback<T>(_: *mut u8) {}
+
+//~ TRANS_ITEM fn intrinsic_argument_fn_item::main[0]
+fn main() {
+    let data = 0 as *mut u8;
+    unsafe {
+        intrinsics::try(callback::<u32>, data, data);
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Check that the functions referenced only from the bodies of constants are
+// collected, however deeply the constants are nested and however many items
+// use them.
+
+#![deny(dead_code)]
+#![feature(associated_consts)]
+
+trait Trait {
+    const F: fn() -> u32;
+}
+
+struct A;
+struct B;
+
+//~ TRANS_ITEM fn nested_associated_consts::a_fn[0]
+fn a_fn() -> u32 { 1 }
+
+//~ TRANS_ITEM fn nested_associated_consts::b_fn[0]
+fn b_fn() -> u32 { 2 }
+
+impl Trait for A {
+    const F: fn() -> u32 = a_fn;
+}
+
+impl Trait for B {
+    const F: fn() -> u32 = <A as Trait>::F;
+}
+
+const PAIR: (fn() -> u32, fn() -> u32) = (<B as Trait>::F, b_fn);
+const NESTED: [(fn() -> u32, fn() -> u32); 2] = [PAIR, PAIR];
+
+//~ TRANS_ITEM fn nested_associated_consts::call_f[0]<nested_associated_consts::A[0]>
+//~ TRANS_ITEM fn nested_associated_consts::call_f[0]<nested_associated_consts::B[0]>
+fn call_f<T: Trait>() -> u32 {
+    (T::F)()
+}
+
+//~ TRANS_ITEM fn nested_associated_consts::user1[0]
+fn user1() -> u32 {
+    (NESTED[0].0)() + (PAIR.1)()
+}
+
+//~ TRANS_ITEM fn nested_associated_consts::user2[0]
+fn user2() -> u32 {
+    (NESTED[1].1)() + (NESTED[0].0)()
+}
+
+//~ TRANS_ITEM fn nested_associated_consts::main[0]
+fn main() {
+    user1();
+    user2();
+    call_f::<A>();
+    call_f::<B>();
+}
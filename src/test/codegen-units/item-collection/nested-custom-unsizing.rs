@@ -0,0 +1,76 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Check that the vtable methods are collected for custom unsizing coercions
+// that go through more than one user-defined smart pointer, and through a
+// pointer to a struct that is unsized itself.
+
+#![deny(dead_code)]
+#![feature(coerce_unsized)]
+#![feature(unsize)]
+
+use std::marker::Unsize;
+use std::ops::CoerceUnsized;
+
+trait Trait {
+    fn foo(&self);
+}
+
+impl Trait for u8 {
+    fn foo(&self) {}
+}
+
+impl Trait for u16 {
+    fn foo(&self) {}
+}
+
+impl Trait for u32 {
+    fn foo(&self) {}
+}
+
+struct Inner<T: ?Sized>(*const T);
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Inner<U>> for Inner<T> {}
+
+struct Outer<T: ?Sized> {
+    _tag: u32,
+    _inner: Inner<T>,
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Outer<U>> for Outer<T> {}
+
+struct MyCell<T: ?Sized> {
+    _flag: bool,
+    _value: T,
+}
+
+//~ TRANS_ITEM fn nested_custom_unsizing::main[0]
+fn main() {
+    // Through two smart pointers down to the raw pointer.
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<u8>
+    //~ TRANS_ITEM fn nested_custom_unsizing::{{impl}}[0]::foo[0]
+    let outer = Outer { _tag: 0, _inner: Inner(&1u8) };
+    let _outer = outer as Outer<Trait>;
+
+    // Through a smart pointer to a struct with an unsized field.
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<u16>
+    //~ TRANS_ITEM fn nested_custom_unsizing::{{impl}}[1]::foo[0]
+    let cell = MyCell { _flag: true, _value: 2u16 };
+    let _cell = Inner(&cell) as Inner<MyCell<Trait>>;
+
+    // Both at once.
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<u32>
+    //~ TRANS_ITEM fn nested_custom_unsizing::{{impl}}[2]::foo[0]
+    let cell = MyCell { _flag: false, _value: 3u32 };
+    let outer = Outer { _tag: 1, _inner: Inner(&cell) };
+    let _outer = outer as Outer<MyCell<Trait>>;
+}
@@ -0,0 +1,45 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Test that statics declared inside a function body are only translated
+// along with a function referring to them, unless they are `#[no_mangle]`.
+
+//~ TRANS_ITEM fn nested_statics::called[0]
+fn called() -> u32 {
+    //~ TRANS_ITEM static nested_statics::called[0]::COUNTER[0]
+    static COUNTER: u32 = 1;
+    COUNTER
+}
+
+//~ TRANS_ITEM fn nested_statics::instantiated[0]<u8>
+fn instantiated<T>() -> u32 {
+    //~ TRANS_ITEM static nested_statics::instantiated[0]::IN_INSTANTIATED[0]
+    static IN_INSTANTIATED: u32 = 2;
+    IN_INSTANTIATED
+}
+
+fn never_instantiated<T>() -> u32 {
+    static IN_UNINSTANTIATED: u32 = 3;
+
+    //~ TRANS_ITEM static nested_statics::never_instantiated[0]::NO_MANGLE[0]
+    #[no_mangle]
+    static NO_MANGLE: u32 = 4;
+
+    IN_UNINSTANTIATED + NO_MANGLE
+}
+
+//~ TRANS_ITEM fn nested_statics::main[0]
+fn main() {
+    called();
+    instantiated::<u8>();
+}
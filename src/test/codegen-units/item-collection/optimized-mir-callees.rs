@@ -0,0 +1,43 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy -Zmir-opt-level=2
+
+// The collector walks the same optimized MIR that translation consumes, so
+// callees absorbed by the MIR inliner and calls in branches removed by
+// `SimplifyBranches` do not show up as translation items.
+
+#![deny(dead_code)]
+
+#[inline(always)]
+fn helper<T>(x: T) -> T {
+    x
+}
+
+fn heavy<T: Default>() -> T {
+    T::default()
+}
+
+//~ TRANS_ITEM fn optimized_mir_callees::not_inlined[0]<u64>
+#[inline(never)]
+fn not_inlined<T>(x: T) -> T {
+    x
+}
+
+//~ TRANS_ITEM fn optimized_mir_callees::main[0]
+fn main() {
+    let _ = helper(0u32);
+    let _ = not_inlined(0u64);
+
+    if false {
+        let _ = heavy::<u8>();
+    }
+}
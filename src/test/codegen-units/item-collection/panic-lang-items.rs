@@ -0,0 +1,40 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager -Coverflow-checks=on
+// aux-build:cgu_panic_lang_items.rs
+
+// Check that the panic functions called by failing assertions are collected.
+// Nothing but the assertions themselves refers to them here.
+
+#![feature(no_core)]
+#![no_core]
+#![crate_type = "lib"]
+#![deny(dead_code)]
+
+extern crate cgu_panic_lang_items;
+
+//~ TRANS_ITEM fn cgu_panic_lang_items::panic_bounds_check[0]
+//~ TRANS_ITEM fn panic_lang_items::index[0]
+pub fn index(values: &[u32; 4], i: usize) -> u32 {
+    values[i]
+}
+
+//~ TRANS_ITEM fn cgu_panic_lang_items::panic[0]
+//~ TRANS_ITEM fn panic_lang_items::add[0]
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+//~ TRANS_ITEM fn panic_lang_items::divide[0]
+pub fn divide(a: u32, b: u32) -> u32 {
+    a / b
+}
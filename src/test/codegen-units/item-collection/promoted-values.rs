@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+
+//~ TRANS_ITEM fn promoted_values::main[0]
+fn main() {
+    // Promoted values are numbered in reverse order. Each of them gets a
+    // translation item, even if it is the same value as another one.
+    let _first = &[1, 2, 3];
+    let _second = &[1, 2, 3];
+    let _third = &[4, 5];
+}
+
+//~ TRANS_ITEM promoted[0] of fn promoted_values::main[0]
+//~ TRANS_ITEM promoted[1] of fn promoted_values::main[0]
+//~ TRANS_ITEM promoted[2] of fn promoted_values::main[0]
@@ -0,0 +1,64 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+
+trait Trait {
+    fn method(&self);
+}
+
+struct Struct;
+
+impl Trait for Struct {
+    //~ TRANS_ITEM fn shim_reasons::{{impl}}[0]::method[0]
+    fn method(&self) {}
+}
+
+struct StructWithDrop;
+
+impl Drop for StructWithDrop {
+    //~ TRANS_ITEM fn shim_reasons::{{impl}}[1]::drop[0]
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn shim_reasons::take_fn[0]<fn(u32)>
+fn take_fn<F: Fn(u32)>(f: F) {
+    f(0)
+}
+
+//~ TRANS_ITEM fn shim_reasons::take_fn_once[0]<shim_reasons::main[0]::{{closure}}[0]>
+fn take_fn_once<F: FnOnce()>(f: F) {
+    f()
+}
+
+//~ TRANS_ITEM fn shim_reasons::function[0]
+fn function(_: u32) {}
+
+//~ TRANS_ITEM fn shim_reasons::main[0]
+fn main() {
+    //~ TRANS_ITEM fn core::ops[0]::Fn[0]::call[0]<fn(u32), (u32)> ## shim: calls a `fn(u32)` through `std::ops::Fn::call`
+    take_fn(function as fn(u32));
+
+    //~ TRANS_ITEM fn shim_reasons::main[0]::{{closure}}[0]
+    //~ TRANS_ITEM fn core::ops[0]::FnOnce[0]::call_once[0]<shim_reasons::main[0]::{{closure}}[0], ()> ## shim: calls the `Fn` closure `shim_reasons::main[0]::{{closure}}[0]` through `FnOnce`
+    take_fn_once(|| {});
+
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<shim_reasons::Struct[0]> @@ shim_reasons.cgu-0[Internal] ## glue: drops `Struct`, required by a vtable
+    let _ = &Struct as &Trait;
+
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<shim_reasons::StructWithDrop[0]> @@ shim_reasons.cgu-0[Internal] ## glue: drops `StructWithDrop`, required directly
+    let _ = StructWithDrop;
+
+    //~ TRANS_ITEM fn shim_reasons::Trait[0]::method[0]<shim_reasons::Trait[0]> ## shim: calls `Trait::method` through a vtable
+    let _: fn(&Trait) = Trait::method;
+}
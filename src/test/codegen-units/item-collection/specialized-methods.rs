@@ -0,0 +1,45 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+// Check that vtables and eagerly translated provided methods use the method
+// of the most specialized impl, or of the closest impl it specializes, and
+// not the version provided by the trait.
+
+#![feature(specialization)]
+
+trait Describe {
+    fn describe(&self) -> u32 { 0 }
+    fn kind(&self) -> u32 { 0 }
+}
+
+impl<T> Describe for T {
+    //~ TRANS_ITEM fn specialized_methods::{{impl}}[0]::describe[0]<u8>
+    //~ TRANS_ITEM fn specialized_methods::{{impl}}[0]::describe[0]<u16>
+    default fn describe(&self) -> u32 { 1 }
+}
+
+impl Describe for u8 {
+    //~ TRANS_ITEM fn specialized_methods::{{impl}}[1]::kind[0]
+    fn kind(&self) -> u32 { 2 }
+}
+
+//~ TRANS_ITEM fn specialized_methods::main[0]
+fn main() {
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<u8>
+    let a = &0u8 as &Describe;
+
+    //~ TRANS_ITEM fn specialized_methods::Describe[0]::kind[0]<u16>
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<u16>
+    let b = &0u16 as &Describe;
+
+    let _ = a.describe() + a.kind() + b.describe() + b.kind();
+}
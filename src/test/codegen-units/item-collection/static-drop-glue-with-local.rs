@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+#![feature(drop_types_in_const)]
+
+// Test that the drop glue of a type is still translated when a local
+// variable of it is dropped, even if a static of the same type exists.
+
+struct StructWithDrop(u32);
+
+impl Drop for StructWithDrop {
+    //~ TRANS_ITEM fn static_drop_glue_with_local::{{impl}}[0]::drop[0]
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM static static_drop_glue_with_local::STATIC[0]
+static STATIC: StructWithDrop = StructWithDrop(0);
+
+//~ TRANS_ITEM fn static_drop_glue_with_local::main[0]
+fn main() {
+    let _ = STATIC.0;
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<static_drop_glue_with_local::StructWithDrop[0]>
+    let _local = StructWithDrop(1);
+}
@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+#![feature(drop_types_in_const)]
+
+// Test that statics are not dropped, so that neither the drop glue of their
+// type nor its `Drop::drop` method is translated for them.
+
+struct StructWithDrop(u32);
+
+impl Drop for StructWithDrop {
+    fn drop(&mut self) {}
+}
+
+struct Wrapper<T>(T);
+
+//~ TRANS_ITEM static static_drop_glue::DIRECT[0]
+static DIRECT: StructWithDrop = StructWithDrop(0);
+
+//~ TRANS_ITEM static static_drop_glue::NESTED[0]
+static NESTED: Option<Wrapper<StructWithDrop>> = Some(Wrapper(StructWithDrop(1)));
+
+//~ TRANS_ITEM fn static_drop_glue::main[0]
+fn main() {
+    let _ = DIRECT.0;
+    let _ = &NESTED;
+}
@@ -0,0 +1,57 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=eager
+
+#![deny(dead_code)]
+
+trait Trait {
+    fn method(&self) -> u32;
+}
+
+struct Struct<T> {
+    _a: T
+}
+
+impl<T> Trait for Struct<T> {
+    fn method(&self) -> u32 { 0 }
+}
+
+fn generic<T>() {}
+
+const CONST_FN: fn() = generic::<u16>;
+
+//~ TRANS_ITEM static static_initializers::FN[0]
+//~ TRANS_ITEM fn static_initializers::generic[0]<u8>
+static FN: fn() = generic::<u8>;
+
+//~ TRANS_ITEM static static_initializers::FNS[0]
+//~ TRANS_ITEM fn static_initializers::generic[0]<u32>
+//~ TRANS_ITEM fn static_initializers::generic[0]<u64>
+static FNS: [fn(); 2] = [generic::<u32>, generic::<u64>];
+
+//~ TRANS_ITEM static static_initializers::FROM_CONST[0]
+//~ TRANS_ITEM fn static_initializers::generic[0]<u16>
+static FROM_CONST: fn() = CONST_FN;
+
+//~ TRANS_ITEM static static_initializers::OBJECT[0]
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<static_initializers::Struct[0]<i8>>
+//~ TRANS_ITEM fn static_initializers::{{impl}}[0]::method[0]<i8>
+static OBJECT: &'static (Trait + Sync) = &Struct { _a: 0i8 };
+
+//~ TRANS_ITEM fn static_initializers::main[0]
+fn main() {
+    FN();
+    FNS[0]();
+    FNS[1]();
+    FROM_CONST();
+    OBJECT.method();
+}
@@ -0,0 +1,56 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+#![crate_type = "staticlib"]
+
+// Test that only the items with a fixed symbol name are roots in a crate that
+// is only built as a C-compatible library, and that the items they refer to,
+// through function pointers too, are still collected.
+
+//~ TRANS_ITEM fn staticlib_roots::exported[0]
+#[no_mangle]
+pub extern fn exported() -> u32 {
+    let f: fn() -> u32 = through_pointer;
+    if f() == called() {
+        USED_BY_EXPORT
+    } else {
+        0
+    }
+}
+
+//~ TRANS_ITEM fn staticlib_roots::through_pointer[0]
+fn through_pointer() -> u32 {
+    1
+}
+
+//~ TRANS_ITEM fn staticlib_roots::called[0]
+fn called() -> u32 {
+    1
+}
+
+fn dead() -> u32 {
+    2
+}
+
+pub fn public_but_not_exported() -> u32 {
+    3
+}
+
+//~ TRANS_ITEM static staticlib_roots::USED_BY_EXPORT[0]
+static USED_BY_EXPORT: u32 = 4;
+
+static UNUSED: u32 = 5;
+
+//~ TRANS_ITEM static staticlib_roots::NO_MANGLE[0]
+#[no_mangle]
+pub static NO_MANGLE: u32 = 6;
@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+#![feature(drop_types_in_const)]
+#![feature(thread_local)]
+
+// Test that `#[thread_local]` statics are not dropped either: nothing
+// registers a destructor for them, so the drop glue of their type is not
+// translated, just like for other statics.
+
+struct StructWithDrop(u32);
+
+impl Drop for StructWithDrop {
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM static thread_local_static_drop_glue::LOCAL[0]
+#[thread_local]
+static LOCAL: StructWithDrop = StructWithDrop(0);
+
+//~ TRANS_ITEM fn thread_local_static_drop_glue::main[0]
+fn main() {
+    let _ = LOCAL.0;
+}
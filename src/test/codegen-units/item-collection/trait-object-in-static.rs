@@ -0,0 +1,52 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that the methods in the vtables of trait objects created in the
+// initializers of statics and constants are collected, even if they are only
+// ever called through these.
+
+#![deny(dead_code)]
+
+trait Log {
+    fn log(&self, message: &str) -> usize;
+
+    fn name(&self) -> &'static str {
+        "log"
+    }
+}
+
+struct Prefixed<T>(T);
+
+impl<T> Log for Prefixed<T> {
+    //~ TRANS_ITEM fn trait_object_in_static::{{impl}}[0]::log[0]<u8>
+    //~ TRANS_ITEM fn trait_object_in_static::{{impl}}[0]::log[0]<u16>
+    fn log(&self, message: &str) -> usize {
+        message.len()
+    }
+}
+
+//~ TRANS_ITEM fn trait_object_in_static::Log[0]::name[0]<trait_object_in_static::Prefixed[0]<u8>>
+//~ TRANS_ITEM fn trait_object_in_static::Log[0]::name[0]<trait_object_in_static::Prefixed[0]<u16>>
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<trait_object_in_static::Prefixed[0]<u8>>
+//~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<trait_object_in_static::Prefixed[0]<u16>>
+
+//~ TRANS_ITEM static trait_object_in_static::LOGGER[0]
+static LOGGER: &'static Log = &Prefixed(0u8);
+
+const CONST_LOGGER: &'static Log = &Prefixed(0u16);
+
+//~ TRANS_ITEM fn trait_object_in_static::main[0]
+fn main() {
+    LOGGER.log(LOGGER.name());
+    CONST_LOGGER.log(CONST_LOGGER.name());
+}
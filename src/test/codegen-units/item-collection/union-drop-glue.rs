@@ -0,0 +1,48 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that dropping a union never leads to the drop glue of its fields,
+// only to its own destructor.
+
+#![feature(untagged_unions)]
+#![allow(dead_code)]
+
+struct HasString {
+    s: String,
+}
+
+struct AlsoHasString {
+    s: String,
+}
+
+union Plain {
+    a: HasString,
+    b: u32,
+}
+
+union WithDrop {
+    a: AlsoHasString,
+    b: u32,
+}
+
+impl Drop for WithDrop {
+    //~ TRANS_ITEM fn union_drop_glue::{{impl}}[0]::drop[0]
+    fn drop(&mut self) {}
+}
+
+//~ TRANS_ITEM fn union_drop_glue::main[0]
+fn main() {
+    let _plain = Plain { b: 0 };
+
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<union_drop_glue::WithDrop[0]>
+    let _with_drop = WithDrop { b: 1 };
+}
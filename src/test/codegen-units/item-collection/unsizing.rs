@@ -9,7 +9,7 @@
 // except according to those terms.
 
 // ignore-tidy-linelength
-// compile-flags:-Zprint-trans-items=eager
+// compile-flags:-Zprint-trans-items=eager -Zverify-mono-determinism
 
 #![deny(dead_code)]
 #![feature(coerce_unsized)]
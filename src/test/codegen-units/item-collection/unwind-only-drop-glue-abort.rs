@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy -Cpanic=abort
+// aux-build:cgu_unwind_only_drop.rs
+
+// Check that drop glue only called when unwinding is not collected with
+// `-C panic=abort`, even from the MIR of a crate built with
+// `-C panic=unwind`. See `unwind-only-drop-glue-unwind.rs`.
+
+extern crate cgu_unwind_only_drop;
+
+use cgu_unwind_only_drop::{Heavy, consume};
+
+//~ TRANS_ITEM fn unwind_only_drop_glue_abort::main[0]
+fn main() {
+    //~ TRANS_ITEM fn cgu_unwind_only_drop::consume[0]<u8>
+    //~ TRANS_ITEM fn core::mem[0]::forget[0]<cgu_unwind_only_drop::Heavy[0]<u8>>
+    consume(Heavy(0u8));
+}
@@ -0,0 +1,40 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that the drop glue of arguments passed by value to methods called
+// through trait objects is collected, even if nothing else drops them.
+
+struct Payload<T>(T);
+
+impl<T> Drop for Payload<T> {
+    //~ TRANS_ITEM fn virtual_call_argument_drop::{{impl}}[0]::drop[0]<u8>
+    fn drop(&mut self) {}
+}
+
+trait Sink {
+    fn take(&self, payload: Payload<u8>);
+}
+
+struct Discard;
+
+impl Sink for Discard {
+    //~ TRANS_ITEM fn virtual_call_argument_drop::{{impl}}[1]::take[0]
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<virtual_call_argument_drop::Payload[0]<u8>>
+    fn take(&self, _payload: Payload<u8>) {}
+}
+
+//~ TRANS_ITEM fn virtual_call_argument_drop::main[0]
+fn main() {
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<virtual_call_argument_drop::Discard[0]>
+    let sink: &Sink = &Discard;
+    sink.take(Payload(0));
+}
@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that weak lang items defined in a panic-runtime-style crate are
+// collected, although nothing in the crate calls them.
+
+#![feature(lang_items)]
+#![no_std]
+#![crate_type = "rlib"]
+
+//~ TRANS_ITEM fn weak_lang_items::panic_fmt[0]
+#[lang = "panic_fmt"]
+extern fn panic_fmt(_: core::fmt::Arguments, _: &'static str, _: u32) -> ! {
+    loop {}
+}
+
+//~ TRANS_ITEM fn weak_lang_items::eh_personality[0]
+#[lang = "eh_personality"]
+extern fn eh_personality() {}
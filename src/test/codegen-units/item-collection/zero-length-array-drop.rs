@@ -0,0 +1,48 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// compile-flags:-Zprint-trans-items=lazy
+
+// Check that dropping an array of length zero does not lead to the drop glue
+// of its element type, while the glue of the struct containing it remains.
+
+#![allow(dead_code)]
+
+struct Heavy<T>(T);
+
+impl<T> Drop for Heavy<T> {
+    //~ TRANS_ITEM fn zero_length_array_drop::{{impl}}[0]::drop[0]<u64>
+    fn drop(&mut self) {
+        heavy_cleanup::<T>();
+    }
+}
+
+//~ TRANS_ITEM fn zero_length_array_drop::heavy_cleanup[0]<u64>
+fn heavy_cleanup<T>() {}
+
+struct Empty {
+    id: u32,
+    heavies: [Heavy<u32>; 0],
+}
+
+struct One {
+    heavies: [Heavy<u64>; 1],
+}
+
+//~ TRANS_ITEM fn zero_length_array_drop::main[0]
+fn main() {
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<zero_length_array_drop::Empty[0]>
+    let _empty = Empty { id: 0, heavies: [] };
+
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<zero_length_array_drop::One[0]>
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<[zero_length_array_drop::Heavy[0]<u64>; 1]>
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<zero_length_array_drop::Heavy[0]<u64>>
+    let _one = One { heavies: [Heavy(1)] };
+}
@@ -0,0 +1,56 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:-Zprint-trans-items=lazy -Zincremental=tmp/partitioning-tests/inline-never-and-naked
+
+// Check that functions marked `#[inline(never)]` or `#[naked]` get a single
+// definition, instead of being copied into every codegen unit using them.
+
+#![allow(dead_code)]
+#![crate_type="lib"]
+#![feature(naked_functions)]
+#![feature(stmt_expr_attributes)]
+
+mod shared {
+    //~ TRANS_ITEM fn inline_never_and_naked::shared[0]::naked[0] @@ inline_never_and_naked-shared[External]
+    #[naked]
+    #[inline]
+    pub fn naked() {}
+
+    //~ TRANS_ITEM fn inline_never_and_naked::shared[0]::run[0]<inline_never_and_naked::user1[0]::foo[0]::{{closure}}[0]>
+    pub fn run<F: Fn()>(f: F) {
+        f()
+    }
+}
+
+mod user1 {
+    use super::shared;
+
+    //~ TRANS_ITEM fn inline_never_and_naked::user1[0]::foo[0] @@ inline_never_and_naked-user1[External]
+    fn foo() {
+        shared::naked();
+
+        //~ TRANS_ITEM fn inline_never_and_naked::user1[0]::foo[0]::{{closure}}[0] @@ inline_never_and_naked-user1[External]
+        let f = #[inline(never)] || {};
+        f();
+        shared::run(f);
+    }
+}
+
+mod user2 {
+    use super::shared;
+
+    //~ TRANS_ITEM fn inline_never_and_naked::user2[0]::bar[0] @@ inline_never_and_naked-user2[External]
+    fn bar() {
+        shared::naked();
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:-Zprint-trans-items=lazy -Zincremental=tmp/partitioning-tests/promoted-values
+
+#![crate_type="lib"]
+#![feature(rvalue_static_promotion)]
+
+//~ TRANS_ITEM fn promoted_values::function[0] @@ promoted_values[External]
+pub fn function() -> &'static u32 {
+    &1
+}
+
+//~ TRANS_ITEM promoted[0] of fn promoted_values::function[0] @@ promoted_values[Internal]
+
+pub mod mod1 {
+    //~ TRANS_ITEM fn promoted_values::mod1[0]::function[0] @@ promoted_values-mod1[External]
+    pub fn function() -> &'static u32 {
+        &2
+    }
+
+    //~ TRANS_ITEM promoted[0] of fn promoted_values::mod1[0]::function[0] @@ promoted_values-mod1[Internal]
+
+    //~ TRANS_ITEM fn promoted_values::mod1[0]::inlined[0] @@ promoted_values[Internal] promoted_values-mod1[Internal]
+    #[inline]
+    pub fn inlined() -> &'static u32 {
+        &3
+    }
+
+    //~ TRANS_ITEM promoted[0] of fn promoted_values::mod1[0]::inlined[0] @@ promoted_values[Internal] promoted_values-mod1[Internal]
+
+    //~ TRANS_ITEM fn promoted_values::mod1[0]::user[0] @@ promoted_values-mod1[External]
+    pub fn user() -> &'static u32 {
+        inlined()
+    }
+}
+
+//~ TRANS_ITEM fn promoted_values::user[0] @@ promoted_values[External]
+pub fn user() -> &'static u32 {
+    mod1::inlined()
+}
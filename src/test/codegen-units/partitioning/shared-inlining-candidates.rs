@@ -0,0 +1,54 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:-Zprint-trans-items=lazy -Zincremental=tmp/partitioning-tests/shared-inlining-candidates
+
+// Check that inlining candidates referenced several times by the same item,
+// and by items in different codegen units, still end up in every codegen
+// unit that needs them.
+
+#![allow(dead_code)]
+#![crate_type="lib"]
+
+mod inline {
+
+    //~ TRANS_ITEM fn shared_inlining_candidates::inline[0]::leaf[0] @@ shared_inlining_candidates-user1[Internal] shared_inlining_candidates-user2[Internal]
+    #[inline(always)]
+    pub fn leaf() -> u32 {
+        1
+    }
+
+    //~ TRANS_ITEM fn shared_inlining_candidates::inline[0]::twice[0] @@ shared_inlining_candidates-user1[Internal] shared_inlining_candidates-user2[Internal]
+    #[inline(always)]
+    pub fn twice() -> u32 {
+        leaf() + leaf()
+    }
+}
+
+mod user1 {
+    use super::inline;
+
+    //~ TRANS_ITEM fn shared_inlining_candidates::user1[0]::foo[0] @@ shared_inlining_candidates-user1[External]
+    pub fn foo() -> u32 {
+        inline::twice() + inline::twice() + inline::leaf()
+    }
+}
+
+mod user2 {
+    use super::inline;
+
+    //~ TRANS_ITEM fn shared_inlining_candidates::user2[0]::bar[0] @@ shared_inlining_candidates-user2[External]
+    pub fn bar() -> u32 {
+        inline::leaf() + inline::twice()
+    }
+}
@@ -22,12 +22,14 @@ static FOO: u32 = 0;
 static BAR: u32 = 0;
 
 //~ TRANS_ITEM fn statics::function[0] @@ statics[External]
-fn function() {
+fn function() -> u32 {
     //~ TRANS_ITEM static statics::function[0]::FOO[0] @@ statics[External]
     static FOO: u32 = 0;
 
     //~ TRANS_ITEM static statics::function[0]::BAR[0] @@ statics[External]
     static BAR: u32 = 0;
+
+    FOO + BAR
 }
 
 mod mod1 {
@@ -38,11 +40,13 @@ mod mod1 {
     static BAR: u32 = 0;
 
     //~ TRANS_ITEM fn statics::mod1[0]::function[0] @@ statics-mod1[External]
-    fn function() {
+    fn function() -> u32 {
         //~ TRANS_ITEM static statics::mod1[0]::function[0]::FOO[0] @@ statics-mod1[External]
         static FOO: u32 = 0;
 
         //~ TRANS_ITEM static statics::mod1[0]::function[0]::BAR[0] @@ statics-mod1[External]
         static BAR: u32 = 0;
+
+        FOO + BAR
     }
 }
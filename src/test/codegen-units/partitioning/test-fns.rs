@@ -0,0 +1,38 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// ignore-tidy-linelength
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:--test -Zprint-trans-items=lazy -Zincremental=tmp/partitioning-tests/test-fns
+
+// Check that the tests and benchmarks run by the harness are collected and
+// stay visible to the harness in its own codegen unit.
+
+#![feature(test)]
+
+extern crate test;
+
+use test::Bencher;
+
+//~ TRANS_ITEM fn test_fns::first[0] @@ test_fns[External]
+#[test]
+fn first() {}
+
+//~ TRANS_ITEM fn test_fns::measure[0] @@ test_fns[External]
+#[bench]
+fn measure(_: &mut Bencher) {}
+
+mod nested {
+    //~ TRANS_ITEM fn test_fns::nested[0]::second[0] @@ test_fns-nested[External]
+    #[test]
+    fn second() {}
+}
+
+//~ TRANS_ITEM fn test_fns::__test[0]::main[0]
@@ -0,0 +1,55 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-tidy-linelength
+// ignore-msvc
+// We specify -Z incremental here because we want to test the partitioning for
+// incremental compilation
+// compile-flags:-Zprint-trans-items=lazy -Zincremental=tmp/partitioning-tests/unwind-lang-items
+
+// Check that an `#[inline]` personality function is copied into the codegen
+// units of the functions with landing pads, which refer to it without any
+// call in the MIR, and only into those.
+
+#![feature(lang_items)]
+#![no_std]
+#![crate_type = "rlib"]
+
+mod personality {
+    //~ TRANS_ITEM fn unwind_lang_items::personality[0]::eh_personality[0] @@ unwind_lang_items-guarded[Internal]
+    #[inline]
+    #[lang = "eh_personality"]
+    pub extern fn eh_personality() {}
+}
+
+pub mod guarded {
+    struct Guard;
+
+    impl Drop for Guard {
+        //~ TRANS_ITEM fn unwind_lang_items::guarded[0]::{{impl}}[0]::drop[0] @@ unwind_lang_items-guarded[External]
+        fn drop(&mut self) {}
+    }
+
+    //~ TRANS_ITEM fn unwind_lang_items::guarded[0]::may_panic[0] @@ unwind_lang_items-guarded[External]
+    #[inline(never)]
+    fn may_panic() {}
+
+    //~ TRANS_ITEM fn unwind_lang_items::guarded[0]::guarded[0] @@ unwind_lang_items-guarded[External]
+    //~ TRANS_ITEM fn core::ptr[0]::drop_in_place[0]<unwind_lang_items::guarded[0]::Guard[0]> @@ unwind_lang_items-guarded[Internal]
+    pub fn guarded() {
+        let _guard = Guard;
+        may_panic();
+    }
+}
+
+pub mod unguarded {
+    //~ TRANS_ITEM fn unwind_lang_items::unguarded[0]::unguarded[0] @@ unwind_lang_items-unguarded[External]
+    pub fn unguarded() {}
+}
@@ -0,0 +1,28 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Going through closures does not exempt unbounded recursion from the
+// recursion limit, the depth of the closures themselves is still limited.
+
+fn call<T, F: Fn(T) -> T>(f: &F, x: T) -> T {
+    f(x)
+}
+
+fn apply<T, F: Fn(T) -> T>(f: F, x: T) -> T {
+    let p: fn(&F, T) -> T = call::<T, F>;
+    p(&f, x)
+}
+
+fn descend<R>(x: u32) -> u32 {
+    apply(|y| apply(|z| descend::<(R,)>(z) + 1, y), x) //~ ERROR reached the recursion limit
+}
+
+fn main() {
+    descend::<()>(0);
+}
@@ -0,0 +1,41 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that every function exceeding a limit is reported in the same
+// compilation, instead of only the first one found.
+
+#![type_length_limit="8"]
+
+type T1 = ((),);
+type T2 = (T1,);
+type T3 = (T2,);
+type T4 = (T3,);
+type T5 = (T4,);
+type T6 = (T5,);
+type T7 = (T6,);
+type T8 = (T7,);
+type T9 = (T8,);
+type T10 = (T9,);
+
+fn a<T>() { //~ ERROR infinite instantiation cycle while instantiating `a::<
+    a::<Option<T>>();
+}
+
+fn b<T>() { //~ ERROR infinite instantiation cycle while instantiating `b::<
+    b::<Vec<T>>();
+}
+
+fn c<T>() {} //~ ERROR reached the type-length limit while instantiating `c::<
+
+fn main() {
+    a::<()>();
+    b::<()>();
+    c::<T10>();
+}
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a function instantiating itself with a growing type through
+// another function is reported as an infinite cycle, long before the
+// recursion limit is reached.
+
+#![recursion_limit="1000"]
+
+struct Wrapper<T>(T);
+
+fn outer<T>(value: T) { //~ ERROR infinite instantiation cycle while instantiating `outer::<
+    inner(Wrapper(value));
+}
+
+fn inner<T>(value: T) {
+    outer(value);
+}
+
+fn main() {
+    outer(0u8);
+}
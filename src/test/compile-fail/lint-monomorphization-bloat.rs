@@ -0,0 +1,33 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z monomorphization-bloat-threshold=2
+
+#![deny(monomorphization_bloat)]
+
+fn bloated<T>(_: T) {} //~ ERROR `bloated` is instantiated 3 times
+
+fn fine<T>(_: T) {}
+
+#[allow(monomorphization_bloat)]
+fn allowed<T>(_: T) {}
+
+fn main() {
+    bloated(1u8);
+    bloated(1u16);
+    bloated(1u32);
+
+    fine(1u8);
+    fine(1u16);
+
+    allowed(1u8);
+    allowed(1u16);
+    allowed(1u32);
+}
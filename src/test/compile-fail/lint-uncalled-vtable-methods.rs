@@ -0,0 +1,60 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![deny(uncalled_vtable_methods)]
+
+trait Shape {
+    fn area(&self) -> u32;
+    fn describe(&self) -> String;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 { self.0 * self.0 }
+    fn describe(&self) -> String { format!("a square of side {}", self.0) }
+}
+
+trait Counter {
+    fn count(&self) -> u32;
+}
+
+struct One;
+
+impl Counter for One {
+    fn count(&self) -> u32 { 1 }
+}
+
+struct Two;
+
+impl Counter for Two {
+    fn count(&self) -> u32 { 2 }
+}
+
+// The level is looked up at the function containing the coercion.
+#[allow(uncalled_vtable_methods)]
+fn allowed() -> u32 {
+    let shape: &Shape = &Square(3);
+    shape.area()
+}
+
+fn main() {
+    // `describe` is never called, neither directly nor through the object.
+    let shape: &Shape = &Square(2); //~ ERROR coercing `Square` to `Shape` instantiates 1 method
+    shape.area();
+
+    // `count` is called through a `Counter` object, so both vtables are fine,
+    // even though the one for `Two` is never used for the call.
+    let one: &Counter = &One;
+    let _two: &Counter = &Two;
+    one.count();
+
+    allowed();
+}
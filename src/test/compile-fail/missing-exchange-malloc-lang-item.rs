@@ -0,0 +1,26 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a `box` expression without an `exchange_malloc` lang item is
+// reported at the expression instead of as a fatal error without a span.
+
+#![feature(box_syntax, lang_items, no_core)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"] pub trait Sized {}
+#[lang = "copy"] pub trait Copy {}
+
+#[lang = "owned_box"]
+pub struct Box<T: ?Sized>(*mut T);
+
+pub fn make() -> Box<i32> {
+    box 5 //~ ERROR `box` expressions require the `exchange_malloc` lang item
+}
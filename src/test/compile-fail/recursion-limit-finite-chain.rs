@@ -0,0 +1,32 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a finite chain of instantiations that is deeper than the recursion
+// limit is reported as such, and not as an infinite cycle.
+
+#![recursion_limit="8"]
+
+trait Descend {
+    fn descend();
+}
+
+impl Descend for () {
+    fn descend() {}
+}
+
+impl<T: Descend> Descend for (T,) {
+    fn descend() { //~ ERROR reached the recursion limit while instantiating
+        T::descend();
+    }
+}
+
+fn main() {
+    <((((((((((((),),),),),),),),),),),) as Descend>::descend();
+}
@@ -0,0 +1,46 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the recursion limit is still reached by recursion that goes
+// through more functions than are searched on top of the collection stack.
+
+#![recursion_limit="8"]
+
+trait Wrap {
+    type Out: Wrap;
+}
+
+impl Wrap for () {
+    type Out = Option<()>;
+}
+
+impl<T> Wrap for Option<T> {
+    type Out = Option<Option<T>>;
+}
+
+// Growing through an associated type, this is not an infinite cycle as far as
+// the collector can tell.
+fn f0<T: Wrap>() { //~ ERROR reached the recursion limit while instantiating `f0::<
+    f1::<<T as Wrap>::Out>();
+}
+
+fn f1<T: Wrap>() { f2::<T>(); }
+fn f2<T: Wrap>() { f3::<T>(); }
+fn f3<T: Wrap>() { f4::<T>(); }
+fn f4<T: Wrap>() { f5::<T>(); }
+fn f5<T: Wrap>() { f6::<T>(); }
+fn f6<T: Wrap>() { f7::<T>(); }
+fn f7<T: Wrap>() { f8::<T>(); }
+fn f8<T: Wrap>() { f9::<T>(); }
+fn f9<T: Wrap>() { f0::<T>(); }
+
+fn main() {
+    f0::<()>();
+}
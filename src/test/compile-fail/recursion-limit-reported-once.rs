@@ -0,0 +1,25 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Test that a runaway recursion that branches at every level is reported
+// once, instead of once for every branch that would reach the limit.
+
+#![recursion_limit="16"]
+
+fn fork<T>(n: u32) { //~ ERROR found an infinite instantiation cycle while instantiating `fork::<
+    if n > 0 {
+        fork::<(T,)>(n - 1);
+        fork::<[T; 1]>(n - 1);
+        fork::<Option<T>>(n - 1);
+    }
+}
+
+fn main() {
+    fork::<()>(3);
+}
@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Statics cannot depend on type parameters, not even those of the generic
+// function or impl they are nested in.
+
+fn generic<T>(value: T) -> T {
+    static NESTED: Option<T> = None; //~ ERROR E0401
+    static POINTER: Option<fn(T)> = None; //~ ERROR E0401
+    value
+}
+
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn method(&self) {
+        static IN_IMPL: Option<T> = None; //~ ERROR E0401
+    }
+}
+
+fn main() {
+    generic(0u8);
+    Wrapper(0u8).method();
+}
@@ -0,0 +1,22 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -Z trans-item-limit=4
+// error-pattern: reached the translation item limit of 4
+// error-pattern: `trans_item_limit::generic[0]` has 4 instances
+
+fn generic<T>(_: T) {}
+
+fn main() {
+    generic(0u8);
+    generic(0u16);
+    generic(0u32);
+    generic(0u64);
+}
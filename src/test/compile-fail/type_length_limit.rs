@@ -9,6 +9,7 @@
 // except according to those terms.
 
 // error-pattern: reached the type-length limit while instantiating
+// error-pattern: consider adding a `#![type_length_limit="2048"]` attribute to your crate
 
 // Test that the type length limit can be changed.
 
@@ -0,0 +1,40 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// The field coerced by this `CoerceUnsized` impl has a projection for its
+// type, which translation cannot see through to find the vtable.
+
+#![feature(coerce_unsized)]
+
+use std::fmt::Debug;
+use std::ops::CoerceUnsized;
+
+trait Handle {
+    type Ptr;
+}
+
+impl Handle for u8 {
+    type Ptr = &'static u8;
+}
+
+impl Handle for Debug {
+    type Ptr = &'static Debug;
+}
+
+struct Wrapper<T: Handle + ?Sized> {
+    ptr: T::Ptr,
+}
+
+impl<T, U> CoerceUnsized<Wrapper<U>> for Wrapper<T>
+    where T: Handle + ?Sized, U: Handle + ?Sized, T::Ptr: CoerceUnsized<U::Ptr> {}
+
+fn main() {
+    let wrapper: Wrapper<u8> = Wrapper { ptr: &0 };
+    let _: Wrapper<Debug> = wrapper; //~ ERROR cannot translate the unsizing coercion
+}
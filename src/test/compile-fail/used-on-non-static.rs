@@ -0,0 +1,27 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that `#[used]` is only accepted on statics.
+
+#![feature(used)]
+
+#[used]
+static FOO: u32 = 0;
+
+#[used] //~ ERROR attribute must be applied to a `static` variable
+const BAR: u32 = 0;
+
+#[used] //~ ERROR attribute must be applied to a `static` variable
+fn baz() {}
+
+#[used] //~ ERROR attribute must be applied to a `static` variable
+struct Qux;
+
+fn main() {}
@@ -0,0 +1,44 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that overriding a provided method only under a `cfg` invalidates the
+// codegen unit of the impl doing so, but not that of other impls of the same
+// trait, when provided methods are instantiated eagerly.
+
+// revisions:rpass1 rpass2
+// compile-flags: -Z query-dep-graph -Z print-trans-items=eager
+
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+
+#![rustc_partition_translated(module="default_impl_overrides-a", cfg="rpass2")]
+#![rustc_partition_reused(module="default_impl_overrides-b", cfg="rpass2")]
+
+pub trait Trait {
+    fn provided(&self) -> u32 { 0 }
+    fn also_provided(&self) -> u32 { 1 }
+}
+
+pub mod a {
+    pub struct A;
+
+    impl ::Trait for A {
+        #[cfg(rpass2)]
+        fn provided(&self) -> u32 { 2 }
+    }
+}
+
+pub mod b {
+    pub struct B;
+
+    impl ::Trait for B {}
+}
+
+fn main() {}
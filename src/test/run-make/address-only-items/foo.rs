@@ -0,0 +1,22 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn first() -> u32 { 1 }
+fn second() -> u32 { 2 }
+fn third() -> u32 { 3 }
+
+fn main() {
+    let table: [fn() -> u32; 3] = [first, second, third];
+    let direct = second();
+    let indirect = table[1]();
+    if direct != indirect {
+        std::process::exit(1);
+    }
+}
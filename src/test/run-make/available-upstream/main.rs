@@ -0,0 +1,23 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate a;
+
+fn local() -> u32 {
+    3
+}
+
+fn main() {
+    assert_eq!(a::generic(1u32), 1);
+    assert_eq!(a::generic(2u64), 2);
+    assert_eq!(a::use_generic(), 1);
+    assert_eq!(a::inlined(), 2);
+    assert_eq!(local(), 3);
+}
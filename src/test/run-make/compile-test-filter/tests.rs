@@ -0,0 +1,45 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[inline(never)]
+fn parser_helper<T: Default>() -> T {
+    T::default()
+}
+
+#[inline(never)]
+fn lexer_helper<T: Default>() -> T {
+    T::default()
+}
+
+#[inline(never)]
+fn nested_helper<T: Default>() -> T {
+    T::default()
+}
+
+mod parser {
+    #[test]
+    fn accepts_input() {
+        assert_eq!(::parser_helper::<u32>(), 0);
+    }
+
+    mod nested {
+        #[test]
+        fn accepts_nested_input() {
+            assert_eq!(::nested_helper::<u16>(), 0);
+        }
+    }
+}
+
+mod lexer {
+    #[test]
+    fn rejects_input() {
+        assert_eq!(::lexer_helper::<u64>(), 0);
+    }
+}
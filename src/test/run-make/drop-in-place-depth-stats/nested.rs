@@ -0,0 +1,24 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct S0(Box<u8>);
+struct S1(S0);
+struct S2(S1);
+struct S3(S2);
+struct S4(S3);
+struct S5(S4);
+struct S6(S5);
+struct S7(S6);
+struct S8(S7);
+struct S9(S8);
+
+fn main() {
+    let _s = S9(S8(S7(S6(S5(S4(S3(S2(S1(S0(Box::new(0))))))))));
+}
@@ -0,0 +1,36 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+pub struct Guard<T: Clone> {
+    value: T,
+}
+
+impl<T: Clone> Guard<T> {
+    #[inline]
+    pub fn new(value: T) -> Guard<T> {
+        Guard { value: value }
+    }
+}
+
+impl<T: Clone> Drop for Guard<T> {
+    fn drop(&mut self) {
+        release(self.value.clone(), |value| { observe(&value); });
+    }
+}
+
+#[inline]
+fn release<T, F: Fn(T)>(value: T, f: F) {
+    f(value)
+}
+
+#[inline]
+fn observe<T>(_: &T) {}
@@ -0,0 +1,37 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate lib;
+
+use lib::Guard;
+
+mod a {
+    pub fn guard_u32() {
+        let _guard = ::Guard::new(1u32);
+    }
+}
+
+mod b {
+    pub fn guard_string() {
+        let _guard = ::Guard::new(String::from("b"));
+    }
+}
+
+mod c {
+    pub fn guard_both() {
+        let _guards = (::Guard::new(2u32), ::Guard::new(vec![3u8]));
+    }
+}
+
+fn main() {
+    a::guard_u32();
+    b::guard_string();
+    c::guard_both();
+}
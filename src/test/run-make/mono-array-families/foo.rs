@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub struct Wrapper<T>(pub T);
+
+fn checksum<T>(data: &Wrapper<T>) -> usize {
+    let bytes = data as *const Wrapper<T> as *const u8;
+    let mut acc = 0;
+    for i in 0..::std::mem::size_of::<T>() {
+        acc ^= unsafe { *bytes.offset(i as isize) } as usize;
+    }
+    acc
+}
+
+fn unrelated<T>(_: T) {}
+
+pub fn run() -> usize {
+    unrelated(0u32);
+    unrelated('a');
+    checksum(&Wrapper([0u8; 16])) ^
+    checksum(&Wrapper([0u8; 32])) ^
+    checksum(&Wrapper([0u8; 64]))
+}
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub fn first() {
+    second::<u32>();
+}
+
+fn second<T>() {
+    third::<T>();
+}
+
+fn third<T>() {
+    fourth();
+}
+
+// Out of fuel, so it must not be translated as a local copy in `third` either.
+#[inline]
+fn fourth() {
+    fifth::<u32>();
+}
+
+fn fifth<T>() {}
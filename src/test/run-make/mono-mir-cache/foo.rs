@@ -0,0 +1,49 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(box_syntax)]
+
+// A generic function instantiated many times, whose body uses other items in
+// all the ways the collector looks at.
+
+use std::fmt::Debug;
+
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl<T: Debug> Describe for T {
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+const OFFSET: usize = 1;
+
+fn helper<T>(_: &T) -> usize {
+    OFFSET
+}
+
+fn hot<T: Debug + Clone + 'static>(value: T) -> usize {
+    let boxed = box value.clone();
+    let object: &Describe = &*boxed;
+    let reified: fn(&T) -> usize = helper::<T>;
+    let closure: fn() -> usize = || OFFSET;
+    let size = unsafe { std::intrinsics::size_of::<T>() };
+    let pair = (value, vec![boxed]);
+    object.describe().len() + reified(&pair.0) + closure() + size
+}
+
+fn main() {
+    let total = hot(1u8) + hot(2u16) + hot(3u32) + hot(4u64) + hot('c') +
+                hot("str") + hot(String::from("string")) + hot(vec![1, 2]) +
+                hot(Some(1i8)) + hot((1i16, 2i32)) + hot([0u8; 4]) + hot(Box::new(1));
+    println!("{}", total);
+}
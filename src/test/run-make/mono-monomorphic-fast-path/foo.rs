@@ -0,0 +1,42 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Trait {
+    fn method(&self) -> u32;
+}
+
+struct Struct(u32);
+
+impl Trait for Struct {
+    fn method(&self) -> u32 { self.0 }
+}
+
+impl Drop for Struct {
+    fn drop(&mut self) {}
+}
+
+fn callee(x: u32) -> u32 { x + 1 }
+
+fn generic<T: Into<u64>>(x: T) -> u64 { x.into() }
+
+fn f1() -> u32 { let s = Struct(1); let t: &Trait = &s; callee(t.method()) }
+fn f2() -> u32 { let s = Struct(2); let t: &Trait = &s; callee(t.method()) }
+fn f3() -> u32 { let s = Struct(3); let t: &Trait = &s; callee(t.method()) }
+fn f4() -> u32 { let s = Struct(4); let t: &Trait = &s; callee(t.method()) }
+fn f5() -> u32 { let s = Struct(5); let t: &Trait = &s; callee(t.method()) }
+fn f6() -> u32 { let s = Struct(6); let t: &Trait = &s; callee(t.method()) }
+fn f7() -> u32 { let s = Struct(7); let t: &Trait = &s; callee(t.method()) }
+fn f8() -> u32 { let s = Struct(8); let t: &Trait = &s; callee(t.method()) }
+
+fn main() {
+    let sum = f1() + f2() + f3() + f4() + f5() + f6() + f7() + f8();
+    assert_eq!(sum, 44);
+    assert_eq!(generic(1u32) + generic(2u64), 3);
+}
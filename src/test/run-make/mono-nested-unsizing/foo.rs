@@ -0,0 +1,69 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::rc::Rc;
+
+trait SomeTrait {
+    fn method(&self) -> u32;
+}
+
+struct SomeStruct(u32);
+
+impl SomeTrait for SomeStruct {
+    fn method(&self) -> u32 { self.0 }
+}
+
+struct OtherStruct;
+
+impl SomeTrait for OtherStruct {
+    fn method(&self) -> u32 { 1 }
+}
+
+struct ComplexStruct<T: ?Sized> {
+    a: u32,
+    b: f64,
+    c: T,
+}
+
+struct Outer<T: ?Sized> {
+    tag: u8,
+    inner: ComplexStruct<T>,
+}
+
+fn by_ref(s: &ComplexStruct<SomeStruct>) -> u32 {
+    let t: &ComplexStruct<SomeTrait> = s;
+    t.a + t.c.method()
+}
+
+fn by_box(s: Box<ComplexStruct<SomeStruct>>) -> u32 {
+    let t: Box<ComplexStruct<SomeTrait>> = s;
+    t.b as u32 + t.c.method()
+}
+
+fn by_rc(s: Rc<Outer<SomeStruct>>) -> u32 {
+    let t: Rc<Outer<SomeTrait>> = s;
+    t.tag as u32 + t.inner.c.method()
+}
+
+fn by_raw(s: &Outer<OtherStruct>) -> u32 {
+    let t: *const Outer<SomeTrait> = s;
+    unsafe { (*t).inner.c.method() }
+}
+
+fn main() {
+    let mut sum = by_ref(&ComplexStruct { a: 1, b: 0.0, c: SomeStruct(2) });
+    sum += by_box(Box::new(ComplexStruct { a: 0, b: 3.0, c: SomeStruct(4) }));
+    sum += by_rc(Rc::new(Outer {
+        tag: 5,
+        inner: ComplexStruct { a: 0, b: 0.0, c: SomeStruct(6) },
+    }));
+    sum += by_raw(&Outer { tag: 0, inner: ComplexStruct { a: 0, b: 0.0, c: OtherStruct } });
+    assert_eq!(sum, 22);
+}
@@ -0,0 +1,30 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Depth {
+    fn depth() -> u32;
+}
+
+impl Depth for () {
+    fn depth() -> u32 { 0 }
+}
+
+// Instantiated recursively, so its def-id is found on the collection stack.
+impl<T: Depth> Depth for Option<T> {
+    fn depth() -> u32 { T::depth() + 1 }
+}
+
+fn depth_of<T: Depth>() -> u32 {
+    T::depth()
+}
+
+fn main() {
+    assert_eq!(depth_of::<Option<Option<Option<()>>>>(), 3);
+}
@@ -0,0 +1,42 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+impl Point {
+    fn new(x: u32, y: u32) -> Point {
+        Point { x: x, y: y }
+    }
+
+    fn x(&self) -> u32 {
+        self.x
+    }
+}
+
+struct Wrapper<T> {
+    value: T,
+}
+
+impl<T: Copy> Wrapper<T> {
+    fn get(&self) -> T {
+        self.value
+    }
+}
+
+fn main() {
+    let p = Point::new(1, 2);
+    let a = Wrapper { value: 3u32 };
+    let b = Wrapper { value: 4u64 };
+    assert_eq!(p.x() + p.y + a.get(), 6);
+    assert_eq!(b.get(), 4);
+}
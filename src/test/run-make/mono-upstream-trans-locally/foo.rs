@@ -0,0 +1,33 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+fn bytes() -> Vec<u8> {
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2);
+    v
+}
+
+fn words() -> Vec<u32> {
+    let mut v = Vec::new();
+    v.push(3);
+    v.push(4);
+    v
+}
+
+fn more_words() -> Vec<u32> {
+    let mut v = words();
+    v.push(5);
+    v
+}
+
+fn main() {
+    assert_eq!(bytes().len() + words().len() + more_words().len(), 7);
+}
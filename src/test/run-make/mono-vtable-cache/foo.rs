@@ -0,0 +1,53 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::rc::Rc;
+
+trait Trait {
+    fn method(&self) -> u32;
+}
+
+struct Struct(u32);
+
+impl Trait for Struct {
+    fn method(&self) -> u32 { self.0 }
+}
+
+struct Wrapper<T: ?Sized> {
+    tag: u8,
+    inner: T,
+}
+
+fn by_ref(s: &Struct) -> u32 {
+    let t: &Trait = s;
+    t.method()
+}
+
+fn by_box(s: Struct) -> u32 {
+    let t: Box<Trait> = Box::new(s);
+    t.method()
+}
+
+fn by_rc(s: Struct) -> u32 {
+    let t: Rc<Trait> = Rc::new(s);
+    t.method()
+}
+
+fn by_field(w: &Wrapper<Struct>) -> u32 {
+    let t: &Wrapper<Trait> = w;
+    t.tag as u32 + t.inner.method()
+}
+
+fn main() {
+    let mut sum = by_ref(&Struct(1)) + by_ref(&Struct(2));
+    sum += by_box(Struct(3)) + by_rc(Struct(4)) + by_rc(Struct(5));
+    sum += by_field(&Wrapper { tag: 6, inner: Struct(7) });
+    assert_eq!(sum, 28);
+}
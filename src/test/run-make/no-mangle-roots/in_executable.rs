@@ -0,0 +1,23 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub struct Exported;
+
+impl Exported {
+    #[export_name = "renamed_impl_method"]
+    pub extern "C" fn impl_method() {}
+}
+
+pub mod nested {
+    #[no_mangle]
+    pub extern "C" fn nested_fn() {}
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "lib"]
+#![feature(specialization)]
+
+pub trait Describe {
+    fn name(&self) -> &'static str;
+    fn describe(&self) -> &'static str { "a thing" }
+    fn shout(&self) -> &'static str { "A THING" }
+}
+
+impl<T> Describe for T {
+    default fn name(&self) -> &'static str { "anything" }
+    default fn describe(&self) -> &'static str { "anything at all" }
+}
+
+pub struct Special;
+
+impl Describe for Special {
+    fn name(&self) -> &'static str { "special" }
+}
@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub mod parser {
+    pub struct Token(pub String);
+
+    pub fn parse<T: Clone>(value: &T) -> T {
+        value.clone()
+    }
+}
+
+pub mod lexer {
+    pub fn lex(input: &str) -> usize {
+        input.len()
+    }
+}
+
+fn main() {
+    let _token = parser::Token(String::from("token"));
+    parser::parse(&1u32);
+    lexer::lex("input");
+}
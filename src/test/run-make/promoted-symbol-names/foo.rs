@@ -0,0 +1,22 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rvalue_static_promotion)]
+
+fn values() -> (&'static [u32; 3], &'static [u32; 3], &'static [u32; 2]) {
+    (&[1, 2, 3], &[1, 2, 3], &[4, 5])
+}
+
+fn main() {
+    let (first, second, third) = values();
+    if first != second || third.len() != 2 {
+        std::process::exit(1);
+    }
+}
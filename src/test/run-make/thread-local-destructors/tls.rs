@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
+use std::thread;
+
+static DROPPED: AtomicBool = ATOMIC_BOOL_INIT;
+
+struct Noisy(u32);
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        DROPPED.store(true, Ordering::SeqCst);
+    }
+}
+
+thread_local!(static NOISY: Noisy = Noisy(1));
+
+fn main() {
+    thread::spawn(|| {
+        NOISY.with(|noisy| assert_eq!(noisy.0, 1));
+    }).join().unwrap();
+    assert!(DROPPED.load(Ordering::SeqCst));
+}
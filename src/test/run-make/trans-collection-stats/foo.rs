@@ -0,0 +1,31 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn total<T: Shape>(shapes: &[T]) -> u32 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+fn main() {
+    let squares = vec![Square(1), Square(2)];
+    let boxed: Box<Shape> = Box::new(Square(3));
+    assert_eq!(total(&squares) + boxed.area(), 14);
+}
@@ -0,0 +1,21 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+#![feature(used)]
+
+// Nothing refers to `PLUGIN`; something scanning the `.my_plugins` section
+// at runtime would find it.
+#[used]
+#[cfg_attr(not(target_os = "macos"), link_section = ".my_plugins")]
+#[cfg_attr(target_os = "macos", link_section = "__DATA,__my_plugins")]
+static PLUGIN: fn() = plugin_init;
+
+fn plugin_init() {}
+
+fn main() {}
@@ -0,0 +1,32 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+pub trait Log {
+    fn log(&self, value: u32) -> u32;
+}
+
+struct Doubler;
+
+impl Log for Doubler {
+    fn log(&self, value: u32) -> u32 {
+        value * 2
+    }
+}
+
+struct Adder<T>(T);
+
+impl<T: Copy + Into<u32>> Log for Adder<T> {
+    fn log(&self, value: u32) -> u32 {
+        value + self.0.into()
+    }
+}
+
+pub static DOUBLER: &'static Log = &Doubler;
+pub const ADDER: &'static Log = &Adder(3u8);
@@ -0,0 +1,34 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+pub trait Trait {
+    fn value(&self) -> u32;
+}
+
+struct Counted(Rc<Cell<u32>>);
+
+impl Trait for Counted {
+    fn value(&self) -> u32 {
+        7
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+pub fn make(drops: Rc<Cell<u32>>) -> Box<Trait> {
+    Box::new(Counted(drops))
+}
@@ -0,0 +1,78 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that boxes of zero-sized values call neither a user-defined
+// `exchange_malloc` nor a user-defined `box_free`, while boxes of other
+// values call both, once.
+
+#![feature(box_syntax, lang_items, libc, start)]
+#![no_std]
+
+extern crate libc;
+
+use core::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+
+static ALLOCATED: AtomicUsize = ATOMIC_USIZE_INIT;
+static FREED: AtomicUsize = ATOMIC_USIZE_INIT;
+static DROPPED: AtomicUsize = ATOMIC_USIZE_INIT;
+
+#[lang = "owned_box"]
+pub struct Box<T: ?Sized>(*mut T);
+
+#[lang = "exchange_malloc"]
+unsafe fn allocate(size: usize, _align: usize) -> *mut u8 {
+    ALLOCATED.fetch_add(1, Ordering::SeqCst);
+    libc::malloc(size) as *mut u8
+}
+
+#[lang = "box_free"]
+unsafe fn box_free<T>(ptr: *mut T) {
+    FREED.fetch_add(1, Ordering::SeqCst);
+    libc::free(ptr as *mut libc::c_void);
+}
+
+struct Zst([u64; 0]);
+
+impl Drop for Zst {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    {
+        let zst: Box<Zst> = box Zst([]);
+        let addr = zst.0 as usize;
+        assert!(addr != 0);
+        assert_eq!(addr % core::mem::align_of::<Zst>(), 0);
+    }
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 0);
+    assert_eq!(FREED.load(Ordering::SeqCst), 0);
+
+    {
+        let value: Box<u32> = box 5;
+        assert_eq!(*value, 5);
+    }
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 1);
+    assert_eq!(FREED.load(Ordering::SeqCst), 1);
+    0
+}
+
+#[lang = "eh_personality"] extern fn eh_personality() {}
+#[lang = "eh_unwind_resume"] extern fn eh_unwind_resume() {}
+#[lang = "panic_fmt"] extern fn panic_fmt() -> ! { loop {} }
+#[no_mangle] pub extern fn rust_eh_register_frames () {}
+#[no_mangle] pub extern fn rust_eh_unregister_frames () {}
+
+#[cfg(target_os = "android")]
+#[link(name="gcc")]
+extern { }
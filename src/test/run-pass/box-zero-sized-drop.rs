@@ -0,0 +1,45 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a box of a zero-sized value with a destructor has a non-null,
+// well-aligned address, although nothing is allocated for it, and that the
+// destructor of the value runs when the box is dropped.
+
+#![feature(box_syntax)]
+
+use std::mem;
+
+static mut DROPS: usize = 0;
+
+struct Zst([u64; 0]);
+
+impl Drop for Zst {
+    fn drop(&mut self) {
+        unsafe { DROPS += 1; }
+    }
+}
+
+fn boxed<T>(value: T) -> Box<T> {
+    box value
+}
+
+fn check_and_drop(b: Box<Zst>, drops: usize) {
+    let addr = &*b as *const Zst as usize;
+    assert!(addr != 0);
+    assert_eq!(addr % mem::align_of::<Zst>(), 0);
+    drop(b);
+    assert_eq!(unsafe { DROPS }, drops);
+}
+
+fn main() {
+    check_and_drop(box Zst([]), 1);
+    check_and_drop(boxed(Zst([])), 2);
+    check_and_drop(Box::new(Zst([])), 3);
+}
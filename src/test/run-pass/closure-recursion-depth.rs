@@ -0,0 +1,46 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Each level of this recursive-descent-style structure instantiates `apply`
+// and `call` twice, through a pair of closures and a fn pointer. The
+// recursion limit only applies to the depth of the closures, though, so 40
+// levels stay well below the default limit of 64.
+
+fn call<T, F: Fn(T) -> T>(f: &F, x: T) -> T {
+    f(x)
+}
+
+fn apply<T, F: Fn(T) -> T>(f: F, x: T) -> T {
+    let p: fn(&F, T) -> T = call::<T, F>;
+    p(&f, x)
+}
+
+trait Rule {
+    fn descend(x: u32) -> u32;
+}
+
+impl Rule for () {
+    fn descend(x: u32) -> u32 {
+        x
+    }
+}
+
+impl<R: Rule> Rule for (R,) {
+    fn descend(x: u32) -> u32 {
+        apply(|y| apply(|z| R::descend(z) + 1, y), x)
+    }
+}
+
+type Four<R> = ((((R,),),),);
+type Sixteen<R> = Four<Four<Four<Four<R>>>>;
+type Forty = Sixteen<Sixteen<Four<Four<()>>>>;
+
+fn main() {
+    assert_eq!(<Forty as Rule>::descend(2), 42);
+}
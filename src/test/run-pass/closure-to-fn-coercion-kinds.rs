@@ -0,0 +1,54 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that closures of all kinds can be called through the function
+// pointers they are coerced to, at runtime, in constants and in generic
+// functions.
+
+#![feature(closure_to_fn_coercion)]
+
+fn as_fn_mut<F: FnMut(u32) -> u32>(f: F) -> F { f }
+fn as_fn_once<F: FnOnce(u32) -> u32>(f: F) -> F { f }
+
+const IN_CONST: fn(u32) -> u32 = |x| x + 1;
+
+trait Factor {
+    fn factor() -> u32;
+}
+
+impl Factor for u8 {
+    fn factor() -> u32 { 5 }
+}
+
+impl Factor for u16 {
+    fn factor() -> u32 { 6 }
+}
+
+// The closure depends on the type parameter of the function casting it.
+fn generic<T: Factor>(x: u32) -> u32 {
+    let by_type: fn(u32) -> u32 = |x| x * T::factor();
+    by_type(x)
+}
+
+fn main() {
+    let by_ref: fn(u32) -> u32 = |x| x * 2;
+    let by_mut: fn(u32) -> u32 = as_fn_mut(|x| x * 3);
+    let by_value: fn(u32) -> u32 = as_fn_once(|x| x * 4);
+
+    assert_eq!(by_ref(1), 2);
+    assert_eq!(by_mut(1), 3);
+    assert_eq!(by_value(1), 4);
+    assert_eq!(IN_CONST(1), 2);
+    assert_eq!(generic::<u8>(1), 5);
+    assert_eq!(generic::<u16>(1), 6);
+
+    let pointers = [by_ref, by_mut, by_value, IN_CONST];
+    assert_eq!(pointers.iter().map(|f| f(10)).sum::<u32>(), 20 + 30 + 40 + 11);
+}
@@ -0,0 +1,40 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// aux-build:ctor_fn_values.rs
+
+// Check that tuple struct and variant constructors can be used as function
+// values, including those of other crates.
+
+extern crate ctor_fn_values;
+
+use ctor_fn_values::{Newtype, Wrapped};
+
+struct Local(u32);
+
+static MAKE_NEWTYPE: fn(u32) -> Newtype = Newtype;
+static MAKE_LOCAL: fn(u32) -> Local = Local;
+
+fn apply<T, R, F: Fn(T) -> R>(f: F, x: T) -> R {
+    f(x)
+}
+
+fn main() {
+    let wrapped: Vec<_> = vec![1, 2].into_iter().map(Wrapped::Value).collect();
+    assert_eq!(wrapped, [Wrapped::Value(1), Wrapped::Value(2)]);
+
+    let options: Vec<_> = vec![3].into_iter().map(Some).collect();
+    assert_eq!(options, [Some(3)]);
+
+    assert_eq!((MAKE_NEWTYPE)(4).0, 4);
+    assert_eq!((MAKE_LOCAL)(5).0, 5);
+    assert_eq!(apply(Newtype, 6).0, 6);
+    assert_eq!(apply(Wrapped::Value, 'x'), Wrapped::Value('x'));
+    assert!(apply(Wrapped::Value::<()>, ()) != Wrapped::Empty);
+}
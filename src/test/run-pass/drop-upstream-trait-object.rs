@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:upstream_trait_object.rs
+
+// Check that a boxed trait object whose vtable is only created in another
+// crate can be dropped, through the destructor in that vtable.
+
+extern crate upstream_trait_object;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use upstream_trait_object::{make, Trait};
+
+fn main() {
+    let drops = Rc::new(Cell::new(0));
+    {
+        let boxed: Box<Trait> = make(drops.clone());
+        assert_eq!(boxed.value(), 7);
+    }
+    assert_eq!(drops.get(), 1);
+}
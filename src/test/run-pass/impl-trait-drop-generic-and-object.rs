@@ -0,0 +1,53 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Check that the destructors of `impl Trait` values run when the values are
+// dropped by generic functions and through trait objects.
+
+#![feature(conservative_impl_trait)]
+
+use std::cell::Cell;
+
+trait Speak {
+    fn speak(&self) -> u32;
+}
+
+struct Loud<'a, T>(T, &'a Cell<u32>);
+
+impl<'a, T> Speak for Loud<'a, T> {
+    fn speak(&self) -> u32 {
+        self.1.get()
+    }
+}
+
+impl<'a, T> Drop for Loud<'a, T> {
+    fn drop(&mut self) {
+        self.1.set(self.1.get() + 1);
+    }
+}
+
+fn make<'a, T>(x: T, drops: &'a Cell<u32>) -> impl Speak + 'a where T: 'a {
+    Loud(x, drops)
+}
+
+fn consume<S: Speak>(s: S) -> u32 {
+    s.speak()
+}
+
+fn main() {
+    let drops = Cell::new(0);
+    assert_eq!(consume(make(0u8, &drops)), 0);
+    assert_eq!(drops.get(), 1);
+
+    {
+        let boxed: Box<Speak> = Box::new(make("loud", &drops));
+        assert_eq!(boxed.speak(), 1);
+    }
+    assert_eq!(drops.get(), 2);
+}
@@ -0,0 +1,64 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that instantiations whose type arguments grow through an associated
+// type are not mistaken for an infinite cycle, even though the same impl is
+// instantiated with ever larger types, as the impls of the associated type end
+// the chain.
+
+struct W<T>(T);
+
+struct Stop;
+
+trait Next {
+    type Out;
+    fn next(&self) -> Self::Out;
+}
+
+impl Next for u8 {
+    type Out = W<Box<u8>>;
+    fn next(&self) -> W<Box<u8>> {
+        W(Box::new(*self + 1))
+    }
+}
+
+impl Next for Box<u8> {
+    type Out = W<Box<Box<u8>>>;
+    fn next(&self) -> W<Box<Box<u8>>> {
+        W(Box::new(Box::new(**self + 1)))
+    }
+}
+
+impl Next for Box<Box<u8>> {
+    type Out = Stop;
+    fn next(&self) -> Stop {
+        Stop
+    }
+}
+
+trait Run {
+    fn run(&self) -> u32;
+}
+
+impl Run for Stop {
+    fn run(&self) -> u32 {
+        0
+    }
+}
+
+impl<U: Next> Run for W<U> where U::Out: Run {
+    fn run(&self) -> u32 {
+        self.0.next().run() + 1
+    }
+}
+
+fn main() {
+    assert_eq!(W(0u8).run(), 3);
+}
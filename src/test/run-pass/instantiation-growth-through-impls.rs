@@ -0,0 +1,50 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that instantiations with growing types are not mistaken for an infinite
+// cycle when every step goes through a different impl.
+
+struct Wrapper<T>(T);
+
+trait Step {
+    fn step(self) -> u32;
+}
+
+fn grow<T: Step>(value: T) -> u32 {
+    value.step() + 1
+}
+
+impl Step for u8 {
+    fn step(self) -> u32 {
+        grow(Wrapper(self))
+    }
+}
+
+impl Step for Wrapper<u8> {
+    fn step(self) -> u32 {
+        grow(Wrapper(self))
+    }
+}
+
+impl Step for Wrapper<Wrapper<u8>> {
+    fn step(self) -> u32 {
+        grow(Wrapper(self))
+    }
+}
+
+impl Step for Wrapper<Wrapper<Wrapper<u8>>> {
+    fn step(self) -> u32 {
+        0
+    }
+}
+
+fn main() {
+    assert_eq!(grow(0u8), 4);
+}
@@ -0,0 +1,41 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that a method instantiating itself with growing types through a trait
+// call is not mistaken for an infinite cycle, even though it is the same
+// method each time. A more specific impl ends the chain at the fourth step.
+
+struct Wrap<T>(T);
+
+trait Small {}
+
+impl Small for u8 {}
+impl Small for Wrap<u8> {}
+impl Small for Wrap<Wrap<u8>> {}
+
+trait Tr {
+    fn go() -> u32;
+}
+
+impl<T: Small> Tr for Wrap<T> where Wrap<Wrap<T>>: Tr {
+    fn go() -> u32 {
+        <Wrap<Wrap<T>> as Tr>::go() + 1
+    }
+}
+
+impl Tr for Wrap<Wrap<Wrap<Wrap<u8>>>> {
+    fn go() -> u32 {
+        0
+    }
+}
+
+fn main() {
+    assert_eq!(<Wrap<u8> as Tr>::go(), 3);
+}
@@ -0,0 +1,68 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that the arms of a `match` or `if` on an associated constant that the
+// constant does not select are left out of the translated function, and that
+// the selected one still runs.
+
+#![feature(associated_consts)]
+
+trait Kind {
+    const KIND: u8;
+    const BIG: bool = false;
+}
+
+struct A;
+struct B;
+struct C;
+
+impl Kind for A {
+    const KIND: u8 = 0;
+}
+
+impl Kind for B {
+    const KIND: u8 = 1;
+    const BIG: bool = true;
+}
+
+impl Kind for C {
+    const KIND: u8 = 7;
+}
+
+fn name<T>(x: u32) -> u32 {
+    x
+}
+
+fn kind<T: Kind>() -> u32 {
+    match T::KIND {
+        0 => name::<T>(10),
+        1 => name::<T>(11),
+        _ => name::<T>(12),
+    }
+}
+
+fn size<T: Kind>(v: &mut Vec<u32>) {
+    if T::BIG {
+        v.push(100);
+    } else {
+        v.push(1);
+    }
+}
+
+fn main() {
+    assert_eq!(kind::<A>(), 10);
+    assert_eq!(kind::<B>(), 11);
+    assert_eq!(kind::<C>(), 12);
+
+    let mut v = Vec::new();
+    size::<A>(&mut v);
+    size::<B>(&mut v);
+    assert_eq!(v, [1, 100]);
+}
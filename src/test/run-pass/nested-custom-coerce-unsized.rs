@@ -0,0 +1,68 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Unsizing coercions through more than one user-defined smart pointer, and
+// through a smart pointer to a struct that is unsized itself.
+
+#![feature(coerce_unsized)]
+#![feature(unsize)]
+
+use std::marker::Unsize;
+use std::ops::CoerceUnsized;
+
+trait Value {
+    fn get(&self) -> u32;
+}
+
+impl Value for u8 {
+    fn get(&self) -> u32 { *self as u32 }
+}
+
+impl Value for u16 {
+    fn get(&self) -> u32 { *self as u32 * 10 }
+}
+
+struct MyBox<T: ?Sized>(*const T);
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<MyBox<U>> for MyBox<T> {}
+
+struct Handle<T: ?Sized> {
+    id: u32,
+    ptr: MyBox<T>,
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Handle<U>> for Handle<T> {}
+
+struct MyCell<T: ?Sized> {
+    extra: u32,
+    value: T,
+}
+
+fn through_handle(h: Handle<Value>) -> u32 {
+    h.id + unsafe { (*h.ptr.0).get() }
+}
+
+fn through_cell(b: MyBox<MyCell<Value>>) -> u32 {
+    unsafe { (*b.0).extra + (*b.0).value.get() }
+}
+
+fn through_both(h: Handle<MyCell<Value>>) -> u32 {
+    h.id + unsafe { (*h.ptr.0).extra + (*h.ptr.0).value.get() }
+}
+
+fn main() {
+    let x = 1u8;
+    assert_eq!(through_handle(Handle { id: 100, ptr: MyBox(&x) }), 101);
+
+    let cell = MyCell { extra: 1000, value: 2u16 };
+    assert_eq!(through_cell(MyBox(&cell)), 1020);
+
+    let cell = MyCell { extra: 3000, value: 4u8 };
+    assert_eq!(through_both(Handle { id: 5, ptr: MyBox(&cell) }), 3009);
+}
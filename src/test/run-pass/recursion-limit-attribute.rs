@@ -0,0 +1,65 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that `#[recursion_limit]` on a function raises the limit for
+// instantiating that function only. `value_of` is instantiated 20 levels
+// deep, which exceeds the crate-wide limit.
+
+#![recursion_limit="16"]
+
+trait Nat {
+    fn value() -> usize;
+}
+
+struct N0;
+
+impl Nat for N0 {
+    fn value() -> usize { 0 }
+}
+
+macro_rules! nat {
+    ($prev:ident, $cur:ident) => {
+        struct $cur;
+
+        impl Nat for $cur {
+            fn value() -> usize { value_of::<$prev>() + 1 }
+        }
+    }
+}
+
+nat!(N0, N1);
+nat!(N1, N2);
+nat!(N2, N3);
+nat!(N3, N4);
+nat!(N4, N5);
+nat!(N5, N6);
+nat!(N6, N7);
+nat!(N7, N8);
+nat!(N8, N9);
+nat!(N9, N10);
+nat!(N10, N11);
+nat!(N11, N12);
+nat!(N12, N13);
+nat!(N13, N14);
+nat!(N14, N15);
+nat!(N15, N16);
+nat!(N16, N17);
+nat!(N17, N18);
+nat!(N18, N19);
+nat!(N19, N20);
+
+#[recursion_limit="32"]
+fn value_of<T: Nat>() -> usize {
+    T::value()
+}
+
+fn main() {
+    assert_eq!(value_of::<N20>(), 20);
+}
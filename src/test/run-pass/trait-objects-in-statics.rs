@@ -0,0 +1,45 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:static_trait_objects.rs
+
+// Check that trait objects created in the initializers of statics and
+// constants, of this crate or another one, can have their methods called,
+// even if nothing else refers to these methods.
+
+extern crate static_trait_objects;
+
+use static_trait_objects::{Log, ADDER, DOUBLER};
+
+struct Negator;
+
+impl Log for Negator {
+    fn log(&self, value: u32) -> u32 {
+        !value
+    }
+}
+
+struct Multiplier<T>(T);
+
+impl Log for Multiplier<u32> {
+    fn log(&self, value: u32) -> u32 {
+        value * self.0
+    }
+}
+
+static NEGATOR: &'static Log = &Negator;
+const TRIPLER: &'static Log = &Multiplier(3u32);
+
+fn main() {
+    assert_eq!(DOUBLER.log(2), 4);
+    assert_eq!(ADDER.log(2), 5);
+    assert_eq!(NEGATOR.log(0), !0);
+    assert_eq!(TRIPLER.log(2), 6);
+}
@@ -0,0 +1,39 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+// Check that values passed by value to methods called through trait objects
+// are dropped, whether the method returns or panics.
+
+use std::panic;
+
+trait Sink {
+    fn take(&self, text: String, fail: bool) -> usize;
+}
+
+struct Measure;
+
+impl Sink for Measure {
+    fn take(&self, text: String, fail: bool) -> usize {
+        if fail {
+            panic!("failing with {}", text);
+        }
+        text.len()
+    }
+}
+
+fn main() {
+    let sink: &Sink = &Measure;
+    assert_eq!(sink.take(String::from("text"), false), 4);
+
+    let result = panic::catch_unwind(|| {
+        let sink: &Sink = &Measure;
+        sink.take(String::from("text"), true)
+    });
+    assert!(result.is_err());
+}
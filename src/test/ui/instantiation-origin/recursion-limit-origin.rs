@@ -0,0 +1,20 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:runaway_lib.rs
+
+// Test that the instantiation cycle error names the crate defining the
+// runaway function and the local item that caused its instantiation.
+
+#![recursion_limit="8"]
+
+extern crate runaway_lib;
+
+fn main() { runaway_lib::nest(3, ()); }
@@ -0,0 +1,23 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Test that the type-length limit error shows the full path of the function
+// and the outermost constructors of its type arguments, eliding the deeply
+// nested parts of the arguments instead of cutting the name off, and points
+// at the call that instantiates it.
+
+#![allow(dead_code)]
+#![type_length_limit="8"]
+
+struct W<T>(T);
+
+fn pair<A, B>() {}
+
+fn main() { pair::<W<W<W<W<W<W<W<W<W<W<()>>>>>>>>>>, u32>(); }
@@ -0,0 +1,49 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(drop_types_in_const)]
+#![allow(dead_code)]
+#![deny(static_destructors)]
+
+// Test that statics whose type has a destructor are linted against, naming the
+// component of the type that has the destructor, unless the lint is allowed on
+// the static itself. The path to the component stops where a recursive type
+// refers back to itself.
+
+struct Droppy;
+
+impl Drop for Droppy {
+    fn drop(&mut self) {}
+}
+
+struct Wrapper<T> {
+    value: T,
+}
+
+trait Trait: Sync {}
+
+static DIRECT: Droppy = Droppy;
+
+static NESTED: Option<Wrapper<Droppy>> = Some(Wrapper { value: Droppy });
+
+static OBJECT: Option<Box<Trait>> = None;
+
+static NO_DROP: Option<u32> = None;
+
+struct Node {
+    next: Option<Box<Node>>,
+}
+
+static HEAD: Option<Box<Node>> = None;
+
+#[allow(static_destructors)]
+static ALLOWED: Droppy = Droppy;
+
+fn main() {}
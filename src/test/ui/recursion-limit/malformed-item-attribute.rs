@@ -0,0 +1,29 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that a malformed `#[recursion_limit]` on an impl is reported once,
+// not once for each of its methods.
+
+struct Foo;
+
+#[recursion_limit="many"]
+impl Foo {
+    fn first<T>(_: T) {}
+    fn second<T>(_: T) {}
+}
+
+#[recursion_limit]
+fn generic<T>(_: T) {}
+
+fn main() {
+    Foo::first(0u8);
+    Foo::second(0u8);
+    generic(0u8);
+}
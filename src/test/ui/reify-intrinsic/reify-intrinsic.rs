@@ -0,0 +1,20 @@
+// Copyright 2017 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(core_intrinsics, intrinsics)]
+
+use std::intrinsics;
+
+fn main() {
+    let _size_of: unsafe extern "rust-intrinsic" fn() -> usize = intrinsics::size_of::<u32>;
+
+    // Calling the intrinsic directly is fine.
+    let _ = unsafe { intrinsics::size_of::<u64>() };
+}
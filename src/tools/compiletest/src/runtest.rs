@@ -2045,21 +2045,30 @@ actual:\n\
 
         const PREFIX: &'static str = "TRANS_ITEM ";
         const CGU_MARKER: &'static str = "@@";
+        const REASON_MARKER: &'static str = "##";
+
+        const PROMOTED_PREFIX: &'static str = "promoted";
+
+        let expected: Vec<TransItem> = errors::load_errors(&self.testpaths.file, None)
+            .iter()
+            .map(|e| str_to_trans_item(&e.msg[..]))
+            .collect();
+
+        // Like notes in compile-fail tests, promoted values are only checked
+        // if the test expects any of them.
+        let check_promoted = expected.iter().any(|ti| ti.name.starts_with(PROMOTED_PREFIX));
 
         let actual: Vec<TransItem> = proc_res
             .stdout
             .lines()
             .filter(|line| line.starts_with(PREFIX))
             .map(str_to_trans_item)
-            .collect();
-
-        let expected: Vec<TransItem> = errors::load_errors(&self.testpaths.file, None)
-            .iter()
-            .map(|e| str_to_trans_item(&e.msg[..]))
+            .filter(|ti| check_promoted || !ti.name.starts_with(PROMOTED_PREFIX))
             .collect();
 
         let mut missing = Vec::new();
         let mut wrong_cgus = Vec::new();
+        let mut wrong_reasons = Vec::new();
 
         for expected_item in &expected {
             let actual_item_with_same_name = actual.iter()
@@ -2072,6 +2081,12 @@ actual:\n\
                         wrong_cgus.push((expected_item.clone(), actual_item.clone()));
                     }
                 }
+                if expected_item.reason.is_some() {
+                    // Also check the reason the item exists for
+                    if expected_item.reason != actual_item.reason {
+                        wrong_reasons.push((expected_item.clone(), actual_item.clone()));
+                    }
+                }
             } else {
                 missing.push(expected_item.string.clone());
             }
@@ -2123,7 +2138,20 @@ actual:\n\
             }
         }
 
-        if !(missing.is_empty() && unexpected.is_empty() && wrong_cgus.is_empty())
+        if !wrong_reasons.is_empty() {
+            wrong_reasons.sort_by_key(|pair| pair.0.name.clone());
+            println!("\nThe following items were given wrong reasons:\n");
+
+            for &(ref expected_item, ref actual_item) in &wrong_reasons {
+                println!("{}", expected_item.name);
+                println!("  expected: {}", expected_item.reason.as_ref().unwrap());
+                println!("  actual:   {}", actual_item.reason.as_ref().map_or("none", |r| &r[..]));
+                println!("");
+            }
+        }
+
+        if !(missing.is_empty() && unexpected.is_empty() && wrong_cgus.is_empty() &&
+             wrong_reasons.is_empty())
         {
             panic!();
         }
@@ -2132,10 +2160,11 @@ actual:\n\
         struct TransItem {
             name: String,
             codegen_units: HashSet<String>,
+            reason: Option<String>,
             string: String,
         }
 
-        // [TRANS_ITEM] name [@@ (cgu)+]
+        // [TRANS_ITEM] name [@@ (cgu)+] [## reason]
         fn str_to_trans_item(s: &str) -> TransItem {
             let s = if s.starts_with(PREFIX) {
                 (&s[PREFIX.len()..]).trim()
@@ -2145,6 +2174,14 @@ actual:\n\
 
             let full_string = format!("{}{}", PREFIX, s.trim().to_owned());
 
+            let (s, reason) = match s.find(REASON_MARKER) {
+                Some(index) => {
+                    let reason = s[index + REASON_MARKER.len()..].trim().to_owned();
+                    (&s[..index], Some(reason))
+                }
+                None => (s, None)
+            };
+
             let parts: Vec<&str> = s.split(CGU_MARKER)
                                     .map(str::trim)
                                     .filter(|s| !s.is_empty())
@@ -2168,6 +2205,7 @@ actual:\n\
             TransItem {
                 name: name.to_owned(),
                 codegen_units: cgus,
+                reason: reason,
                 string: full_string,
             }
         }